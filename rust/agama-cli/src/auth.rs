@@ -21,6 +21,7 @@
 use agama_lib::{auth::AuthToken, error::ServiceError};
 use clap::Subcommand;
 
+use crate::context::Connection;
 use crate::error::CliError;
 use agama_lib::base_http_client::BaseHTTPClient;
 use inquire::Password;
@@ -60,8 +61,9 @@ pub enum AuthCommands {
     /// Authenticate with Agama's server and store the token.
     ///
     /// This command tries to get the password from the standard input. If it is not there, it asks
-    /// the user interactively. Upon successful login, it stores the token in .agama/agama-jwt. The
-    /// token will be automatically sent to authenticate the following requests.
+    /// the user interactively. Upon successful login, it stores the token in .local/agama/token
+    /// (or in the directory of the context, if any). The token will be automatically sent to
+    /// authenticate the following requests.
     Login,
     /// Deauthenticate by removing the token.
     Logout,
@@ -70,13 +72,17 @@ pub enum AuthCommands {
 }
 
 /// Main entry point called from agama CLI main loop
-pub async fn run(client: BaseHTTPClient, subcommand: AuthCommands) -> anyhow::Result<()> {
+pub async fn run(
+    client: BaseHTTPClient,
+    connection: &Connection,
+    subcommand: AuthCommands,
+) -> anyhow::Result<()> {
     let auth_client = AuthHTTPClient::load(client)?;
 
     match subcommand {
-        AuthCommands::Login => login(auth_client, read_password()?).await,
-        AuthCommands::Logout => logout(),
-        AuthCommands::Show => show(),
+        AuthCommands::Login => login(auth_client, connection, read_password()?).await,
+        AuthCommands::Logout => logout(connection),
+        AuthCommands::Show => show(connection),
     }
 }
 
@@ -108,23 +114,27 @@ fn ask_password() -> Result<String, CliError> {
 }
 
 /// Logs into the installation web server and stores JWT for later use.
-async fn login(client: AuthHTTPClient, password: String) -> anyhow::Result<()> {
+async fn login(
+    client: AuthHTTPClient,
+    connection: &Connection,
+    password: String,
+) -> anyhow::Result<()> {
     // 1) ask web server for JWT
     let res = client.authenticate(password).await?;
     let token = AuthToken::new(&res);
-    Ok(token.write_user_token()?)
+    Ok(connection.write_token(&token)?)
 }
 
 /// Releases JWT
-fn logout() -> anyhow::Result<()> {
-    Ok(AuthToken::remove_user_token()?)
+fn logout(connection: &Connection) -> anyhow::Result<()> {
+    Ok(connection.remove_token()?)
 }
 
 /// Shows stored JWT on stdout
-fn show() -> anyhow::Result<()> {
+fn show(connection: &Connection) -> anyhow::Result<()> {
     // we do not care if jwt() fails or not. If there is something to print, show it otherwise
     // stay silent
-    if let Some(token) = AuthToken::find() {
+    if let Some(token) = connection.find_token() {
         println!("{}", token.as_str());
     }
 
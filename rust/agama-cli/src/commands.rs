@@ -20,7 +20,9 @@
 
 use crate::auth::AuthCommands;
 use crate::config::ConfigCommands;
+use crate::context::ContextCommands;
 use crate::logs::LogsCommands;
+use crate::network::NetworkCommands;
 use crate::profile::ProfileCommands;
 use crate::questions::QuestionsCommands;
 use clap::Subcommand;
@@ -78,6 +80,13 @@ pub enum Commands {
     #[command(subcommand)]
     Logs(LogsCommands),
 
+    /// Inspect the network configuration.
+    ///
+    /// The "export" subcommand writes the network connections to the standard output, either in
+    /// the format of the "network" section of the profile or as an nmstate document.
+    #[command(subcommand)]
+    Network(NetworkCommands),
+
     /// Authenticate with Agama's server.
     ///
     /// Unless you are executing this program as root, you need to authenticate with Agama's server
@@ -94,6 +103,17 @@ pub enum Commands {
     #[command(subcommand)]
     Auth(AuthCommands),
 
+    /// Manage the connection contexts.
+    ///
+    /// A context is a named set of connection settings (API URL and whether to accept invalid
+    /// certificates). Each context has its own authentication token, so you can work with several
+    /// installers at the same time without logging in again.
+    ///
+    /// Use the global --context option to select a context for a single command or the "context
+    /// use" command to change the current one. The --api option takes precedence over contexts.
+    #[command(subcommand)]
+    Context(ContextCommands),
+
     /// Download file from given URL
     ///
     /// The purpose of this command is to download files using AutoYaST supported schemas (e.g. device:// or relurl://).
@@ -0,0 +1,412 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Implements the "agama context" subcommands.
+//!
+//! A context is a named set of connection settings (API URL and whether to accept invalid
+//! certificates). The contexts are stored in `~/.local/agama/contexts.json` and each of them has
+//! its own authentication token (`~/.local/agama/contexts/<name>/token`), so logging into one
+//! installer does not overwrite the token of another one.
+
+use agama_lib::{auth::AuthToken, certificate::KnownCertificates};
+use anyhow::anyhow;
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufReader, Write},
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+};
+
+const USER_CONTEXTS_PATH: &str = ".local/agama/contexts.json";
+const USER_CONTEXTS_DIR: &str = ".local/agama/contexts";
+const DEFAULT_API_URL: &str = "http://localhost/api";
+
+#[derive(Subcommand, Debug)]
+pub enum ContextCommands {
+    /// Add a new context or update an existing one.
+    ///
+    /// If there is no current context yet, the new one becomes the current one.
+    Add {
+        /// Context name
+        name: String,
+        /// URI pointing to Agama's remote API (e.g., https://agama.example.net/api)
+        #[arg(long)]
+        api: String,
+        /// Whether to accept invalid (self-signed, ...) certificates or not
+        #[arg(long, default_value = "false")]
+        insecure: bool,
+    },
+    /// Set the current context.
+    ///
+    /// The current context is used when the --context option is not given.
+    Use {
+        /// Context name
+        name: String,
+    },
+    /// List the contexts, marking the current one with an asterisk.
+    List,
+    /// Remove a context, including its authentication token.
+    Remove {
+        /// Context name
+        name: String,
+    },
+}
+
+/// Connection settings for an Agama server.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Context {
+    /// URI pointing to Agama's API.
+    pub api: String,
+    /// Whether to accept invalid certificates.
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+/// List of contexts and the name of the current one.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Contexts {
+    #[serde(skip)]
+    path: PathBuf,
+    /// Name of the current context.
+    current: Option<String>,
+    /// Contexts indexed by name.
+    contexts: BTreeMap<String, Context>,
+}
+
+impl Contexts {
+    /// Reads the contexts of the current user.
+    ///
+    /// It returns an empty list if the file does not exist yet.
+    pub fn read_user_contexts() -> io::Result<Self> {
+        Self::read(user_path(USER_CONTEXTS_PATH)?)
+    }
+
+    /// Reads the contexts from the given path.
+    ///
+    /// It returns an empty list if the file does not exist yet.
+    ///
+    /// * `path`: file's path to read the contexts from.
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                ..Default::default()
+            });
+        }
+
+        let reader = BufReader::new(File::open(&path)?);
+        let contexts: Self = serde_json::from_reader(reader)?;
+        Ok(Self { path, ..contexts })
+    }
+
+    /// Writes the contexts to the file they were read from.
+    pub fn write(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .mode(0o600)
+            .open(&self.path)?;
+        file.write_all(serde_json::to_string_pretty(&self)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Returns the context with the given name.
+    ///
+    /// * `name`: context name.
+    pub fn get(&self, name: &str) -> Option<&Context> {
+        self.contexts.get(name)
+    }
+
+    /// Returns the name of the current context, if any.
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Adds or replaces a context.
+    ///
+    /// The context becomes the current one if there is no current context.
+    ///
+    /// * `name`: context name.
+    /// * `context`: connection settings.
+    pub fn insert(&mut self, name: &str, context: Context) {
+        self.contexts.insert(name.to_string(), context);
+        if self.current.is_none() {
+            self.current = Some(name.to_string());
+        }
+    }
+
+    /// Sets the current context.
+    ///
+    /// * `name`: context name.
+    pub fn set_current(&mut self, name: &str) -> anyhow::Result<()> {
+        if !self.contexts.contains_key(name) {
+            return Err(anyhow!("Unknown context '{}'", name));
+        }
+        self.current = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Removes a context, unsetting the current one if needed.
+    ///
+    /// * `name`: context name.
+    pub fn remove(&mut self, name: &str) -> anyhow::Result<()> {
+        if self.contexts.remove(name).is_none() {
+            return Err(anyhow!("Unknown context '{}'", name));
+        }
+        if self.current() == Some(name) {
+            self.current = None;
+        }
+        Ok(())
+    }
+}
+
+/// Connection settings resolved from the command-line options and the contexts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Connection {
+    /// Context name, if any.
+    pub context: Option<String>,
+    /// URI pointing to Agama's API.
+    pub api: String,
+    /// Whether to accept invalid certificates.
+    pub insecure: bool,
+}
+
+impl Connection {
+    /// Resolves the connection settings.
+    ///
+    /// An explicit API URL takes precedence over the contexts. Otherwise, it uses the given
+    /// context, the current one or, as a last resort, the local API.
+    ///
+    /// * `contexts`: known contexts.
+    /// * `name`: context name given by the user.
+    /// * `api`: API URL given by the user.
+    /// * `insecure`: whether the user asked to accept invalid certificates.
+    pub fn resolve(
+        contexts: &Contexts,
+        name: Option<&str>,
+        api: Option<&str>,
+        insecure: bool,
+    ) -> anyhow::Result<Self> {
+        if let Some(api) = api {
+            return Ok(Self {
+                context: None,
+                api: api.trim_end_matches('/').to_string(),
+                insecure,
+            });
+        }
+
+        let Some(name) = name.or(contexts.current()) else {
+            return Ok(Self {
+                context: None,
+                api: DEFAULT_API_URL.to_string(),
+                insecure,
+            });
+        };
+
+        let context = contexts
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown context '{}'", name))?;
+        Ok(Self {
+            context: Some(name.to_string()),
+            api: context.api.trim_end_matches('/').to_string(),
+            insecure: insecure || context.insecure,
+        })
+    }
+
+    /// Returns the token to authenticate with the server.
+    ///
+    /// When using a context, it reads the context token. Otherwise, it searches for the user
+    /// token or the master token (see [AuthToken::find]).
+    pub fn find_token(&self) -> Option<AuthToken> {
+        match &self.context {
+            Some(name) => AuthToken::read(context_token_path(name).ok()?).ok(),
+            None => AuthToken::find(),
+        }
+    }
+
+    /// Stores the token to authenticate with the server.
+    ///
+    /// * `token`: token to store.
+    pub fn write_token(&self, token: &AuthToken) -> io::Result<()> {
+        match &self.context {
+            Some(name) => token.write(context_token_path(name)?),
+            None => token.write_user_token(),
+        }
+    }
+
+    /// Removes the token to authenticate with the server.
+    pub fn remove_token(&self) -> io::Result<()> {
+        match &self.context {
+            Some(name) => remove_context_token(name),
+            None => AuthToken::remove_user_token(),
+        }
+    }
+}
+
+/// Main entry point called from agama CLI main loop
+pub fn run(subcommand: ContextCommands) -> anyhow::Result<()> {
+    let mut contexts = Contexts::read_user_contexts()?;
+
+    match subcommand {
+        ContextCommands::Add {
+            name,
+            api,
+            insecure,
+        } => {
+            contexts.insert(&name, Context { api, insecure });
+            Ok(contexts.write()?)
+        }
+        ContextCommands::Use { name } => {
+            contexts.set_current(&name)?;
+            Ok(contexts.write()?)
+        }
+        ContextCommands::List => list(&contexts),
+        ContextCommands::Remove { name } => {
+            contexts.remove(&name)?;
+            contexts.write()?;
+            Ok(remove_context_token(&name)?)
+        }
+    }
+}
+
+/// Prints the contexts, including the fingerprint of the accepted certificate
+fn list(contexts: &Contexts) -> anyhow::Result<()> {
+    let certificates = KnownCertificates::read_user_certificates()?;
+
+    for (name, context) in &contexts.contexts {
+        let mark = if contexts.current() == Some(name) {
+            "*"
+        } else {
+            " "
+        };
+        let api = context.api.trim_end_matches('/');
+        let fingerprint = if context.insecure {
+            "(insecure)"
+        } else {
+            certificates
+                .get(api)
+                .map(|c| c.fingerprint.as_str())
+                .unwrap_or("-")
+        };
+        println!("{} {}\t{}\t{}", mark, name, api, fingerprint);
+    }
+
+    Ok(())
+}
+
+fn context_token_path(name: &str) -> io::Result<PathBuf> {
+    Ok(user_path(USER_CONTEXTS_DIR)?.join(name).join("token"))
+}
+
+fn remove_context_token(name: &str) -> io::Result<()> {
+    let path = context_token_path(name)?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+fn user_path(relative: &str) -> io::Result<PathBuf> {
+    let Some(path) = home::home_dir() else {
+        return Err(io::Error::other("Cannot find the user's home directory"));
+    };
+
+    Ok(path.join(relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Connection, Context, Contexts};
+    use tempfile::tempdir;
+
+    fn contexts() -> Contexts {
+        let mut contexts = Contexts::default();
+        contexts.insert(
+            "lab1",
+            Context {
+                api: "https://lab1.example.net/api/".to_string(),
+                insecure: false,
+            },
+        );
+        contexts.insert(
+            "lab2",
+            Context {
+                api: "https://lab2.example.net/api".to_string(),
+                insecure: true,
+            },
+        );
+        contexts
+    }
+
+    #[test]
+    fn test_write_and_read_contexts() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("contexts.json");
+
+        let mut contexts = Contexts::read(&path).unwrap();
+        contexts.insert(
+            "lab1",
+            Context {
+                api: "https://lab1.example.net/api".to_string(),
+                insecure: false,
+            },
+        );
+        contexts.write().unwrap();
+
+        let contexts = Contexts::read(&path).unwrap();
+        assert_eq!(contexts.current(), Some("lab1"));
+        assert!(contexts.get("lab1").is_some());
+    }
+
+    #[test]
+    fn test_resolve_connection() {
+        let mut contexts = contexts();
+
+        let connection = Connection::resolve(&contexts, None, None, false).unwrap();
+        assert_eq!(connection.context.as_deref(), Some("lab1"));
+        assert_eq!(connection.api, "https://lab1.example.net/api");
+        assert!(!connection.insecure);
+
+        let connection = Connection::resolve(&contexts, Some("lab2"), None, false).unwrap();
+        assert_eq!(connection.context.as_deref(), Some("lab2"));
+        assert!(connection.insecure);
+
+        let connection =
+            Connection::resolve(&contexts, Some("lab2"), Some("http://localhost/api"), false)
+                .unwrap();
+        assert_eq!(connection.context, None);
+        assert_eq!(connection.api, "http://localhost/api");
+
+        assert!(Connection::resolve(&contexts, Some("unknown"), None, false).is_err());
+
+        contexts.remove("lab1").unwrap();
+        let connection = Connection::resolve(&contexts, None, None, false).unwrap();
+        assert_eq!(connection.context, None);
+        assert_eq!(connection.api, "http://localhost/api");
+    }
+}
@@ -23,22 +23,27 @@ use clap::{Args, Parser};
 mod auth;
 mod commands;
 mod config;
+mod context;
 mod error;
 mod logs;
+mod network;
 mod profile;
 mod progress;
 mod questions;
 
 use crate::error::CliError;
 use agama_lib::base_http_client::BaseHTTPClient;
+use agama_lib::certificate::{KnownCertificates, PinnedCertificate};
 use agama_lib::{
     error::ServiceError, manager::ManagerClient, progress::ProgressMonitor, utils::Transfer,
 };
 use auth::run as run_auth_cmd;
 use commands::Commands;
 use config::run as run_config_cmd;
+use context::{run as run_context_cmd, Connection, Contexts};
 use inquire::Confirm;
 use logs::run as run_logs_cmd;
+use network::run as run_network_cmd;
 use profile::run as run_profile_cmd;
 use progress::InstallerProgress;
 use questions::run as run_questions_cmd;
@@ -52,13 +57,20 @@ use std::{
 /// Agama's CLI global options
 #[derive(Args)]
 pub struct GlobalOpts {
-    #[clap(long, default_value = "http://localhost/api")]
-    /// URI pointing to Agama's remote API. If not provided, default https://localhost/api is
-    /// used
-    pub api: String,
+    #[clap(long)]
+    /// URI pointing to Agama's remote API. If not provided, the one from the selected context is
+    /// used, falling back to http://localhost/api
+    pub api: Option<String>,
+
+    #[clap(long)]
+    /// Name of the context to use (see the "context" command). If not provided, the current
+    /// context is used
+    pub context: Option<String>,
 
     #[clap(long, default_value = "false")]
-    /// Whether to accept invalid (self-signed, ...) certificates or not
+    /// Whether to accept invalid (self-signed, ...) certificates or not. Without this option,
+    /// the user is asked to accept a certificate that cannot be verified, and the accepted
+    /// certificate is the only one trusted for the given API URL from then on
     pub insecure: bool,
 }
 
@@ -157,38 +169,87 @@ async fn build_manager<'a>() -> anyhow::Result<ManagerClient<'a>> {
     Ok(ManagerClient::new(conn).await?)
 }
 
-/// True if use of the remote API is allowed (yes by default when the API is secure, the user is
-/// asked if the API is insecure - e.g. when it uses self-signed certificate)
-async fn allowed_insecure_api(use_insecure: bool, api_url: String) -> Result<bool, ServiceError> {
+/// Configures how the client verifies the server certificate
+///
+/// If the certificate cannot be verified (e.g. it is self-signed), the user is asked to accept
+/// it. The accepted certificate is stored and it is the only one trusted for the given API URL
+/// from then on (trust-on-first-use). If the server presents a different certificate later, it
+/// fails instead of asking again.
+///
+/// * `client`: client to configure.
+/// * `insecure`: whether to accept any certificate.
+async fn trusted_client(
+    client: BaseHTTPClient,
+    insecure: bool,
+) -> Result<BaseHTTPClient, ServiceError> {
+    if insecure {
+        return Ok(client.insecure());
+    }
+
     // fake client used for remote site detection
-    let mut ping_client = BaseHTTPClient::default();
-    ping_client.base_url = api_url;
-
-    // decide whether access to remote site has to be insecure (self-signed certificate or not)
-    match ping_client.get::<HashMap<String, String>>("/ping").await {
-        // Problem with http remote API reachability
-        Err(ServiceError::HTTPError(_)) => Ok(use_insecure || Confirm::new("There was a problem with the remote API and it is treated as insecure. Do you want to continue?")
-            .with_default(false)
-            .prompt()
-            .unwrap_or(false)),
-        // another error
-        Err(e) => Err(e),
-        // success doesn't bother us here
-        Ok(_) => Ok(false)
+    let ping_client = client.clone().unauthenticated()?;
+    let error = match ping_client.get::<HashMap<String, String>>("/ping").await {
+        // the certificate (if any) is valid
+        Ok(_) => return Ok(client),
+        Err(ServiceError::HTTPError(error)) if error.is_connect() => error,
+        Err(e) => return Err(e),
+    };
+
+    // if the server cannot be reached even without verifying the certificate, there is
+    // nothing the user can accept
+    let Ok(Some(der)) = client.peer_certificate().await else {
+        return Err(ServiceError::HTTPError(error));
+    };
+
+    let api_url = client.base_url.clone();
+    let received = PinnedCertificate::from_der(&der);
+    let mut known = KnownCertificates::read_user_certificates().map_err(anyhow::Error::new)?;
+
+    match known.get(&api_url) {
+        Some(pinned) if pinned == &received => client.pinned(&der),
+        Some(pinned) => Err(ServiceError::CertificateChanged(
+            api_url,
+            pinned.fingerprint.clone(),
+            received.fingerprint,
+            known.path().display().to_string(),
+        )),
+        None => {
+            eprintln!("The certificate of {} cannot be verified.", api_url);
+            eprintln!("SHA-256 fingerprint: {}", received.fingerprint);
+            let accepted = Confirm::new("Do you want to trust this certificate?")
+                .with_default(false)
+                .prompt()
+                .unwrap_or(false);
+            if !accepted {
+                return Err(ServiceError::UntrustedCertificate(api_url));
+            }
+
+            known.insert(&api_url, received);
+            known.write().map_err(anyhow::Error::new)?;
+            client.pinned(&der)
+        }
     }
 }
 
 pub async fn run_command(cli: Cli) -> Result<(), ServiceError> {
-    // somehow check whether we need to ask user for self-signed certificate acceptance
-    let api_url = cli.opts.api.trim_end_matches('/').to_string();
+    // contexts are handled locally, no connection to the server is needed
+    if let Commands::Context(subcommand) = cli.command {
+        return Ok(run_context_cmd(subcommand)?);
+    }
 
-    let mut client = BaseHTTPClient::default();
+    let contexts = Contexts::read_user_contexts().map_err(anyhow::Error::new)?;
+    let connection = Connection::resolve(
+        &contexts,
+        cli.opts.context.as_deref(),
+        cli.opts.api.as_deref(),
+        cli.opts.insecure,
+    )?;
 
-    client.base_url = api_url.clone();
+    let mut client = BaseHTTPClient::default();
 
-    if allowed_insecure_api(cli.opts.insecure, api_url.clone()).await? {
-        client = client.insecure();
-    }
+    client.base_url = connection.api.clone();
+    // somehow check whether we need to ask user for self-signed certificate acceptance
+    client = trusted_client(client, connection.insecure).await?;
 
     // we need to distinguish commands on those which assume that authentication JWT is already
     // available and those which not (or don't need it)
@@ -196,7 +257,10 @@ pub async fn run_command(cli: Cli) -> Result<(), ServiceError> {
         client.unauthenticated()?
     } else {
         // this deals with authentication need inside
-        client.authenticated()?
+        let token = connection
+            .find_token()
+            .ok_or(ServiceError::NotAuthenticated)?;
+        client.authenticated_with(&token)?
     };
 
     match cli.command {
@@ -213,10 +277,12 @@ pub async fn run_command(cli: Cli) -> Result<(), ServiceError> {
         }
         Commands::Questions(subcommand) => run_questions_cmd(client, subcommand).await?,
         Commands::Logs(subcommand) => run_logs_cmd(client, subcommand).await?,
+        Commands::Network(subcommand) => run_network_cmd(client, subcommand).await?,
         Commands::Download { url } => Transfer::get(&url, std::io::stdout())?,
         Commands::Auth(subcommand) => {
-            run_auth_cmd(client, subcommand).await?;
+            run_auth_cmd(client, &connection, subcommand).await?;
         }
+        Commands::Context(_) => unreachable!("contexts are handled before connecting"),
     };
 
     Ok(())
@@ -0,0 +1,65 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+use agama_lib::base_http_client::BaseHTTPClient;
+use agama_lib::network::{NetworkClient, NetworkSettings};
+use clap::{Subcommand, ValueEnum};
+
+// definition of "agama network" subcommands, see clap crate for details
+#[derive(Subcommand, Debug)]
+pub enum NetworkCommands {
+    /// Print the network configuration.
+    Export {
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// JSON, as in the "network" section of the profile.
+    Json,
+    /// nmstate YAML document.
+    Nmstate,
+}
+
+/// Main entry point called from agama CLI main loop
+pub async fn run(client: BaseHTTPClient, subcommand: NetworkCommands) -> anyhow::Result<()> {
+    let client = NetworkClient::new(client).await?;
+
+    match subcommand {
+        NetworkCommands::Export { format } => {
+            let output = match format {
+                ExportFormat::Json => {
+                    let settings = NetworkSettings {
+                        connections: client.connections().await?,
+                        proxy: client.proxy().await?,
+                        general: client.general().await?,
+                    };
+                    serde_json::to_string_pretty(&settings)?
+                }
+                ExportFormat::Nmstate => client.nmstate().await?,
+            };
+            println!("{}", output.trim_end());
+            Ok(())
+        }
+    }
+}
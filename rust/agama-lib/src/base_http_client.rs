@@ -21,7 +21,7 @@
 use reqwest::{header, Response};
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::{auth::AuthToken, error::ServiceError};
+use crate::{auth::AuthToken, endpoints::Endpoint, error::ServiceError, network::ProxySettings};
 
 /// Base that all HTTP clients should use.
 ///
@@ -45,6 +45,7 @@ use crate::{auth::AuthToken, error::ServiceError};
 pub struct BaseHTTPClient {
     client: reqwest::Client,
     insecure: bool,
+    certificate: Option<reqwest::Certificate>,
     pub base_url: String,
 }
 
@@ -58,6 +59,7 @@ impl Default for BaseHTTPClient {
         Self {
             client: reqwest::Client::new(),
             insecure: false,
+            certificate: None,
             base_url: API_URL.to_owned(),
         }
     }
@@ -72,10 +74,37 @@ impl BaseHTTPClient {
         }
     }
 
+    /// Trusts only the given certificate (e.g. a self-signed certificate accepted by the user)
+    ///
+    /// The host name is not verified because a self-signed certificate might not include the
+    /// name used to reach the server.
+    ///
+    /// Arguments:
+    ///
+    /// * `der`: DER encoded certificate.
+    pub fn pinned(self, der: &[u8]) -> Result<Self, ServiceError> {
+        Ok(Self {
+            certificate: Some(reqwest::Certificate::from_der(der)?),
+            ..self
+        })
+    }
+
     /// Uses `localhost`, authenticates with [`AuthToken`].
     pub fn authenticated(self) -> Result<Self, ServiceError> {
+        // TODO: this error is subtly misleading, leading me to believe the SERVER said it,
+        // but in fact it is the CLIENT not finding an auth token
+        let token = AuthToken::find().ok_or(ServiceError::NotAuthenticated)?;
+        self.authenticated_with(&token)
+    }
+
+    /// Authenticates with the given [`AuthToken`].
+    ///
+    /// Arguments:
+    ///
+    /// * `token`: token to send in the requests.
+    pub fn authenticated_with(self, token: &AuthToken) -> Result<Self, ServiceError> {
         Ok(Self {
-            client: Self::authenticated_client(self.insecure)?,
+            client: self.authenticated_client(token)?,
             ..self
         })
     }
@@ -83,19 +112,47 @@ impl BaseHTTPClient {
     /// Configures itself for connection(s) without authentication token
     pub fn unauthenticated(self) -> Result<Self, ServiceError> {
         Ok(Self {
-            client: reqwest::Client::builder()
-                .danger_accept_invalid_certs(self.insecure)
-                .build()
-                .map_err(anyhow::Error::new)?,
+            client: self.client_builder()?.build().map_err(anyhow::Error::new)?,
             ..self
         })
     }
 
-    fn authenticated_client(insecure: bool) -> Result<reqwest::Client, ServiceError> {
-        // TODO: this error is subtly misleading, leading me to believe the SERVER said it,
-        // but in fact it is the CLIENT not finding an auth token
-        let token = AuthToken::find().ok_or(ServiceError::NotAuthenticated)?;
+    /// Returns the DER encoded certificate presented by the server, if any.
+    ///
+    /// The certificate is not verified and no authentication token is sent, so it is safe to
+    /// use it to find out which certificate the server uses before trusting it.
+    pub async fn peer_certificate(&self) -> Result<Option<Vec<u8>>, ServiceError> {
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .tls_info(true)
+            .build()?;
+        let response = client.get(self.url("/ping")).send().await?;
+        let certificate = response
+            .extensions()
+            .get::<reqwest::tls::TlsInfo>()
+            .and_then(|info| info.peer_certificate())
+            .map(|der| der.to_vec());
+        Ok(certificate)
+    }
+
+    /// Returns a client builder honoring the certificate and proxy settings
+    fn client_builder(&self) -> Result<reqwest::ClientBuilder, ServiceError> {
+        let mut builder = reqwest::Client::builder().danger_accept_invalid_certs(self.insecure);
+        if let Some(proxy) = ProxySettings::system() {
+            builder = proxy.configure_reqwest(builder)?;
+        }
 
+        let builder = match &self.certificate {
+            Some(certificate) => builder
+                .tls_built_in_root_certs(false)
+                .add_root_certificate(certificate.clone())
+                .danger_accept_invalid_hostnames(true),
+            None => builder,
+        };
+        Ok(builder)
+    }
+
+    fn authenticated_client(&self, token: &AuthToken) -> Result<reqwest::Client, ServiceError> {
         let mut headers = header::HeaderMap::new();
         // just use generic anyhow error here as Bearer format is constructed by us, so failures can come only from token
         let value = header::HeaderValue::from_str(format!("Bearer {}", token).as_str())
@@ -103,10 +160,7 @@ impl BaseHTTPClient {
 
         headers.insert(header::AUTHORIZATION, value);
 
-        let client = reqwest::Client::builder()
-            .danger_accept_invalid_certs(insecure)
-            .default_headers(headers)
-            .build()?;
+        let client = self.client_builder()?.default_headers(headers).build()?;
         Ok(client)
     }
 
@@ -226,6 +280,34 @@ impl BaseHTTPClient {
         self.unit_or_error(response?).await
     }
 
+    /// Calls the given endpoint, deserializing the response.
+    ///
+    /// Arguments:
+    ///
+    /// * `endpoint`: endpoint to call (see [crate::endpoints]).
+    /// * `body`: request body. It is ignored for `GET` and `DELETE` requests.
+    pub async fn call<E: Endpoint>(
+        &self,
+        endpoint: &E,
+        body: &E::Body,
+    ) -> Result<E::Response, ServiceError> {
+        let mut request = self.client.request(E::METHOD, self.url(&endpoint.path()));
+        if E::METHOD != reqwest::Method::GET && E::METHOD != reqwest::Method::DELETE {
+            request = request.json(body);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(self.build_backend_error(response).await);
+        }
+
+        let bytes = response.bytes().await?;
+        if bytes.is_empty() {
+            Ok(serde_json::from_str("null")?)
+        } else {
+            Ok(serde_json::from_slice(&bytes)?)
+        }
+    }
+
     /// Returns raw reqwest::Response. Use e.g. in case when response content is not
     /// JSON body but e.g. binary data
     pub async fn get_raw(&self, path: &str) -> Result<Response, ServiceError> {
@@ -22,6 +22,7 @@
 
 use crate::base_http_client::BaseHTTPClient;
 use crate::bootloader::model::BootloaderSettings;
+use crate::endpoints::{GetBootloaderConfig, SetBootloaderConfig};
 use crate::ServiceError;
 
 pub struct BootloaderHTTPClient {
@@ -34,10 +35,10 @@ impl BootloaderHTTPClient {
     }
 
     pub async fn get_config(&self) -> Result<BootloaderSettings, ServiceError> {
-        self.client.get("/bootloader/config").await
+        self.client.call(&GetBootloaderConfig, &()).await
     }
 
     pub async fn set_config(&self, config: &BootloaderSettings) -> Result<(), ServiceError> {
-        self.client.put_void("/bootloader/config", config).await
+        self.client.call(&SetBootloaderConfig, config).await
     }
 }
@@ -0,0 +1,196 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! This module implements an API to deal with the certificates accepted by the user.
+//!
+//! When Agama web server uses a self-signed certificate, clients cannot verify it against the
+//! system certificate authorities. Instead of disabling the verification, the user can accept
+//! the certificate the first time it connects to the server (trust-on-first-use). The accepted
+//! certificate is stored in the user's home directory (`~/.local/agama/certificates.json`) and
+//! it is the only one trusted when connecting to the same API URL later.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufReader, Write},
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const USER_CERTIFICATES_PATH: &str = ".local/agama/certificates.json";
+
+/// Returns the SHA-256 fingerprint of a DER encoded certificate.
+///
+/// The fingerprint is formatted as colon separated uppercase hexadecimal bytes, which is the
+/// format used by the web server (and by `openssl x509 -fingerprint -sha256`).
+///
+/// * `der`: DER encoded certificate.
+pub fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Certificate accepted by the user.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PinnedCertificate {
+    /// SHA-256 fingerprint.
+    pub fingerprint: String,
+    /// Base64 encoded DER certificate.
+    certificate: String,
+}
+
+impl PinnedCertificate {
+    /// Creates a pinned certificate from a DER encoded certificate.
+    ///
+    /// * `der`: DER encoded certificate.
+    pub fn from_der(der: &[u8]) -> Self {
+        Self {
+            fingerprint: fingerprint(der),
+            certificate: STANDARD.encode(der),
+        }
+    }
+
+    /// Returns the DER encoded certificate.
+    pub fn der(&self) -> io::Result<Vec<u8>> {
+        STANDARD
+            .decode(&self.certificate)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Certificates accepted by the user, indexed by the API URL.
+#[derive(Debug, Default)]
+pub struct KnownCertificates {
+    path: PathBuf,
+    certificates: HashMap<String, PinnedCertificate>,
+}
+
+impl KnownCertificates {
+    /// Reads the certificates accepted by the current user.
+    ///
+    /// It returns an empty list if the file does not exist yet.
+    pub fn read_user_certificates() -> io::Result<Self> {
+        Self::read(Self::user_certificates_path()?)
+    }
+
+    /// Reads the certificates from the given path.
+    ///
+    /// It returns an empty list if the file does not exist yet.
+    ///
+    /// * `path`: file's path to read the certificates from.
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                ..Default::default()
+            });
+        }
+
+        let reader = BufReader::new(File::open(&path)?);
+        let certificates = serde_json::from_reader(reader)?;
+        Ok(Self { path, certificates })
+    }
+
+    /// Writes the certificates to the file they were read from.
+    ///
+    /// It takes care of setting the right permissions (0600).
+    pub fn write(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .mode(0o600)
+            .open(&self.path)?;
+        file.write_all(serde_json::to_string_pretty(&self.certificates)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Returns the certificate accepted for the given API URL.
+    ///
+    /// * `api_url`: API URL.
+    pub fn get(&self, api_url: &str) -> Option<&PinnedCertificate> {
+        self.certificates.get(api_url)
+    }
+
+    /// Accepts a certificate for the given API URL, replacing the previous one.
+    ///
+    /// * `api_url`: API URL.
+    /// * `certificate`: accepted certificate.
+    pub fn insert(&mut self, api_url: &str, certificate: PinnedCertificate) {
+        self.certificates.insert(api_url.to_string(), certificate);
+    }
+
+    /// Returns the path of the file containing the certificates.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn user_certificates_path() -> io::Result<PathBuf> {
+        let Some(path) = home::home_dir() else {
+            return Err(io::Error::other("Cannot find the user's home directory"));
+        };
+
+        Ok(path.join(USER_CERTIFICATES_PATH))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::{fingerprint, KnownCertificates, PinnedCertificate};
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(
+            fingerprint(b"agama"),
+            "52:58:9F:AC:98:63:0C:60:3B:D5:C2:B0:8C:B0:F6:CC:F2:73:CC:4A:47:72:F0:FF:28:D4:9A:01:BC:7D:2F:4B"
+        );
+    }
+
+    #[test]
+    fn test_write_and_read_certificates() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("certificates.json");
+
+        let mut known = KnownCertificates::read(&path).unwrap();
+        assert!(known.get("https://agama.local/api").is_none());
+
+        let certificate = PinnedCertificate::from_der(b"agama");
+        known.insert("https://agama.local/api", certificate.clone());
+        known.write().unwrap();
+
+        let known = KnownCertificates::read(&path).unwrap();
+        let pinned = known.get("https://agama.local/api").unwrap();
+        assert_eq!(pinned, &certificate);
+        assert_eq!(pinned.der().unwrap(), b"agama");
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Typed description of the HTTP API endpoints used by the HTTP clients.
+//!
+//! Each endpoint defines its method, its path (using the same `{param}` syntax than the OpenAPI
+//! specification, relative to `/api`), the type of the request body and the type of the response.
+//! [BaseHTTPClient::call](crate::base_http_client::BaseHTTPClient::call) relies on them, so
+//! sending the wrong body, forgetting a path parameter or expecting the wrong response are
+//! compile errors.
+//!
+//! ```no_run
+//!   use agama_lib::base_http_client::BaseHTTPClient;
+//!   use agama_lib::endpoints::GetQuestionAnswer;
+//!   use agama_lib::error::ServiceError;
+//!   use agama_lib::questions::model::Answer;
+//!
+//!   async fn get_answer(id: u32) -> Result<Option<Answer>, ServiceError> {
+//!     let client = BaseHTTPClient::default();
+//!     client.call(&GetQuestionAnswer { id }, &()).await
+//!   }
+//! ```
+//!
+//! The [ENDPOINTS] list is checked against the OpenAPI specification generated by the server, so
+//! the clients cannot drift away from the documented API.
+
+use crate::{
+    bootloader::model::BootloaderSettings,
+    localization::model::LocaleConfig,
+    logs::LogsLists,
+    manager::InstallerStatus,
+    network::{
+        settings::{GeneralSettings, NetworkConnection, ProxySettings},
+        types::Device,
+    },
+    questions::model::{Answer, Question},
+    scripts::{Script, ScriptsGroup},
+    software::model::{RegistrationInfo, RegistrationParams, ResolvableParams, SoftwareConfig},
+    storage::StorageSettings,
+    users::{
+        model::{RootConfig, RootPatchSettings},
+        FirstUser,
+    },
+};
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// HTTP API endpoint.
+pub trait Endpoint {
+    /// HTTP method.
+    const METHOD: Method;
+    /// Path template relative to `/api` (e.g., `/questions/{id}`).
+    const PATH: &'static str;
+    /// Type of the request body. It is not sent for `GET` and `DELETE` requests.
+    type Body: Serialize + ?Sized;
+    /// Type of the response body. An empty response is handled as `null`.
+    type Response: DeserializeOwned;
+
+    /// Path to request, replacing the parameters in [Self::PATH].
+    fn path(&self) -> String {
+        Self::PATH.to_string()
+    }
+}
+
+macro_rules! endpoint_struct {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Default)]
+        pub struct $name;
+    };
+    ($(#[$meta:meta])* $name:ident $($param:ident: $param_ty:ty),+) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            $(pub $param: $param_ty),+
+        }
+    };
+}
+
+macro_rules! endpoints {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident $({ $($param:ident: $param_ty:ty),+ })? =
+            $method:ident $path:literal, $body:ty => $response:ty;
+    )*) => {
+        $(
+            endpoint_struct!($(#[$meta])* $name $($($param: $param_ty),+)?);
+
+            impl Endpoint for $name {
+                const METHOD: Method = Method::$method;
+                const PATH: &'static str = $path;
+                type Body = $body;
+                type Response = $response;
+
+                #[allow(unused_mut)]
+                fn path(&self) -> String {
+                    let mut path = Self::PATH.to_string();
+                    $($(
+                        path = path.replace(
+                            concat!("{", stringify!($param), "}"),
+                            &self.$param.to_string(),
+                        );
+                    )+)?
+                    path
+                }
+            }
+        )*
+
+        /// Method and path of all the known endpoints.
+        pub const ENDPOINTS: &[(&str, &str)] = &[$((stringify!($method), $path)),*];
+    };
+}
+
+endpoints! {
+    /// Returns the bootloader configuration.
+    GetBootloaderConfig = GET "/bootloader/config", () => BootloaderSettings;
+    /// Sets the bootloader configuration.
+    SetBootloaderConfig = PUT "/bootloader/config", BootloaderSettings => ();
+
+    /// Returns the localization configuration.
+    GetL10nConfig = GET "/l10n/config", () => LocaleConfig;
+    /// Updates the localization configuration.
+    PatchL10nConfig = PATCH "/l10n/config", LocaleConfig => ();
+
+    /// Returns the installer status.
+    GetInstallerStatus = GET "/manager/installer", () => InstallerStatus;
+    /// Starts the probing and waits until it is done.
+    ProbeSync = POST "/manager/probe_sync", () => ();
+    /// Lists the files and commands included in the logs.
+    ListLogs = GET "/manager/logs/list", () => LogsLists;
+
+    /// Returns the network devices.
+    GetNetworkDevices = GET "/network/devices", () => Vec<Device>;
+    /// Returns the network connections.
+    GetNetworkConnections = GET "/network/connections", () => Vec<NetworkConnection>;
+    /// Adds a network connection.
+    AddNetworkConnection = POST "/network/connections", NetworkConnection => ();
+    /// Returns a network connection.
+    GetNetworkConnection { id: String } =
+        GET "/network/connections/{id}", () => NetworkConnection;
+    /// Updates a network connection.
+    UpdateNetworkConnection { id: String } =
+        PUT "/network/connections/{id}", NetworkConnection => ();
+    /// Returns the network settings which are not bound to any connection.
+    GetNetworkGeneral = GET "/network/general", () => GeneralSettings;
+    /// Updates the network settings which are not bound to any connection.
+    UpdateNetworkGeneral = PUT "/network/general", GeneralSettings => ();
+    /// Returns the HTTP(S) proxy settings.
+    GetNetworkProxy = GET "/network/proxy", () => ProxySettings;
+    /// Updates the HTTP(S) proxy settings.
+    UpdateNetworkProxy = PUT "/network/proxy", ProxySettings => ();
+    /// Applies the network configuration.
+    ApplyNetwork = POST "/network/system/apply", () => ();
+    /// Confirms the network configuration applied with a confirmation timeout.
+    ConfirmNetwork = POST "/network/system/confirm", () => ();
+    /// Restores the network configuration previous to the one waiting for confirmation.
+    RollbackNetwork = POST "/network/system/rollback", () => ();
+
+    /// Returns the pending questions.
+    GetQuestions = GET "/questions", () => Vec<Question>;
+    /// Creates a question, returning it with its ID.
+    CreateQuestion = POST "/questions", Question => Question;
+    /// Deletes a question.
+    DeleteQuestion { id: u32 } = DELETE "/questions/{id}", () => ();
+    /// Returns the answer to a question (if any).
+    GetQuestionAnswer { id: u32 } = GET "/questions/{id}/answer", () => Option<Answer>;
+
+    /// Returns the user-defined scripts.
+    GetScripts = GET "/scripts", () => Vec<Script>;
+    /// Adds a user-defined script.
+    AddScript = POST "/scripts", Script => ();
+    /// Removes all the user-defined scripts.
+    DeleteScripts = DELETE "/scripts", () => ();
+    /// Runs the scripts of a group.
+    RunScripts = POST "/scripts/run", ScriptsGroup => ();
+
+    /// Returns the software configuration.
+    GetSoftwareConfig = GET "/software/config", () => SoftwareConfig;
+    /// Sets the software configuration.
+    SetSoftwareConfig = PUT "/software/config", SoftwareConfig => ();
+    /// Returns the registration information.
+    GetRegistration = GET "/software/registration", () => RegistrationInfo;
+    /// Registers the product.
+    Register = POST "/software/registration", RegistrationParams => ();
+    /// Updates a resolvables list.
+    SetResolvables { id: String } =
+        PUT "/software/resolvables/{id}", ResolvableParams => ();
+
+    /// Returns the storage configuration.
+    GetStorageConfig = GET "/storage/config", () => StorageSettings;
+    /// Sets the storage configuration.
+    SetStorageConfig = PUT "/storage/config", StorageSettings => ();
+
+    /// Returns the first user.
+    GetFirstUser = GET "/users/first", () => FirstUser;
+    /// Sets the first user.
+    SetFirstUser = PUT "/users/first", FirstUser => ();
+    /// Returns the root user configuration.
+    GetRootConfig = GET "/users/root", () => RootConfig;
+    /// Updates the root user configuration, returning the result code.
+    PatchRootConfig = PATCH "/users/root", RootPatchSettings => u32;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Endpoint, GetQuestionAnswer, SetResolvables, ENDPOINTS};
+
+    #[test]
+    fn test_path() {
+        let endpoint = GetQuestionAnswer { id: 7 };
+        assert_eq!(endpoint.path(), "/questions/7/answer");
+
+        let endpoint = SetResolvables {
+            id: "agama".to_string(),
+        };
+        assert_eq!(endpoint.path(), "/software/resolvables/agama");
+    }
+
+    #[test]
+    fn test_endpoints() {
+        assert!(ENDPOINTS.contains(&("PATCH", "/users/root")));
+    }
+}
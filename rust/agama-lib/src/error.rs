@@ -64,6 +64,13 @@ pub enum ServiceError {
     BackendError(u16, String),
     #[error("You are not logged in. Please use: agama auth login")]
     NotAuthenticated,
+    #[error("The certificate of '{0}' is not trusted")]
+    UntrustedCertificate(String),
+    #[error(
+        "The certificate of '{0}' has changed (expected fingerprint: {1}, received: {2}). \
+         If the change is expected, remove the old certificate from {3}"
+    )]
+    CertificateChanged(String, String, String, String),
     // Specific error when something does not work as expected, but it is not user fault
     #[error("Internal error. Please report a bug and attach logs. Details: {0}")]
     InternalError(String),
@@ -32,7 +32,7 @@ use crate::{dbus::get_property, error::ServiceError};
 pub mod client;
 
 /// Represents a job.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Job {
     /// Artificial job identifier.
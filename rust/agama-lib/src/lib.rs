@@ -46,6 +46,8 @@
 pub mod auth;
 pub mod base_http_client;
 pub mod bootloader;
+pub mod certificate;
+pub mod endpoints;
 pub mod error;
 pub mod install_settings;
 pub mod jobs;
@@ -66,7 +68,10 @@ mod store;
 pub use store::Store;
 pub mod openapi;
 pub mod questions;
+pub mod recording;
 pub mod scripts;
+#[cfg(test)]
+mod testing;
 pub mod utils;
 
 use crate::error::ServiceError;
@@ -94,8 +99,13 @@ pub fn http_client(token: &str) -> Result<reqwest::Client, ServiceError> {
 
     headers.insert(header::AUTHORIZATION, value);
 
-    let client = Client::builder()
-        .default_headers(headers)
+    let mut builder = Client::builder().default_headers(headers);
+    if let Some(proxy) = network::ProxySettings::system() {
+        builder = proxy
+            .configure_reqwest(builder)
+            .map_err(|e| ServiceError::NetworkClientError(e.to_string()))?;
+    }
+    let client = builder
         .build()
         .map_err(|e| ServiceError::NetworkClientError(e.to_string()))?;
 
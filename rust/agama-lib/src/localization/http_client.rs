@@ -19,7 +19,11 @@
 // find current contact information at www.suse.com.
 
 use super::model::LocaleConfig;
-use crate::{base_http_client::BaseHTTPClient, error::ServiceError};
+use crate::{
+    base_http_client::BaseHTTPClient,
+    endpoints::{GetL10nConfig, PatchL10nConfig},
+    error::ServiceError,
+};
 
 pub struct LocalizationHTTPClient {
     client: BaseHTTPClient,
@@ -31,10 +35,10 @@ impl LocalizationHTTPClient {
     }
 
     pub async fn get_config(&self) -> Result<LocaleConfig, ServiceError> {
-        self.client.get("/l10n/config").await
+        self.client.call(&GetL10nConfig, &()).await
     }
 
     pub async fn set_config(&self, config: &LocaleConfig) -> Result<(), ServiceError> {
-        self.client.patch_void("/l10n/config", config).await
+        self.client.call(&PatchL10nConfig, config).await
     }
 }
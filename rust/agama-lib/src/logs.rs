@@ -42,7 +42,7 @@ const DEFAULT_COMMANDS: [(&str, &str); 6] = [
     ("rpm -qa", "rpm-qa"),
 ];
 
-const DEFAULT_PATHS: [&str; 14] = [
+const DEFAULT_PATHS: [&str; 15] = [
     // logs
     "/var/log/YaST2",
     "/var/log/zypper.log",
@@ -54,6 +54,7 @@ const DEFAULT_PATHS: [&str; 14] = [
     "/var/log/boot.msg",
     "/var/log/udev.log",
     "/run/agama/dbus.log",
+    "/run/agama/events.jsonl",
     // config
     "/etc/install.inf",
     "/etc/os-release",
@@ -19,7 +19,10 @@
 // find current contact information at www.suse.com.
 
 use crate::{
-    base_http_client::BaseHTTPClient, error::ServiceError, logs::LogsLists,
+    base_http_client::BaseHTTPClient,
+    endpoints::{GetInstallerStatus, ListLogs, ProbeSync},
+    error::ServiceError,
+    logs::LogsLists,
     manager::InstallerStatus,
 };
 use reqwest::header::CONTENT_ENCODING;
@@ -37,9 +40,7 @@ impl ManagerHTTPClient {
 
     /// Starts a "probing".
     pub async fn probe(&self) -> Result<(), ServiceError> {
-        // BaseHTTPClient did not anticipate POST without request body
-        // so we pass () which is rendered as `null`
-        self.client.post_void("/manager/probe_sync", &()).await
+        self.client.call(&ProbeSync, &()).await
     }
 
     /// Downloads package of logs from the backend
@@ -83,13 +84,51 @@ impl ManagerHTTPClient {
     /// Asks backend for lists of log files and commands used for creating logs archive returned by
     /// store (/logs/store) backed HTTP API command
     pub async fn list(&self) -> Result<LogsLists, ServiceError> {
-        self.client.get("/manager/logs/list").await
+        self.client.call(&ListLogs, &()).await
     }
 
     /// Returns the installer status.
     pub async fn status(&self) -> Result<InstallerStatus, ServiceError> {
-        self.client
-            .get::<InstallerStatus>("/manager/installer")
-            .await
+        self.client.call(&GetInstallerStatus, &()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ManagerHTTPClient;
+    use crate::{manager::InstallationPhase, testing::ReplayServer};
+
+    #[tokio::test]
+    async fn test_status() {
+        let server = ReplayServer::from_fixture("manager");
+        let mocks = server.mocks();
+        let client = ManagerHTTPClient::new(server.client());
+
+        let status = client.status().await.unwrap();
+        assert_eq!(status.phase, InstallationPhase::Config);
+        assert!(status.can_install);
+        assert!(!status.is_busy);
+        mocks[0].assert();
+    }
+
+    #[tokio::test]
+    async fn test_list() {
+        let server = ReplayServer::from_fixture("manager");
+        let _mocks = server.mocks();
+        let client = ManagerHTTPClient::new(server.client());
+
+        let lists = client.list().await.unwrap();
+        assert_eq!(lists.commands.len(), 2);
+        assert!(lists.files.contains(&"/run/agama/events.jsonl".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_probe() {
+        let server = ReplayServer::from_fixture("manager");
+        let mocks = server.mocks();
+        let client = ManagerHTTPClient::new(server.client());
+
+        client.probe().await.unwrap();
+        mocks[2].assert();
     }
 }
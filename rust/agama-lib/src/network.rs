@@ -21,11 +21,12 @@
 //! Implements support for handling the network settings
 
 mod client;
+pub mod http_proxy;
 mod proxies;
 pub mod settings;
 mod store;
 pub mod types;
 
 pub use client::NetworkClient;
-pub use settings::NetworkSettings;
+pub use settings::{GeneralSettings, NetworkSettings, ProxySettings};
 pub use store::NetworkStore;
@@ -18,8 +18,16 @@
 // To contact SUSE LLC about this file by physical or electronic mail, you may
 // find current contact information at www.suse.com.
 
-use super::{settings::NetworkConnection, types::Device};
+use super::{
+    settings::{GeneralSettings, NetworkConnection, ProxySettings},
+    types::Device,
+};
 use crate::base_http_client::BaseHTTPClient;
+use crate::endpoints::{
+    AddNetworkConnection, ApplyNetwork, ConfirmNetwork, GetNetworkConnection,
+    GetNetworkConnections, GetNetworkDevices, GetNetworkGeneral, GetNetworkProxy, RollbackNetwork,
+    UpdateNetworkConnection, UpdateNetworkGeneral, UpdateNetworkProxy,
+};
 use crate::error::ServiceError;
 
 /// HTTP/JSON client for the network service
@@ -34,27 +42,22 @@ impl NetworkClient {
 
     /// Returns an array of network devices
     pub async fn devices(&self) -> Result<Vec<Device>, ServiceError> {
-        let json = self.client.get::<Vec<Device>>("/network/devices").await?;
+        let json = self.client.call(&GetNetworkDevices, &()).await?;
 
         Ok(json)
     }
 
     /// Returns an array of network connections
     pub async fn connections(&self) -> Result<Vec<NetworkConnection>, ServiceError> {
-        let json = self
-            .client
-            .get::<Vec<NetworkConnection>>("/network/connections")
-            .await?;
+        let json = self.client.call(&GetNetworkConnections, &()).await?;
 
         Ok(json)
     }
 
     /// Returns an array of network connections
     pub async fn connection(&self, id: &str) -> Result<NetworkConnection, ServiceError> {
-        let json = self
-            .client
-            .get::<NetworkConnection>(format!("/network/connections/{id}").as_str())
-            .await?;
+        let endpoint = GetNetworkConnection { id: id.to_string() };
+        let json = self.client.call(&endpoint, &()).await?;
 
         Ok(json)
     }
@@ -68,25 +71,68 @@ impl NetworkClient {
         let response = self.connection(id.as_str()).await;
 
         if response.is_ok() {
-            let path = format!("/network/connections/{id}");
-            self.client.put_void(path.as_str(), &connection).await?
+            let endpoint = UpdateNetworkConnection { id };
+            self.client.call(&endpoint, &connection).await?
         } else {
-            self.client
-                .post_void("/network/connections".to_string().as_str(), &connection)
-                .await?
+            self.client.call(&AddNetworkConnection, &connection).await?
         }
 
         Ok(())
     }
 
+    /// Returns the settings which are not bound to any connection, if any
+    pub async fn general(&self) -> Result<Option<GeneralSettings>, ServiceError> {
+        let general: GeneralSettings = self.client.call(&GetNetworkGeneral, &()).await?;
+
+        Ok(Some(general).filter(|g| !g.is_empty()))
+    }
+
+    /// Updates the settings which are not bound to any connection
+    pub async fn update_general(&self, general: &GeneralSettings) -> Result<(), ServiceError> {
+        self.client.call(&UpdateNetworkGeneral, general).await?;
+
+        Ok(())
+    }
+
+    /// Returns the HTTP(S) proxy settings, if a proxy is configured
+    pub async fn proxy(&self) -> Result<Option<ProxySettings>, ServiceError> {
+        let proxy: ProxySettings = self.client.call(&GetNetworkProxy, &()).await?;
+
+        Ok(Some(proxy).filter(ProxySettings::is_enabled))
+    }
+
+    /// Updates the HTTP(S) proxy settings
+    pub async fn update_proxy(&self, proxy: &ProxySettings) -> Result<(), ServiceError> {
+        self.client.call(&UpdateNetworkProxy, proxy).await?;
+
+        Ok(())
+    }
+
     /// Returns an array of network connections
     pub async fn apply(&self) -> Result<(), ServiceError> {
-        // trying to be tricky here. If something breaks then we need a put method on
-        // BaseHTTPClient which doesn't require a serialiable object for the body
-        self.client
-            .post_void("/network/system/apply".to_string().as_str(), &())
-            .await?;
+        self.client.call(&ApplyNetwork, &()).await?;
+
+        Ok(())
+    }
+
+    /// Confirms the configuration which is waiting for confirmation
+    pub async fn confirm(&self) -> Result<(), ServiceError> {
+        self.client.call(&ConfirmNetwork, &()).await?;
+
+        Ok(())
+    }
+
+    /// Restores the configuration previous to the one waiting for confirmation
+    pub async fn rollback(&self) -> Result<(), ServiceError> {
+        self.client.call(&RollbackNetwork, &()).await?;
 
         Ok(())
     }
+
+    /// Returns the network configuration as an nmstate YAML document
+    pub async fn nmstate(&self) -> Result<String, ServiceError> {
+        let response = self.client.get_raw("/network/nmstate").await?;
+
+        Ok(response.text().await?)
+    }
 }
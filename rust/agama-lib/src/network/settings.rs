@@ -20,7 +20,7 @@
 
 //! Representation of the network settings
 
-use super::types::{DeviceState, DeviceType, Status};
+use super::types::{DeviceState, DeviceType, DnsMode, Status};
 use crate::openapi::schemas;
 use cidr::IpInet;
 use serde::{Deserialize, Serialize};
@@ -33,10 +33,74 @@ use std::net::IpAddr;
 pub struct NetworkSettings {
     /// Connections to use in the installation
     pub connections: Vec<NetworkConnection>,
+    /// HTTP(S) proxy for the installer and the installed system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxySettings>,
+    /// Settings which are not bound to any connection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub general: Option<GeneralSettings>,
+}
+
+/// Network settings which are not bound to any connection
+///
+/// Only the given values are changed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneralSettings {
+    /// Static host name, which is kept in the installed system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// Transient host name, which is only used by the installer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transient_hostname: Option<String>,
+    /// Global DNS server IP addresses
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[schema(schema_with = schemas::ip_addr_array)]
+    pub nameservers: Vec<IpAddr>,
+    /// Global search domains for DNS resolution
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub dns_searchlist: Vec<String>,
+    /// DNS processing mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_mode: Option<DnsMode>,
+}
+
+impl GeneralSettings {
+    pub fn is_empty(&self) -> bool {
+        self.hostname.is_none()
+            && self.transient_hostname.is_none()
+            && self.nameservers.is_empty()
+            && self.dns_searchlist.is_empty()
+            && self.dns_mode.is_none()
+    }
+}
+
+/// HTTP(S) proxy settings
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySettings {
+    /// Proxy for HTTP connections (e.g., "http://proxy.example.com:3128")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<String>,
+    /// Proxy for HTTPS connections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub https: Option<String>,
+    /// Hosts or domains which are reached without the proxy
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub no_proxy: Vec<String>,
+    /// User name to authenticate against the proxy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Password to authenticate against the proxy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct MatchSettings {
+    /// Permanent MAC address of the device
+    #[serde(rename = "macAddress", skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub driver: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
@@ -49,7 +113,8 @@ pub struct MatchSettings {
 
 impl MatchSettings {
     pub fn is_empty(&self) -> bool {
-        self.path.is_empty()
+        self.mac_address.is_none()
+            && self.path.is_empty()
             && self.driver.is_empty()
             && self.kernel.is_empty()
             && self.interface.is_empty()
@@ -114,6 +179,150 @@ impl Default for BondSettings {
     }
 }
 
+/// VLAN configuration
+#[derive(Clone, Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct VlanSettings {
+    /// Interface or connection ID of the parent device
+    pub parent: String,
+    /// VLAN ID
+    pub id: u32,
+    /// VLAN protocol ("802.1Q" or "802.1ad")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+}
+
+/// Bridge configuration
+#[derive(Clone, Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeSettings {
+    /// Whether the Spanning Tree Protocol is enabled
+    #[serde(default)]
+    pub stp: bool,
+    /// STP priority of the bridge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    /// STP forward delay, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_delay: Option<u32>,
+    /// STP hello time, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hello_time: Option<u32>,
+    /// STP maximum message age, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u32>,
+    /// Ethernet MAC ageing time, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ageing_time: Option<u32>,
+    /// Interfaces or connection IDs of the bridge ports
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub ports: Vec<String>,
+}
+
+/// Settings of a connection which is a bridge port
+#[derive(Clone, Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgePortSettings {
+    /// STP priority of the port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    /// STP cost of the port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_cost: Option<u32>,
+}
+
+/// Static route
+#[derive(Clone, Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IpRouteSettings {
+    /// Destination network
+    #[schema(schema_with = schemas::ip_inet_ref)]
+    pub destination: IpInet,
+    /// Address of the next hop
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(schema_with = schemas::ip_addr_ref)]
+    pub next_hop: Option<IpAddr>,
+    /// Metric of the route
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<u32>,
+}
+
+/// InfiniBand configuration
+#[derive(Clone, Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InfinibandSettings {
+    /// Partition key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p_key: Option<i32>,
+    /// Parent interface, when the partition key is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Transport mode ("datagram" or "connected")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport_mode: Option<String>,
+}
+
+/// TUN/TAP configuration
+#[derive(Clone, Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TunSettings {
+    /// Device mode ("tun" or "tap")
+    pub mode: String,
+    /// Group ID which owns the device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// User ID which owns the device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+impl Default for TunSettings {
+    fn default() -> Self {
+        Self {
+            mode: "tun".to_string(),
+            group: None,
+            owner: None,
+        }
+    }
+}
+
+/// WireGuard configuration
+#[derive(Clone, Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WireguardSettings {
+    /// Base64 private key of the interface
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<String>,
+    /// UDP port to listen on (random if it is not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listen_port: Option<u16>,
+    /// Firewall mark for the outgoing packets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fwmark: Option<u32>,
+    /// Peers of the tunnel
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub peers: Vec<WireguardPeerSettings>,
+}
+
+/// WireGuard peer
+#[derive(Clone, Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WireguardPeerSettings {
+    /// Base64 public key of the peer
+    pub public_key: String,
+    /// Networks which are routed through the peer
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[schema(schema_with = schemas::ip_inet_array)]
+    pub allowed_ips: Vec<IpInet>,
+    /// Address and port of the peer (e.g., "vpn.example.com:51820")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Base64 pre-shared key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preshared_key: Option<String>,
+    /// Interval (in seconds) to send keepalive packets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persistent_keepalive: Option<u32>,
+}
+
 /// IEEE 802.1x (EAP) settings
 #[derive(Clone, Debug, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -207,7 +416,11 @@ pub struct NetworkConnection {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub interface: Option<String>,
     /// Match settings for the network connection
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "match",
+        alias = "matchSettings",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub match_settings: Option<MatchSettings>,
     /// Identifier for the parent connection, if this connection is part of a bond
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -215,6 +428,33 @@ pub struct NetworkConnection {
     /// Bonding settings if part of a bond
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bond: Option<BondSettings>,
+    /// VLAN settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<VlanSettings>,
+    /// Bridge settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge: Option<BridgeSettings>,
+    /// Bridge port settings, if the connection is a bridge port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge_port: Option<BridgePortSettings>,
+    /// InfiniBand settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub infiniband: Option<InfinibandSettings>,
+    /// TUN/TAP settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tun: Option<TunSettings>,
+    /// WireGuard settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wireguard: Option<WireguardSettings>,
+    /// Static IPv4 routes
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub routes4: Vec<IpRouteSettings>,
+    /// Static IPv6 routes
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub routes6: Vec<IpRouteSettings>,
+    /// Firewall zone of the connection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub firewall_zone: Option<String>,
     /// MAC address of the connection's interface
     #[serde(rename = "mac-address", skip_serializing_if = "Option::is_none")]
     pub mac_address: Option<String>,
@@ -250,6 +490,16 @@ impl NetworkConnection {
             DeviceType::Wireless
         } else if self.bond.is_some() {
             DeviceType::Bond
+        } else if self.vlan.is_some() {
+            DeviceType::Vlan
+        } else if self.bridge.is_some() {
+            DeviceType::Bridge
+        } else if self.infiniband.is_some() {
+            DeviceType::Infiniband
+        } else if self.tun.is_some() {
+            DeviceType::Tun
+        } else if self.wireguard.is_some() {
+            DeviceType::Wireguard
         } else {
             DeviceType::Ethernet
         }
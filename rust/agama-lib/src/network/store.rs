@@ -38,11 +38,24 @@ impl NetworkStore {
     // TODO: read the settings from the service
     pub async fn load(&self) -> Result<NetworkSettings, ServiceError> {
         let connections = self.network_client.connections().await?;
+        let proxy = self.network_client.proxy().await?;
+        let general = self.network_client.general().await?;
 
-        Ok(NetworkSettings { connections })
+        Ok(NetworkSettings {
+            connections,
+            proxy,
+            general,
+        })
     }
 
     pub async fn store(&self, settings: &NetworkSettings) -> Result<(), ServiceError> {
+        if let Some(proxy) = &settings.proxy {
+            self.network_client.update_proxy(proxy).await?;
+        }
+        if let Some(general) = &settings.general {
+            self.network_client.update_general(general).await?;
+        }
+
         for id in ordered_connections(&settings.connections) {
             let id = id.as_str();
             let fallback = default_connection(id);
@@ -78,13 +91,23 @@ fn add_ordered_connection(
     conns: &Vec<NetworkConnection>,
     ordered: &mut Vec<String>,
 ) {
-    if let Some(bond) = &conn.bond {
-        for port in &bond.ports {
-            if let Some(conn) = find_connection(port, conns) {
-                add_ordered_connection(conn, conns, ordered);
-            } else if !ordered.contains(&conn.id) {
-                ordered.push(port.clone());
-            }
+    let ports = match (&conn.bond, &conn.bridge) {
+        (Some(bond), _) => bond.ports.as_slice(),
+        (_, Some(bridge)) => bridge.ports.as_slice(),
+        _ => &[],
+    };
+    for port in ports {
+        if let Some(conn) = find_connection(port, conns) {
+            add_ordered_connection(conn, conns, ordered);
+        } else if !ordered.contains(port) {
+            ordered.push(port.clone());
+        }
+    }
+
+    // the parent of a VLAN is only written when it is included in the settings
+    if let Some(vlan) = &conn.vlan {
+        if let Some(parent) = find_connection(&vlan.parent, conns) {
+            add_ordered_connection(parent, conns, ordered);
         }
     }
 
@@ -113,7 +136,7 @@ fn default_connection(id: &str) -> NetworkConnection {
 #[cfg(test)]
 mod tests {
     use super::ordered_connections;
-    use crate::network::settings::{BondSettings, NetworkConnection};
+    use crate::network::settings::{BondSettings, NetworkConnection, VlanSettings};
 
     #[test]
     fn test_ordered_connections() {
@@ -153,4 +176,46 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn test_ordered_vlan_over_bond() {
+        let vlan = NetworkConnection {
+            id: "bond0.10".to_string(),
+            vlan: Some(VlanSettings {
+                parent: "bond0".to_string(),
+                id: 10,
+                protocol: None,
+            }),
+            ..Default::default()
+        };
+        let bond = NetworkConnection {
+            id: "bond0".to_string(),
+            bond: Some(BondSettings {
+                ports: vec!["eth0".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let eth1_vlan = NetworkConnection {
+            id: "eth1.20".to_string(),
+            vlan: Some(VlanSettings {
+                parent: "eth1".to_string(),
+                id: 20,
+                protocol: None,
+            }),
+            ..Default::default()
+        };
+
+        let conns = vec![vlan, bond, eth1_vlan];
+        let ordered = ordered_connections(&conns);
+        assert_eq!(
+            ordered,
+            vec![
+                "eth0".to_string(),
+                "bond0".to_string(),
+                "bond0.10".to_string(),
+                "eth1.20".to_string()
+            ]
+        )
+    }
 }
@@ -76,6 +76,9 @@ pub enum DeviceType {
     Bond = 4,
     Vlan = 5,
     Bridge = 6,
+    Infiniband = 7,
+    Tun = 8,
+    Wireguard = 9,
 }
 
 // For now this mirrors NetworkManager, because it was less mental work than coming up with
@@ -182,6 +185,47 @@ impl TryFrom<&str> for Status {
     }
 }
 
+/// DNS processing mode (the `dns` option of NetworkManager)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum DnsMode {
+    #[default]
+    Default,
+    Dnsmasq,
+    SystemdResolved,
+    None,
+}
+
+impl fmt::Display for DnsMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            DnsMode::Default => "default",
+            DnsMode::Dnsmasq => "dnsmasq",
+            DnsMode::SystemdResolved => "systemd-resolved",
+            DnsMode::None => "none",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Invalid DNS mode: {0}")]
+pub struct InvalidDnsMode(String);
+
+impl TryFrom<&str> for DnsMode {
+    type Error = InvalidDnsMode;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "default" => Ok(DnsMode::Default),
+            "dnsmasq" => Ok(DnsMode::Dnsmasq),
+            "systemd-resolved" => Ok(DnsMode::SystemdResolved),
+            "none" => Ok(DnsMode::None),
+            _ => Err(InvalidDnsMode(value.to_string())),
+        }
+    }
+}
+
 /// Bond mode
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, utoipa::ToSchema)]
 pub enum BondMode {
@@ -283,6 +327,9 @@ impl TryFrom<u8> for DeviceType {
             4 => Ok(DeviceType::Bond),
             5 => Ok(DeviceType::Vlan),
             6 => Ok(DeviceType::Bridge),
+            7 => Ok(DeviceType::Infiniband),
+            8 => Ok(DeviceType::Tun),
+            9 => Ok(DeviceType::Wireguard),
             _ => Err(InvalidDeviceType(value)),
         }
     }
@@ -22,7 +22,11 @@ use crate::software::model::RegistrationError;
 use crate::software::model::RegistrationInfo;
 use crate::software::model::RegistrationParams;
 use crate::software::model::SoftwareConfig;
-use crate::{base_http_client::BaseHTTPClient, error::ServiceError};
+use crate::{
+    base_http_client::BaseHTTPClient,
+    endpoints::{GetRegistration, GetSoftwareConfig, Register, SetSoftwareConfig},
+    error::ServiceError,
+};
 
 pub struct ProductHTTPClient {
     client: BaseHTTPClient,
@@ -34,11 +38,11 @@ impl ProductHTTPClient {
     }
 
     pub async fn get_software(&self) -> Result<SoftwareConfig, ServiceError> {
-        self.client.get("/software/config").await
+        self.client.call(&GetSoftwareConfig, &()).await
     }
 
     pub async fn set_software(&self, config: &SoftwareConfig) -> Result<(), ServiceError> {
-        self.client.put_void("/software/config", config).await
+        self.client.call(&SetSoftwareConfig, config).await
     }
 
     /// Returns the id of the selected product to install
@@ -61,7 +65,7 @@ impl ProductHTTPClient {
     }
 
     pub async fn get_registration(&self) -> Result<RegistrationInfo, ServiceError> {
-        self.client.get("/software/registration").await
+        self.client.call(&GetRegistration, &()).await
     }
 
     /// register product
@@ -71,10 +75,7 @@ impl ProductHTTPClient {
             key: key.to_owned(),
             email: email.to_owned(),
         };
-        let result = self
-            .client
-            .post_void("/software/registration", &params)
-            .await;
+        let result = self.client.call(&Register, &params).await;
 
         let Err(error) = result else {
             return Ok(());
@@ -23,7 +23,11 @@ use std::time::Duration;
 use reqwest::StatusCode;
 use tokio::time::sleep;
 
-use crate::{base_http_client::BaseHTTPClient, error::ServiceError};
+use crate::{
+    base_http_client::BaseHTTPClient,
+    endpoints::{CreateQuestion, DeleteQuestion, GetQuestionAnswer, GetQuestions},
+    error::ServiceError,
+};
 
 use super::model::{self, Answer, Question};
 
@@ -37,18 +41,18 @@ impl HTTPClient {
     }
 
     pub async fn list_questions(&self) -> Result<Vec<model::Question>, ServiceError> {
-        self.client.get("/questions").await
+        self.client.call(&GetQuestions, &()).await
     }
 
     /// Creates question and return newly created question including id
     pub async fn create_question(&self, question: &Question) -> Result<Question, ServiceError> {
-        self.client.post("/questions", question).await
+        self.client.call(&CreateQuestion, question).await
     }
 
     /// non blocking varient of checking if question has already answer
     pub async fn try_answer(&self, question_id: u32) -> Result<Option<Answer>, ServiceError> {
-        let path = format!("/questions/{}/answer", question_id);
-        let result: Result<Option<Answer>, _> = self.client.get(path.as_str()).await;
+        let endpoint = GetQuestionAnswer { id: question_id };
+        let result = self.client.call(&endpoint, &()).await;
         match result {
             Err(ServiceError::BackendError(code, ref _body_s)) => {
                 if code == StatusCode::NOT_FOUND {
@@ -77,8 +81,8 @@ impl HTTPClient {
     }
 
     pub async fn delete_question(&self, question_id: u32) -> Result<(), ServiceError> {
-        let path = format!("/questions/{}", question_id);
-        self.client.delete_void(path.as_str()).await
+        let endpoint = DeleteQuestion { id: question_id };
+        self.client.call(&endpoint, &()).await
     }
 }
 
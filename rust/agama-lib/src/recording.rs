@@ -0,0 +1,167 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Records of the HTTP API traffic.
+//!
+//! The web server can record the requests and the responses (see the `--record` option of
+//! `agama-web-server`) to a JSON-lines file, where each line is an [Exchange]. The recordings
+//! can be replayed to test the HTTP clients without a running server.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+/// Request and its response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    /// HTTP method.
+    pub method: String,
+    /// Request path (e.g., "/api/software/config").
+    pub path: String,
+    /// Query string, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Request body, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<Value>,
+    /// Response status code.
+    pub status: u16,
+    /// Response body, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+}
+
+impl Exchange {
+    /// Converts a body into a JSON value.
+    ///
+    /// Non-JSON bodies are stored as strings and empty bodies are ignored.
+    ///
+    /// * `body`: body content.
+    pub fn body_to_value(body: &[u8]) -> Option<Value> {
+        if body.is_empty() {
+            return None;
+        }
+
+        match serde_json::from_slice(body) {
+            Ok(value) => Some(value),
+            Err(_) => Some(Value::String(String::from_utf8_lossy(body).to_string())),
+        }
+    }
+
+    /// Query string parameters.
+    pub fn query_params(&self) -> Vec<(String, String)> {
+        let Some(query) = &self.query else {
+            return vec![];
+        };
+
+        url::form_urlencoded::parse(query.as_bytes())
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+}
+
+/// Recording of the HTTP API traffic.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Recording {
+    pub exchanges: Vec<Exchange>,
+}
+
+impl Recording {
+    /// Reads a recording from a JSON-lines file.
+    ///
+    /// * `path`: file to read.
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let mut exchanges = vec![];
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let exchange = serde_json::from_str(&line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            exchanges.push(exchange);
+        }
+        Ok(Self { exchanges })
+    }
+
+    /// Appends an exchange to a JSON-lines file.
+    ///
+    /// * `path`: file to write to.
+    /// * `exchange`: exchange to append.
+    pub fn append<P: AsRef<Path>>(path: P, exchange: &Exchange) -> io::Result<()> {
+        let mut line = serde_json::to_string(exchange)?;
+        line.push('\n');
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(line.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Exchange, Recording};
+    use serde_json::json;
+
+    #[test]
+    fn test_body_to_value() {
+        assert_eq!(Exchange::body_to_value(b""), None);
+        assert_eq!(
+            Exchange::body_to_value(br#"{"product":"Tumbleweed"}"#),
+            Some(json!({ "product": "Tumbleweed" }))
+        );
+        assert_eq!(
+            Exchange::body_to_value(b"not json"),
+            Some(json!("not json"))
+        );
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.jsonl");
+        let exchange = Exchange {
+            method: "GET".to_string(),
+            path: "/api/software/config".to_string(),
+            query: Some("lang=en&all=true".to_string()),
+            request: None,
+            status: 200,
+            response: Some(json!({ "product": "Tumbleweed" })),
+        };
+        Recording::append(&path, &exchange).unwrap();
+        Recording::append(&path, &exchange).unwrap();
+
+        let recording = Recording::read(&path).unwrap();
+        assert_eq!(
+            recording.exchanges,
+            vec![exchange.clone(), exchange.clone()]
+        );
+        assert_eq!(
+            exchange.query_params(),
+            vec![
+                ("lang".to_string(), "en".to_string()),
+                ("all".to_string(), "true".to_string())
+            ]
+        );
+    }
+}
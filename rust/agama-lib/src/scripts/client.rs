@@ -18,7 +18,11 @@
 // To contact SUSE LLC about this file by physical or electronic mail, you may
 // find current contact information at www.suse.com.
 
-use crate::{base_http_client::BaseHTTPClient, error::ServiceError};
+use crate::{
+    base_http_client::BaseHTTPClient,
+    endpoints::{AddScript, DeleteScripts, GetScripts, RunScripts},
+    error::ServiceError,
+};
 
 use super::{Script, ScriptsGroup};
 
@@ -36,23 +40,23 @@ impl ScriptsClient {
     ///
     /// * `script`: script's definition.
     pub async fn add_script(&self, script: Script) -> Result<(), ServiceError> {
-        self.client.post_void("/scripts", &script).await
+        self.client.call(&AddScript, &script).await
     }
 
     /// Runs user-defined scripts of the given group.
     ///
     /// * `group`: group of the scripts to run
     pub async fn run_scripts(&self, group: ScriptsGroup) -> Result<(), ServiceError> {
-        self.client.post_void("/scripts/run", &group).await
+        self.client.call(&RunScripts, &group).await
     }
 
     /// Returns the user-defined scripts.
     pub async fn scripts(&self) -> Result<Vec<Script>, ServiceError> {
-        self.client.get("/scripts").await
+        self.client.call(&GetScripts, &()).await
     }
 
     /// Remove all the user-defined scripts.
     pub async fn delete_scripts(&self) -> Result<(), ServiceError> {
-        self.client.delete_void("/scripts").await
+        self.client.call(&DeleteScripts, &()).await
     }
 }
@@ -19,7 +19,11 @@
 // find current contact information at www.suse.com.
 
 use crate::software::model::SoftwareConfig;
-use crate::{base_http_client::BaseHTTPClient, error::ServiceError};
+use crate::{
+    base_http_client::BaseHTTPClient,
+    endpoints::{GetSoftwareConfig, SetResolvables, SetSoftwareConfig},
+    error::ServiceError,
+};
 use std::collections::HashMap;
 
 use super::model::{ResolvableParams, ResolvableType};
@@ -34,7 +38,7 @@ impl SoftwareHTTPClient {
     }
 
     pub async fn get_config(&self) -> Result<SoftwareConfig, ServiceError> {
-        self.client.get("/software/config").await
+        self.client.call(&GetSoftwareConfig, &()).await
     }
 
     pub async fn set_config(&self, config: &SoftwareConfig) -> Result<(), ServiceError> {
@@ -44,7 +48,7 @@ impl SoftwareHTTPClient {
         //            Err(ServiceError::UnknownPatterns(wrong_patterns))
         // CLI prints:
         // Anyhow(Backend call failed with status 400 and text '{"error":"Agama service error: Failed to find these patterns: [\"no_such_pattern\"]"}')
-        self.client.put_void("/software/config", config).await
+        self.client.call(&SetSoftwareConfig, config).await
     }
 
     /// Returns the ids of patterns selected by user
@@ -85,13 +89,56 @@ impl SoftwareHTTPClient {
         names: &[&str],
         optional: bool,
     ) -> Result<(), ServiceError> {
-        let path = format!("/software/resolvables/{}", name);
+        let endpoint = SetResolvables {
+            id: name.to_string(),
+        };
         let options = ResolvableParams {
             names: names.iter().map(|n| n.to_string()).collect(),
             r#type,
             optional,
         };
-        self.client.put_void(&path, &options).await?;
+        self.client.call(&endpoint, &options).await?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SoftwareHTTPClient;
+    use crate::testing::ReplayServer;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_user_selected_patterns() {
+        let server = ReplayServer::from_fixture("software");
+        let _mocks = server.mocks();
+        let client = SoftwareHTTPClient::new(server.client());
+
+        let config = client.get_config().await.unwrap();
+        assert_eq!(config.product, Some("Tumbleweed".to_string()));
+        let patterns = client.user_selected_patterns().await.unwrap();
+        assert_eq!(patterns, vec!["gnome".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_select_patterns() {
+        let server = ReplayServer::from_fixture("software");
+        let mocks = server.mocks();
+        let client = SoftwareHTTPClient::new(server.client());
+
+        let patterns = HashMap::from([("xfce".to_string(), true)]);
+        client.select_patterns(patterns).await.unwrap();
+        mocks[1].assert();
+    }
+
+    #[tokio::test]
+    async fn test_select_unknown_patterns() {
+        let server = ReplayServer::from_fixture("software");
+        let _mocks = server.mocks();
+        let client = SoftwareHTTPClient::new(server.client());
+
+        let patterns = HashMap::from([("no_such_pattern".to_string(), true)]);
+        let error = client.select_patterns(patterns).await.unwrap_err();
+        assert!(error.to_string().contains("no_such_pattern"));
+    }
+}
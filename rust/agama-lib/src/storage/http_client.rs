@@ -20,6 +20,7 @@
 
 //! Implements a client to access Agama's storage service.
 use crate::base_http_client::BaseHTTPClient;
+use crate::endpoints::{GetStorageConfig, SetStorageConfig};
 use crate::storage::StorageSettings;
 use crate::ServiceError;
 
@@ -33,10 +34,44 @@ impl StorageHTTPClient {
     }
 
     pub async fn get_config(&self) -> Result<StorageSettings, ServiceError> {
-        self.client.get("/storage/config").await
+        self.client.call(&GetStorageConfig, &()).await
     }
 
     pub async fn set_config(&self, config: &StorageSettings) -> Result<(), ServiceError> {
-        self.client.put_void("/storage/config", config).await
+        self.client.call(&SetStorageConfig, config).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StorageHTTPClient;
+    use crate::{storage::StorageSettings, testing::ReplayServer};
+    use serde_json::value::RawValue;
+
+    #[tokio::test]
+    async fn test_get_config() {
+        let server = ReplayServer::from_fixture("storage");
+        let _mocks = server.mocks();
+        let client = StorageHTTPClient::new(server.client());
+
+        let config = client.get_config().await.unwrap();
+        let storage = config.storage.unwrap();
+        assert!(storage.get().contains("/dev/vda"));
+        assert!(config.storage_autoyast.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_set_config() {
+        let server = ReplayServer::from_fixture("storage");
+        let mocks = server.mocks();
+        let client = StorageHTTPClient::new(server.client());
+
+        let storage = RawValue::from_string(r#"{"drives":[{"search":"/dev/vdb"}]}"#.to_string());
+        let config = StorageSettings {
+            storage: Some(storage.unwrap()),
+            storage_autoyast: None,
+        };
+        client.set_config(&config).await.unwrap();
+        mocks[1].assert();
     }
 }
@@ -0,0 +1,97 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Helpers to test the HTTP clients.
+
+use crate::{
+    base_http_client::BaseHTTPClient,
+    recording::{Exchange, Recording},
+};
+use httpmock::{Mock, MockServer};
+use std::path::Path;
+
+/// Mock server that replays a recording of the HTTP API traffic.
+///
+/// Each recorded exchange becomes a mock which matches the method, the path, the query
+/// parameters and the request body. The server does not keep any state, so if the same request
+/// was recorded several times, the first response is used.
+pub struct ReplayServer {
+    server: MockServer,
+    recording: Recording,
+}
+
+impl ReplayServer {
+    /// Starts a server replaying the given recording.
+    ///
+    /// * `recording`: recording to replay.
+    pub fn start(recording: Recording) -> Self {
+        let server = MockServer::start();
+        Self { server, recording }
+    }
+
+    /// Starts a server replaying a recording from the `share/recordings` directory.
+    ///
+    /// * `name`: name of the recording (without the extension).
+    pub fn from_fixture(name: &str) -> Self {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("share/recordings")
+            .join(format!("{}.jsonl", name));
+        let recording = Recording::read(&path)
+            .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+        Self::start(recording)
+    }
+
+    /// Registers the mocks and returns them, so the test can check how many times each one was
+    /// called.
+    pub fn mocks(&self) -> Vec<Mock<'_>> {
+        self.recording
+            .exchanges
+            .iter()
+            .map(|e| self.mock(e))
+            .collect()
+    }
+
+    fn mock(&self, exchange: &Exchange) -> Mock<'_> {
+        self.server.mock(|when, then| {
+            let mut when = when
+                .method(exchange.method.as_str())
+                .path(exchange.path.as_str());
+            for (name, value) in exchange.query_params() {
+                when = when.query_param(name, value);
+            }
+            if let Some(body) = &exchange.request {
+                when.json_body(body.clone());
+            }
+
+            let then = then.status(exchange.status);
+            if let Some(body) = &exchange.response {
+                then.header("content-type", "application/json")
+                    .json_body(body.clone());
+            }
+        })
+    }
+
+    /// Returns an HTTP client connected to this server.
+    pub fn client(&self) -> BaseHTTPClient {
+        let mut client = BaseHTTPClient::default();
+        client.base_url = self.server.url("/api");
+        client
+    }
+}
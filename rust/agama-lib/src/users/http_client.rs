@@ -19,6 +19,7 @@
 // find current contact information at www.suse.com.
 
 use super::client::FirstUser;
+use crate::endpoints::{GetFirstUser, GetRootConfig, PatchRootConfig, SetFirstUser};
 use crate::users::model::{RootConfig, RootPatchSettings};
 use crate::{base_http_client::BaseHTTPClient, error::ServiceError};
 
@@ -33,12 +34,12 @@ impl UsersHTTPClient {
 
     /// Returns the settings for first non admin user
     pub async fn first_user(&self) -> Result<FirstUser, ServiceError> {
-        self.client.get("/users/first").await
+        self.client.call(&GetFirstUser, &()).await
     }
 
     /// Set the configuration for the first user
     pub async fn set_first_user(&self, first_user: &FirstUser) -> Result<(), ServiceError> {
-        let result = self.client.put_void("/users/first", first_user).await;
+        let result = self.client.call(&SetFirstUser, first_user).await;
         if let Err(ServiceError::BackendError(422, ref issues_s)) = result {
             let issues: Vec<String> = serde_json::from_str(issues_s)?;
             return Err(ServiceError::WrongUser(issues));
@@ -47,7 +48,7 @@ impl UsersHTTPClient {
     }
 
     async fn root_config(&self) -> Result<RootConfig, ServiceError> {
-        self.client.get("/users/root").await
+        self.client.call(&GetRootConfig, &()).await
     }
 
     /// Whether the root password is set or not
@@ -64,7 +65,7 @@ impl UsersHTTPClient {
             password: Some(value.to_owned()),
             hashed_password: Some(hashed),
         };
-        let ret = self.client.patch("/users/root", &rps).await?;
+        let ret = self.client.call(&PatchRootConfig, &rps).await?;
         Ok(ret)
     }
 
@@ -25,13 +25,16 @@
 //! installer](https://github.com/yast/yast-installation/blob/master/doc/url.md) for further
 //! information.
 //!
-//! At this point, it only supports those schemes supported by CURL.
+//! At this point, it only supports those schemes supported by CURL. The proxy of the system
+//! (see [ProxySettings::system]) is honored.
 
 use std::io::Write;
 
 use curl::easy::Easy;
 use thiserror::Error;
 
+use crate::network::ProxySettings;
+
 #[derive(Error, Debug)]
 #[error(transparent)]
 pub struct TransferError(#[from] curl::Error);
@@ -50,6 +53,9 @@ impl Transfer {
         handle.follow_location(true)?;
         handle.fail_on_error(true)?;
         handle.url(url)?;
+        if let Some(proxy) = ProxySettings::system() {
+            proxy.configure_curl(&mut handle, url)?;
+        }
 
         let mut transfer = handle.transfer();
         transfer.write_function(|buf| Ok(out_fd.write(buf).unwrap()))?;
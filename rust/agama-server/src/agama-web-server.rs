@@ -19,6 +19,11 @@
 // find current contact information at www.suse.com.
 
 use std::{
+    fs,
+    os::{
+        fd::{FromRawFd, RawFd},
+        unix::fs::PermissionsExt,
+    },
     path::{Path, PathBuf},
     pin::Pin,
     process::{ExitCode, Termination},
@@ -26,10 +31,13 @@ use std::{
 
 use agama_lib::{auth::AuthToken, connection_to};
 use agama_server::{
-    cert::Certificate,
+    cert::{run_reloader, CertificateSource, CertificateStore},
     l10n::helpers,
     logs::init_logging,
-    web::{self, run_monitor},
+    web::{
+        self, run_monitor, ClientInfo, EventsJournal, PeerAddress, PeerCredentials, Recorder,
+        ServiceConfig,
+    },
 };
 use anyhow::Context;
 use axum::{
@@ -42,8 +50,12 @@ use futures_util::pin_mut;
 use hyper::body::Incoming;
 use hyper_util::rt::{TokioExecutor, TokioIo};
 use hyper_util::server::conn::auto::Builder;
-use openssl::ssl::{Ssl, SslAcceptor, SslMethod};
-use tokio::sync::broadcast::channel;
+use nix::sys::socket::{getsockname, AddressFamily, SockaddrLike, SockaddrStorage};
+use openssl::ssl::{Ssl, SslAcceptor};
+use tokio::{
+    net::{TcpListener, UnixListener},
+    sync::broadcast::channel,
+};
 use tokio_openssl::SslStream;
 use tower::Service;
 
@@ -56,6 +68,14 @@ enum Commands {
     ///
     /// This command starts the server in the given ports. The secondary port, if enabled, uses SSL.
     /// If no certificate is specified, agama-web-server generates a self-signed one.
+    ///
+    /// The certificate is reloaded when the process receives a SIGHUP signal or when the
+    /// certificate files change. A self-signed certificate is regenerated when the IP addresses
+    /// or the host name change.
+    ///
+    /// The server can also listen on a Unix socket, where the requests coming from root do not
+    /// need a token. If the server is started by systemd using socket activation (LISTEN_FDS),
+    /// it uses the passed sockets instead of the given addresses.
     Serve(ServeArgs),
 }
 
@@ -97,6 +117,18 @@ struct ServeArgs {
     #[arg(long, default_value = None)]
     address2: Option<String>,
 
+    /// Optional Unix socket to listen on (root is authenticated using the peer credentials)
+    #[arg(long, default_value = None)]
+    unix_socket: Option<PathBuf>,
+
+    /// Optional file to write the events to (JSON lines)
+    #[arg(long, default_value = None)]
+    events_journal: Option<PathBuf>,
+
+    /// Optional file to record the HTTP API traffic to (JSON lines), useful to write tests
+    #[arg(long, default_value = None)]
+    record: Option<PathBuf>,
+
     #[arg(long, default_value = "/etc/agama.d/ssl/key.pem")]
     key: Option<PathBuf>,
 
@@ -123,41 +155,21 @@ impl ServeArgs {
         self.key.as_ref().is_some_and(|k| Path::new(&k).exists())
     }
 
-    /// Takes options provided by user and loads / creates Certificate struct according to them
-    fn to_certificate(&self) -> anyhow::Result<Certificate> {
+    /// Takes options provided by user and decides where to get the certificate from
+    fn certificate_source(&self) -> CertificateSource {
         if self.valid_cert_path() && self.valid_key_path() {
-            let cert = self.cert.clone().unwrap();
-            let key = self.key.clone().unwrap();
-
-            // read the provided certificate
-            Certificate::read(cert.as_path(), key.as_path())
+            // use the provided certificate
+            CertificateSource::Files {
+                cert: self.cert.clone().unwrap(),
+                key: self.key.clone().unwrap(),
+            }
         } else {
             // ask for self-signed certificate
-            let certificate = Certificate::new()?;
-
-            // write the certificate for the later use
-            // for now do not care if writing self generated certificate failed or not, in the
-            // worst case we will generate new one ... which will surely be better
-            let _ = certificate.write();
-
-            Ok(certificate)
+            CertificateSource::SelfSigned
         }
     }
 }
 
-/// Builds an SSL acceptor using a provided SSL certificate or generates a self-signed one
-fn ssl_acceptor(certificate: &Certificate) -> Result<SslAcceptor, openssl::error::ErrorStack> {
-    let mut tls_builder = SslAcceptor::mozilla_modern_v5(SslMethod::tls_server())?;
-
-    tls_builder.set_private_key(&certificate.key)?;
-    tls_builder.set_certificate(&certificate.cert)?;
-
-    // check that the key belongs to the certificate
-    tls_builder.check_private_key()?;
-
-    Ok(tls_builder.build())
-}
-
 /// Checks whether the connection uses SSL or not
 /// `stream`: the TCP stream containing a request from client
 async fn is_ssl_stream(stream: &tokio::net::TcpStream) -> bool {
@@ -209,11 +221,18 @@ fn redirect_error() -> Response<String> {
 /// Builds a router for the HTTP -> HTTPS redirection
 /// if the redirection URL cannot be built from the original request it returns error 400
 /// instead of the redirection
+///
+/// The host is taken from the [ClientInfo] extension, so the `X-Forwarded-Host` header is
+/// honored when the request comes from a trusted proxy.
 fn https_redirect() -> Router {
     // see https://docs.rs/axum/latest/axum/routing/struct.Router.html#example
     let redirect_service = tower::service_fn(|req: AxumRequest| async move {
-        if let Some(host) = req.headers().get("host").and_then(|h| h.to_str().ok()) {
-            Ok(redirect_https(host, req.uri()))
+        let host = req
+            .extensions()
+            .get::<ClientInfo>()
+            .and_then(|c| c.host.clone());
+        if let Some(host) = host {
+            Ok(redirect_https(&host, req.uri()))
         } else {
             Ok(redirect_error())
         }
@@ -239,7 +258,9 @@ async fn handle_https_stream(
         tracing::error!("Error during TSL handshake from {}: {}", addr, err);
     } else {
         let stream = TokioIo::new(tls_stream);
-        let hyper_service = hyper::service::service_fn(move |request: Request<Incoming>| {
+        let peer = PeerAddress { addr, tls: true };
+        let hyper_service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+            request.extensions_mut().insert(peer);
             service.clone().call(request)
         });
 
@@ -259,17 +280,23 @@ async fn handle_http_stream(
     tcp_stream: tokio::net::TcpStream,
     service: axum::Router,
     redirector_service: axum::Router,
+    config: ServiceConfig,
 ) {
     let stream = TokioIo::new(tcp_stream);
-    let hyper_service = hyper::service::service_fn(move |request: Request<Incoming>| {
+    let peer = PeerAddress { addr, tls: false };
+    let hyper_service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+        let client = ClientInfo::new(request.headers(), Some(&peer), &config);
+        request.extensions_mut().insert(peer);
         // check if it is local connection or external
         // the to_canonical() converts IPv4-mapped IPv6 addresses
         // to plain IPv4, then is_loopback() works correctly for the IPv4 connections
-        if addr.ip().to_canonical().is_loopback() {
+        // a trusted proxy which terminates the TLS connection is accepted too
+        if addr.ip().to_canonical().is_loopback() || client.is_https() {
             // accept plain HTTP on the local connection
             service.clone().call(request)
         } else {
             // redirect external connections to HTTPS
+            request.extensions_mut().insert(client);
             redirector_service.clone().call(request)
         }
     });
@@ -283,20 +310,91 @@ async fn handle_http_stream(
     }
 }
 
+/// Listeners the web server accepts connections on
+enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Binds a TCP listener to the given address
+    async fn bind_tcp(address: &str) -> anyhow::Result<Self> {
+        tracing::info!("Starting Agama web server at {}", address);
+        let listener = TcpListener::bind(address)
+            .await
+            .with_context(|| format!("could not listen on {}", address))?;
+        Ok(Self::Tcp(listener))
+    }
+
+    /// Binds a Unix listener to the given path
+    ///
+    /// The socket is accessible by any user, the non-root users still need to authenticate.
+    fn bind_unix(path: &Path) -> anyhow::Result<Self> {
+        tracing::info!("Starting Agama web server at {}", path.display());
+        // remove the socket left by a previous run
+        if path.exists() {
+            fs::remove_file(path)
+                .with_context(|| format!("could not remove {}", path.display()))?;
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("could not listen on {}", path.display()))?;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o666))?;
+        Ok(Self::Unix(listener))
+    }
+
+    /// Takes the listening socket passed by systemd
+    ///
+    /// `fd`: file descriptor of the socket
+    fn from_fd(fd: RawFd) -> anyhow::Result<Self> {
+        let address: SockaddrStorage =
+            getsockname(fd).context("the file descriptor is not a socket")?;
+
+        match address.family() {
+            Some(AddressFamily::Unix) => {
+                // SAFETY: systemd passes the ownership of the socket to this process
+                let listener = unsafe { std::os::unix::net::UnixListener::from_raw_fd(fd) };
+                listener.set_nonblocking(true)?;
+                Ok(Self::Unix(UnixListener::from_std(listener)?))
+            }
+            Some(AddressFamily::Inet | AddressFamily::Inet6) => {
+                // SAFETY: systemd passes the ownership of the socket to this process
+                let listener = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+                listener.set_nonblocking(true)?;
+                Ok(Self::Tcp(TcpListener::from_std(listener)?))
+            }
+            family => Err(anyhow::anyhow!("unsupported socket family: {:?}", family)),
+        }
+    }
+}
+
+/// Returns the listening sockets passed by systemd (socket activation)
+fn systemd_listeners() -> anyhow::Result<Vec<Listener>> {
+    let fds = sd_notify::listen_fds().context("invalid socket activation variables")?;
+    fds.map(Listener::from_fd).collect()
+}
+
 /// Starts the web server
-async fn start_server(address: String, service: Router, ssl_acceptor: SslAcceptor) {
-    tracing::info!("Starting Agama web server at {}", address);
+async fn start_server(
+    listener: Listener,
+    service: Router,
+    certificate: CertificateStore,
+    config: ServiceConfig,
+) {
+    match listener {
+        Listener::Tcp(listener) => serve_tcp(listener, service, certificate, config).await,
+        Listener::Unix(listener) => serve_unix(listener, service).await,
+    }
+}
 
+/// Serves the API on a TCP listener (HTTP and HTTPS)
+async fn serve_tcp(
+    listener: TcpListener,
+    service: Router,
+    certificate: CertificateStore,
+    config: ServiceConfig,
+) {
     // see https://github.com/tokio-rs/axum/blob/main/examples/low-level-openssl/src/main.rs
     // how to use axum with openSSL
-    let listener = tokio::net::TcpListener::bind(&address)
-        .await
-        .unwrap_or_else(|error| {
-            let msg = format!("Error: could not listen on {}: {}", &address, error);
-            tracing::error!(msg);
-            panic!("{}", msg)
-        });
-
     pin_mut!(listener);
 
     let redirector = https_redirect();
@@ -304,7 +402,9 @@ async fn start_server(address: String, service: Router, ssl_acceptor: SslAccepto
     loop {
         let tower_service = service.clone();
         let redirector_service = redirector.clone();
-        let tls_acceptor = ssl_acceptor.clone();
+        let config = config.clone();
+        // use the current certificate, it might have been reloaded in the meantime
+        let tls_acceptor = certificate.acceptor();
 
         // Wait for a new tcp connection; if it fails we cannot do much, so print an error and die
         let (tcp_stream, addr) = listener
@@ -318,7 +418,55 @@ async fn start_server(address: String, service: Router, ssl_acceptor: SslAccepto
                 handle_https_stream(tls_acceptor, addr, tcp_stream, tower_service).await;
             } else {
                 // handle HTTP connection
-                handle_http_stream(addr, tcp_stream, tower_service, redirector_service).await;
+                handle_http_stream(addr, tcp_stream, tower_service, redirector_service, config)
+                    .await;
+            }
+        });
+    }
+}
+
+/// Serves the API on a Unix listener
+///
+/// The peer credentials are added to the requests, so the requests coming from root are
+/// considered as authenticated.
+async fn serve_unix(listener: UnixListener, service: Router) {
+    loop {
+        let tower_service = service.clone();
+
+        let (unix_stream, _) = listener
+            .accept()
+            .await
+            .expect("Failed to accept connections on the Unix socket");
+
+        let credentials = match unix_stream.peer_cred() {
+            Ok(cred) => PeerCredentials {
+                uid: cred.uid(),
+                pid: cred.pid(),
+            },
+            Err(err) => {
+                tracing::error!("Could not get the peer credentials: {}", err);
+                continue;
+            }
+        };
+
+        tokio::spawn(async move {
+            let stream = TokioIo::new(unix_stream);
+            let hyper_service =
+                hyper::service::service_fn(move |mut request: Request<Incoming>| {
+                    request.extensions_mut().insert(credentials);
+                    tower_service.clone().call(request)
+                });
+
+            let ret = Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(stream, hyper_service)
+                .await;
+
+            if let Err(err) = ret {
+                tracing::error!(
+                    "Error serving connection from process {:?}: {}",
+                    credentials.pid,
+                    err
+                );
             }
         });
     }
@@ -337,30 +485,53 @@ async fn serve_command(args: ServeArgs) -> anyhow::Result<()> {
 
     write_token(TOKEN_FILE, &config.jwt_secret).context("could not create the token file")?;
 
+    // the same certificate is used on both ports
+    let certificate_source = args.certificate_source();
+    let certificate =
+        CertificateStore::new(certificate_source.load()?).context("SSL initialization failed")?;
+    let reloader = run_reloader(certificate.clone(), certificate_source, tx.clone());
+    tokio::spawn(async move {
+        if let Err(error) = reloader.await {
+            tracing::error!("Certificate reloading is not available: {}", error);
+        }
+    });
+
     let dbus = connection_to(&args.dbus_address).await?;
     let web_ui_dir = args.web_ui_dir.clone().unwrap_or(find_web_ui_dir());
-    let service = web::service(config, tx, dbus, web_ui_dir).await?;
-    // TODO: Move elsewhere? Use a singleton? (It would be nice to use the same
-    // generated self-signed certificate on both ports.)
-    let ssl_acceptor = if let Ok(ssl_acceptor) = ssl_acceptor(&args.to_certificate()?) {
-        ssl_acceptor
+    let journal = args.events_journal.as_ref().map(EventsJournal::new);
+    let recorder = args.record.as_ref().map(Recorder::new);
+    let service = web::service(
+        config.clone(),
+        tx,
+        dbus,
+        web_ui_dir,
+        certificate.clone(),
+        journal,
+        recorder,
+    )
+    .await?;
+
+    let mut listeners = systemd_listeners()?;
+    if listeners.is_empty() {
+        listeners.push(Listener::bind_tcp(&args.address).await?);
+        if let Some(address) = &args.address2 {
+            listeners.push(Listener::bind_tcp(address).await?);
+        }
+        if let Some(path) = &args.unix_socket {
+            listeners.push(Listener::bind_unix(path)?);
+        }
     } else {
-        return Err(anyhow::anyhow!("SSL initialization failed"));
-    };
-
-    let mut addresses = vec![args.address];
-
-    if let Some(a) = args.address2 {
-        addresses.push(a)
+        tracing::info!("Using {} sockets passed by systemd", listeners.len());
     }
 
-    let servers: Vec<_> = addresses
-        .iter()
-        .map(|a| {
+    let servers: Vec<_> = listeners
+        .into_iter()
+        .map(|l| {
             tokio::spawn(start_server(
-                a.clone(),
+                l,
                 service.clone(),
-                ssl_acceptor.clone(),
+                certificate.clone(),
+                config.clone(),
             ))
         })
         .collect();
@@ -18,6 +18,13 @@
 // To contact SUSE LLC about this file by physical or electronic mail, you may
 // find current contact information at www.suse.com.
 
+//! Handles the certificate used by the web server.
+//!
+//! Besides reading and generating certificates, this module allows replacing the certificate
+//! at runtime through a [CertificateStore]. See [run_reloader] for the conditions that trigger
+//! a reload.
+
+use crate::web::{Event, EventsSender};
 use anyhow;
 use gethostname::gethostname;
 use openssl::asn1::Asn1Time;
@@ -25,19 +32,29 @@ use openssl::bn::{BigNum, MsbOption};
 use openssl::hash::MessageDigest;
 use openssl::pkey::{PKey, Private};
 use openssl::rsa::Rsa;
+use openssl::ssl::{SslAcceptor, SslMethod};
 use openssl::x509::extension::{BasicConstraints, SubjectAlternativeName, SubjectKeyIdentifier};
 use openssl::x509::{X509NameBuilder, X509};
 use std::{
+    collections::BTreeSet,
     fs,
     io::{self, Write},
+    net::IpAddr,
     os::unix::fs::OpenOptionsExt,
-    path::Path,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
 };
+use tokio::signal::unix::{signal, SignalKind};
 
 const DEFAULT_CERT_DIR: &str = "/etc/agama.d/ssl";
 
+/// How often the certificate files are checked for changes.
+const FILES_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Structure to handle and store certificate and private key which is later
 /// used for establishing HTTPS connection
+#[derive(Clone)]
 pub struct Certificate {
     pub cert: X509,
     pub key: PKey<Private>,
@@ -75,14 +92,53 @@ impl Certificate {
         }
     }
 
+    /// Returns the SHA-256 fingerprint of the certificate
+    ///
+    /// The fingerprint is formatted as colon separated uppercase hexadecimal bytes, as
+    /// `openssl x509 -fingerprint -sha256` does.
+    pub fn fingerprint(&self) -> anyhow::Result<String> {
+        let digest = self.cert.digest(MessageDigest::sha256())?;
+        Ok(format_fingerprint(&digest))
+    }
+
+    /// Returns the names (DNS names and IP addresses) included in the subject alternative
+    /// name extension
+    pub fn subject_alt_names(&self) -> Vec<String> {
+        let Some(names) = self.cert.subject_alt_names() else {
+            return vec![];
+        };
+
+        names
+            .iter()
+            .filter_map(|name| {
+                if let Some(dns) = name.dnsname() {
+                    return Some(dns.to_string());
+                }
+                let ip = name.ipaddress()?;
+                let ip = match ip.len() {
+                    4 => IpAddr::from(<[u8; 4]>::try_from(ip).ok()?),
+                    16 => IpAddr::from(<[u8; 16]>::try_from(ip).ok()?),
+                    _ => return None,
+                };
+                Some(ip.to_string())
+            })
+            .collect()
+    }
+
     /// Creates a self-signed certificate
+    ///
+    /// The subject alternative names include the current host name, the default Agama names
+    /// and all the IP addresses of the system (see [SubjectNames::from_system]).
     pub fn new() -> anyhow::Result<Self> {
+        Self::with_names(&SubjectNames::from_system())
+    }
+
+    /// Creates a self-signed certificate for the given names
+    pub fn with_names(names: &SubjectNames) -> anyhow::Result<Self> {
         let rsa = Rsa::generate(2048)?;
         let key = PKey::from_rsa(rsa)?;
 
-        let hostname = gethostname()
-            .into_string()
-            .unwrap_or(String::from("localhost"));
+        let hostname = names.hostname.clone().unwrap_or(String::from("localhost"));
         let mut x509_name = X509NameBuilder::new()?;
         x509_name.append_entry_by_text("O", "Agama")?;
         x509_name.append_entry_by_text("CN", hostname.as_str())?;
@@ -107,17 +163,14 @@ impl Certificate {
 
         builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
 
-        builder.append_extension(
-            SubjectAlternativeName::new()
-                // use the default Agama host name
-                // TODO: use the gethostname crate and use the current real hostname
-                .dns("agama")
-                // use the default name for the mDNS/Avahi
-                // TODO: check which name is actually used by mDNS, to avoid
-                // conflicts it might actually use something like agama-2.local
-                .dns("agama.local")
-                .build(&builder.x509v3_context(None, None))?,
-        )?;
+        let mut san = SubjectAlternativeName::new();
+        for dns in names.dns_names() {
+            san.dns(&dns);
+        }
+        for ip in &names.addresses {
+            san.ip(&ip.to_string());
+        }
+        builder.append_extension(san.build(&builder.x509v3_context(None, None))?)?;
 
         let subject_key_identifier =
             SubjectKeyIdentifier::new().build(&builder.x509v3_context(None, None))?;
@@ -130,6 +183,241 @@ impl Certificate {
     }
 }
 
+/// Names to include in a self-signed certificate.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubjectNames {
+    /// Current host name.
+    pub hostname: Option<String>,
+    /// IP addresses of the system.
+    pub addresses: BTreeSet<IpAddr>,
+}
+
+impl SubjectNames {
+    /// Reads the host name and the IP addresses of all the network interfaces.
+    ///
+    /// Link-local IPv6 addresses are ignored because they are not usable without the scope.
+    pub fn from_system() -> Self {
+        let hostname = gethostname().into_string().ok().filter(|h| !h.is_empty());
+
+        let addresses = match nix::ifaddrs::getifaddrs() {
+            Ok(ifaddrs) => ifaddrs
+                .filter_map(|ifaddr| {
+                    let address = ifaddr.address?;
+                    if let Some(ipv4) = address.as_sockaddr_in() {
+                        return Some(IpAddr::V4(ipv4.ip()));
+                    }
+                    let ipv6 = address.as_sockaddr_in6()?.ip();
+                    // fe80::/10 (Ipv6Addr::is_unicast_link_local requires Rust 1.84)
+                    if (ipv6.segments()[0] & 0xffc0) == 0xfe80 {
+                        return None;
+                    }
+                    Some(IpAddr::V6(ipv6))
+                })
+                .collect(),
+            Err(error) => {
+                tracing::warn!("Could not read the IP addresses: {}", error);
+                BTreeSet::new()
+            }
+        };
+
+        Self {
+            hostname,
+            addresses,
+        }
+    }
+
+    /// Returns the DNS names to include in the certificate.
+    fn dns_names(&self) -> BTreeSet<String> {
+        // use the default Agama host name, the default name for the mDNS/Avahi and localhost
+        // TODO: check which name is actually used by mDNS, to avoid
+        // conflicts it might actually use something like agama-2.local
+        let mut names: BTreeSet<String> = ["agama", "agama.local", "localhost"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        if let Some(hostname) = &self.hostname {
+            names.insert(hostname.clone());
+        }
+        names
+    }
+}
+
+/// Where the certificate comes from.
+#[derive(Clone, Debug)]
+pub enum CertificateSource {
+    /// Certificate and key provided by the user (e.g., by an ACME client).
+    Files { cert: PathBuf, key: PathBuf },
+    /// Self-signed certificate generated by the web server.
+    SelfSigned,
+}
+
+impl CertificateSource {
+    /// Reads or generates the certificate.
+    ///
+    /// A generated certificate is written to the default location for the later use.
+    pub fn load(&self) -> anyhow::Result<Certificate> {
+        match self {
+            Self::Files { cert, key } => Certificate::read(cert, key),
+            Self::SelfSigned => {
+                let certificate = Certificate::new()?;
+                // for now do not care if writing self generated certificate failed or not, in
+                // the worst case we will generate new one ... which will surely be better
+                let _ = certificate.write();
+                Ok(certificate)
+            }
+        }
+    }
+
+    /// Returns the modification times of the certificate files, if any.
+    fn modified(&self) -> Option<(SystemTime, SystemTime)> {
+        let Self::Files { cert, key } = self else {
+            return None;
+        };
+        let cert = fs::metadata(cert).and_then(|m| m.modified()).ok()?;
+        let key = fs::metadata(key).and_then(|m| m.modified()).ok()?;
+        Some((cert, key))
+    }
+}
+
+struct StoredCertificate {
+    certificate: Certificate,
+    acceptor: SslAcceptor,
+}
+
+/// Holds the certificate used by the web server and allows replacing it at runtime.
+///
+/// Cloning the store is cheap and all the clones share the same certificate.
+#[derive(Clone)]
+pub struct CertificateStore {
+    inner: Arc<RwLock<StoredCertificate>>,
+}
+
+impl CertificateStore {
+    /// Creates a store for the given certificate.
+    pub fn new(certificate: Certificate) -> anyhow::Result<Self> {
+        let acceptor = ssl_acceptor(&certificate)?;
+        let inner = StoredCertificate {
+            certificate,
+            acceptor,
+        };
+        Ok(Self {
+            inner: Arc::new(RwLock::new(inner)),
+        })
+    }
+
+    /// Returns the SSL acceptor for the current certificate.
+    pub fn acceptor(&self) -> SslAcceptor {
+        self.inner.read().unwrap().acceptor.clone()
+    }
+
+    /// Returns the current certificate.
+    pub fn certificate(&self) -> Certificate {
+        self.inner.read().unwrap().certificate.clone()
+    }
+
+    /// Replaces the certificate.
+    ///
+    /// The current certificate is kept if the new one is not valid (e.g., the key does not
+    /// belong to the certificate). New connections use the new certificate.
+    pub fn replace(&self, certificate: Certificate) -> anyhow::Result<()> {
+        let acceptor = ssl_acceptor(&certificate)?;
+        let mut inner = self.inner.write().unwrap();
+        *inner = StoredCertificate {
+            certificate,
+            acceptor,
+        };
+        Ok(())
+    }
+
+    /// Reloads the certificate from the given source.
+    fn reload(&self, source: &CertificateSource) {
+        let result = source.load().and_then(|c| self.replace(c));
+        match result {
+            Ok(()) => tracing::info!("The certificate has been reloaded ({:?})", source),
+            Err(error) => tracing::error!("Could not reload the certificate: {}", error),
+        }
+    }
+}
+
+/// Builds an SSL acceptor using the given certificate.
+pub fn ssl_acceptor(certificate: &Certificate) -> anyhow::Result<SslAcceptor> {
+    let mut tls_builder = SslAcceptor::mozilla_modern_v5(SslMethod::tls_server())?;
+
+    tls_builder.set_private_key(&certificate.key)?;
+    tls_builder.set_certificate(&certificate.cert)?;
+
+    // check that the key belongs to the certificate
+    tls_builder.check_private_key()?;
+
+    Ok(tls_builder.build())
+}
+
+/// Keeps the certificate in the store up to date.
+///
+/// The certificate is reloaded when:
+///
+/// * The process receives a SIGHUP signal.
+/// * The certificate or the key files change (only for [CertificateSource::Files]).
+/// * The IP addresses or the host name change (only for [CertificateSource::SelfSigned]). The
+///   check is performed when a network change is notified through the `events` channel.
+///
+/// * `store`: certificate store to update.
+/// * `source`: where to read the certificate from.
+/// * `events`: channel to listen for network changes.
+pub async fn run_reloader(
+    store: CertificateStore,
+    source: CertificateSource,
+    events: EventsSender,
+) -> anyhow::Result<()> {
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut events = events.subscribe();
+    let mut interval = tokio::time::interval(FILES_CHECK_INTERVAL);
+    let mut modified = source.modified();
+    let mut names = SubjectNames::from_system();
+
+    loop {
+        tokio::select! {
+            _ = hangup.recv() => {
+                tracing::info!("SIGHUP received, reloading the certificate");
+                store.reload(&source);
+                modified = source.modified();
+                names = SubjectNames::from_system();
+            }
+            _ = interval.tick() => {
+                let current = source.modified();
+                if current.is_some() && current != modified {
+                    tracing::info!("The certificate files have changed");
+                    store.reload(&source);
+                    modified = current;
+                }
+            }
+            event = events.recv() => {
+                let Ok(Event::NetworkChange { .. }) = event else {
+                    continue;
+                };
+                if !matches!(source, CertificateSource::SelfSigned) {
+                    continue;
+                }
+                let current = SubjectNames::from_system();
+                if current != names {
+                    tracing::info!("The network configuration has changed");
+                    store.reload(&source);
+                    names = current;
+                }
+            }
+        }
+    }
+}
+
+/// Formats a digest as colon separated uppercase hexadecimal bytes.
+fn format_fingerprint(digest: &[u8]) -> String {
+    digest
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
 /// Writes buf into a file at path and sets the file permissions for the root only access
 fn write_and_restrict<T: AsRef<Path>>(path: T, buf: &[u8]) -> io::Result<()> {
     let mut file = fs::OpenOptions::new()
@@ -143,3 +431,34 @@ fn write_and_restrict<T: AsRef<Path>>(path: T, buf: &[u8]) -> io::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_fingerprint() {
+        assert_eq!(format_fingerprint(&[0x0a, 0xff, 0x10]), "0A:FF:10");
+    }
+
+    #[test]
+    fn test_self_signed_names() {
+        let names = SubjectNames {
+            hostname: Some("install01".to_string()),
+            addresses: BTreeSet::from([
+                "192.168.1.10".parse().unwrap(),
+                "2001:db8::1".parse().unwrap(),
+            ]),
+        };
+        let certificate = Certificate::with_names(&names).unwrap();
+
+        let alt_names = certificate.subject_alt_names();
+        assert!(alt_names.contains(&"install01".to_string()));
+        assert!(alt_names.contains(&"agama.local".to_string()));
+        assert!(alt_names.contains(&"192.168.1.10".to_string()));
+        assert!(alt_names.contains(&"2001:db8::1".to_string()));
+
+        let fingerprint = certificate.fingerprint().unwrap();
+        assert_eq!(fingerprint.len(), 32 * 3 - 1);
+    }
+}
@@ -60,9 +60,14 @@
 
 mod action;
 mod adapter;
+pub mod binding;
+pub mod diagnostics;
 pub mod error;
+pub mod import;
+pub mod install;
 pub mod model;
 mod nm;
+pub mod nmstate;
 pub mod system;
 pub mod web;
 
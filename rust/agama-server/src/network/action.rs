@@ -20,6 +20,7 @@
 
 use crate::network::model::{AccessPoint, Connection, Device};
 use agama_lib::network::types::DeviceType;
+use std::time::Duration;
 use tokio::sync::oneshot;
 use uuid::Uuid;
 
@@ -79,4 +80,14 @@ pub enum Action {
     RemoveConnection(String, Responder<Result<(), NetworkStateError>>),
     /// Apply the current configuration.
     Apply(Responder<Result<(), NetworkAdapterError>>),
+    /// Apply the current configuration, rolling it back unless it is confirmed within the given
+    /// time.
+    ApplyWithRollback(Duration, Responder<Result<(), NetworkAdapterError>>),
+    /// Confirm the configuration applied with [Action::ApplyWithRollback].
+    ConfirmApply(Responder<Result<(), NetworkAdapterError>>),
+    /// Roll back the configuration applied with [Action::ApplyWithRollback].
+    RollbackApply(Responder<Result<(), NetworkAdapterError>>),
+    /// The time to confirm the given pending configuration has expired. It is emitted by the
+    /// network system itself.
+    ApplyTimeout(u64),
 }
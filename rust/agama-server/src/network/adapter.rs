@@ -21,6 +21,7 @@
 use crate::network::{model::StateConfig, Action, NetworkState};
 use agama_lib::error::ServiceError;
 use async_trait::async_trait;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::mpsc::UnboundedSender;
 
@@ -34,6 +35,12 @@ pub enum NetworkAdapterError {
     Checkpoint(ServiceError), // only relevant for adapters that implement a checkpoint mechanism
     #[error("The network watcher cannot run: {0}")]
     Watcher(ServiceError),
+    #[error("The network backend does not support rolling back the configuration")]
+    RollbackNotSupported,
+    #[error("There is no network configuration waiting for confirmation")]
+    NoPendingCheckpoint,
+    #[error("The previous network configuration is still waiting for confirmation")]
+    PendingCheckpoint,
 }
 
 /// A trait for the ability to read/write from/to a network service.
@@ -41,6 +48,27 @@ pub enum NetworkAdapterError {
 pub trait Adapter {
     async fn read(&self, config: StateConfig) -> Result<NetworkState, NetworkAdapterError>;
     async fn write(&self, network: &NetworkState) -> Result<(), NetworkAdapterError>;
+    /// Writes the network configuration keeping a checkpoint of the previous one.
+    ///
+    /// The previous configuration is restored after the given `timeout` unless the changes are
+    /// confirmed (see [Self::confirm]).
+    async fn write_with_rollback(
+        &self,
+        _network: &NetworkState,
+        _timeout: Duration,
+    ) -> Result<(), NetworkAdapterError> {
+        Err(NetworkAdapterError::RollbackNotSupported)
+    }
+    /// Confirms the configuration written by [Self::write_with_rollback], dropping the checkpoint.
+    async fn confirm(&self) -> Result<(), NetworkAdapterError> {
+        Err(NetworkAdapterError::RollbackNotSupported)
+    }
+    /// Restores the configuration saved by [Self::write_with_rollback].
+    ///
+    /// It does nothing if the backend already restored it.
+    async fn rollback(&self) -> Result<(), NetworkAdapterError> {
+        Err(NetworkAdapterError::RollbackNotSupported)
+    }
     /// Returns the watcher, which is responsible for listening for network changes.
     fn watcher(&self) -> Option<Box<dyn Watcher + Send>> {
         None
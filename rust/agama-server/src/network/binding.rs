@@ -0,0 +1,300 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Resolves which device each connection binds to.
+//!
+//! NetworkManager decides which device a connection is activated on when it activates it, so a
+//! connection which does not match any device (or which matches more than one) goes unnoticed.
+//! [resolve] computes the binding in advance using the same rules: the interface name, the
+//! permanent MAC address and the match settings (interface names, drivers and paths, which can be
+//! globs and be prefixed with `!` to negate them or with `&` to make them mandatory).
+//!
+//! The kernel command line conditions do not depend on the device, so they are ignored.
+
+use super::model::{Connection, ConnectionConfig, Device, MacAddress};
+use crate::web::common::Issue;
+use agama_lib::network::types::DeviceType;
+use serde::Serialize;
+
+/// Issue source for the configuration (see `Agama::Issue::Source`).
+const ISSUE_SOURCE_CONFIG: u32 = 2;
+const ISSUE_SEVERITY_WARN: u32 = 0;
+const ISSUE_SEVERITY_ERROR: u32 = 1;
+
+/// Device a connection binds to.
+#[derive(Clone, Debug, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceBinding {
+    /// Connection ID.
+    pub connection: String,
+    /// Name of the device the connection binds to, if any.
+    pub device: Option<String>,
+    /// Names of all the devices the connection matches.
+    pub candidates: Vec<String>,
+}
+
+/// Result of resolving the bindings.
+#[derive(Clone, Debug, Default, Serialize, utoipa::ToSchema)]
+pub struct BindingPreview {
+    pub bindings: Vec<DeviceBinding>,
+    /// Connections which do not match any device (errors) or which match more than one
+    /// (warnings).
+    pub issues: Vec<Issue>,
+}
+
+/// Computes the device each connection binds to.
+///
+/// Only the connections for physical devices (Ethernet, wireless and InfiniBand) are considered,
+/// as virtual ones create their own device. If a connection matches several devices but it is
+/// already active on one of them, it keeps that one.
+///
+/// * `connections`: connections to bind.
+/// * `devices`: available devices.
+pub fn resolve(connections: &[Connection], devices: &[Device]) -> BindingPreview {
+    let mut preview = BindingPreview::default();
+
+    for conn in connections {
+        let Some(device_type) = physical_device_type(&conn.config) else {
+            continue;
+        };
+        if conn.is_removed() {
+            continue;
+        }
+
+        let candidates: Vec<&Device> = devices
+            .iter()
+            .filter(|d| d.type_ == device_type && matches_device(conn, d))
+            .collect();
+        let device = match candidates.as_slice() {
+            [device] => Some(device.name.clone()),
+            _ => candidates
+                .iter()
+                .find(|d| d.connection.as_ref() == Some(&conn.id))
+                .map(|d| d.name.clone()),
+        };
+
+        if candidates.is_empty() {
+            preview.issues.push(Issue::from_tuple((
+                format!("Connection '{}' does not match any device", conn.id),
+                String::new(),
+                ISSUE_SOURCE_CONFIG,
+                ISSUE_SEVERITY_ERROR,
+            )));
+        } else if device.is_none() {
+            let names: Vec<&str> = candidates.iter().map(|d| d.name.as_str()).collect();
+            preview.issues.push(Issue::from_tuple((
+                format!("Connection '{}' matches more than one device", conn.id),
+                format!("Matching devices: {}", names.join(", ")),
+                ISSUE_SOURCE_CONFIG,
+                ISSUE_SEVERITY_WARN,
+            )));
+        }
+
+        preview.bindings.push(DeviceBinding {
+            connection: conn.id.clone(),
+            device,
+            candidates: candidates.iter().map(|d| d.name.clone()).collect(),
+        });
+    }
+
+    preview
+}
+
+/// Returns the type of the device the connection binds to, if it is a physical one.
+fn physical_device_type(config: &ConnectionConfig) -> Option<DeviceType> {
+    match config {
+        ConnectionConfig::Ethernet => Some(DeviceType::Ethernet),
+        ConnectionConfig::Wireless(_) => Some(DeviceType::Wireless),
+        ConnectionConfig::Infiniband(_) => Some(DeviceType::Infiniband),
+        _ => None,
+    }
+}
+
+/// Determines whether a connection can be activated on the given device.
+fn matches_device(conn: &Connection, device: &Device) -> bool {
+    if let Some(interface) = &conn.interface {
+        if interface != &device.name {
+            return false;
+        }
+    }
+
+    let match_config = &conn.match_config;
+    if let Some(mac_address) = &match_config.mac_address {
+        if device.mac_address != MacAddress::MacAddress(*mac_address) {
+            return false;
+        }
+    }
+
+    matches_list(&match_config.interface, Some(&device.name))
+        && matches_list(&match_config.driver, device.driver.as_deref())
+        && matches_list(&match_config.path, device.path.as_deref())
+}
+
+/// Determines whether a value matches a list of patterns, following NetworkManager rules.
+///
+/// The value must match at least one of the regular patterns (if there is any) and all the
+/// mandatory ones (`&`), and it must not match any of the negated ones (`!`). An empty list
+/// matches any value.
+fn matches_list(patterns: &[String], value: Option<&str>) -> bool {
+    let value = value.unwrap_or_default();
+    let mut optional = None;
+
+    for pattern in patterns {
+        if let Some(pattern) = pattern.strip_prefix('!') {
+            if glob_match(pattern, value) {
+                return false;
+            }
+        } else if let Some(pattern) = pattern.strip_prefix('&') {
+            if !glob_match(pattern, value) {
+                return false;
+            }
+        } else {
+            let pattern = pattern.strip_prefix('|').unwrap_or(pattern);
+            optional = Some(optional.unwrap_or(false) || glob_match(pattern, value));
+        }
+    }
+
+    optional.unwrap_or(true)
+}
+
+/// Matches a value against a glob pattern supporting `*` and `?`.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack = None;
+
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, v));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                v += 1;
+            }
+            Some(c) if *c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    v = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_match, matches_list, resolve};
+    use crate::network::model::{Connection, Device, MacAddress};
+    use agama_lib::network::types::DeviceType;
+    use std::str::FromStr;
+
+    fn device(name: &str, mac: &str, driver: &str, path: &str) -> Device {
+        Device {
+            name: name.to_string(),
+            type_: DeviceType::Ethernet,
+            mac_address: MacAddress::from_str(mac).unwrap(),
+            driver: Some(driver.to_string()),
+            path: Some(path.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn devices() -> Vec<Device> {
+        vec![
+            device("eth0", "52:54:00:00:00:01", "e1000e", "pci-0000:00:19.0"),
+            device("eth1", "52:54:00:00:00:02", "ixgbe", "pci-0000:03:00.0"),
+            device("eth2", "52:54:00:00:00:03", "ixgbe", "pci-0000:03:00.1"),
+        ]
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("eth*", "eth0"));
+        assert!(glob_match("pci-0000:03:00.?", "pci-0000:03:00.1"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("eth?", "eth10"));
+        assert!(!glob_match("wl*", "eth0"));
+    }
+
+    #[test]
+    fn test_matches_list() {
+        let patterns = vec!["eth*".to_string(), "!eth1".to_string()];
+        assert!(matches_list(&patterns, Some("eth0")));
+        assert!(!matches_list(&patterns, Some("eth1")));
+        assert!(!matches_list(&patterns, Some("wlan0")));
+        assert!(matches_list(&[], Some("eth0")));
+        assert!(!matches_list(&["ixgbe".to_string()], None));
+    }
+
+    #[test]
+    fn test_resolve_by_mac_and_path() {
+        let mut by_mac = Connection::new("by-mac".to_string(), DeviceType::Ethernet);
+        by_mac.match_config.mac_address = Some("52:54:00:00:00:02".parse().unwrap());
+        let mut by_path = Connection::new("by-path".to_string(), DeviceType::Ethernet);
+        by_path.match_config.path = vec!["pci-0000:00:19.*".to_string()];
+
+        let preview = resolve(&[by_mac, by_path], &devices());
+        assert_eq!(preview.bindings[0].device, Some("eth1".to_string()));
+        assert_eq!(preview.bindings[1].device, Some("eth0".to_string()));
+        assert!(preview.issues.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_ambiguous_and_unmatched() {
+        let mut ambiguous = Connection::new("ambiguous".to_string(), DeviceType::Ethernet);
+        ambiguous.match_config.driver = vec!["ixgbe".to_string()];
+        let mut unmatched = Connection::new("unmatched".to_string(), DeviceType::Ethernet);
+        unmatched.interface = Some("eth9".to_string());
+        let wireless = Connection::new("wlan0".to_string(), DeviceType::Wireless);
+        let bond = Connection::new("bond0".to_string(), DeviceType::Bond);
+
+        let preview = resolve(&[ambiguous, unmatched, wireless, bond], &devices());
+        assert_eq!(preview.bindings.len(), 3);
+        assert_eq!(preview.bindings[0].device, None);
+        assert_eq!(preview.bindings[0].candidates, vec!["eth1", "eth2"]);
+        assert!(preview.bindings[1].candidates.is_empty());
+        assert_eq!(preview.issues.len(), 3);
+        assert_eq!(preview.issues[0].severity(), 0);
+        assert_eq!(preview.issues[1].severity(), 1);
+    }
+
+    #[test]
+    fn test_resolve_active_device() {
+        let mut devices = devices();
+        devices[2].connection = Some("ixgbe".to_string());
+        let mut conn = Connection::new("ixgbe".to_string(), DeviceType::Ethernet);
+        conn.match_config.driver = vec!["ixgbe".to_string()];
+
+        let preview = resolve(&[conn], &devices);
+        assert_eq!(preview.bindings[0].device, Some("eth2".to_string()));
+        assert!(preview.issues.is_empty());
+    }
+}
@@ -0,0 +1,587 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Checks whether the installer can reach the services it needs.
+//!
+//! A working link does not mean that the installation can proceed: the repositories or the
+//! registration server might be unreachable because of a missing gateway, a broken DNS setup, a
+//! proxy or a link with a small MTU. [Diagnostics] runs a set of checks against the servers
+//! defined by the selected product and reports the result of each one.
+
+use super::model::Device;
+use agama_lib::network::{http_proxy::PROXY_PATH, types::DeviceState, ProxySettings};
+use serde::Serialize;
+use std::{
+    fs,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use thiserror::Error;
+use tokio::{net::TcpStream, process::Command, time::timeout};
+use url::Url;
+use yaml_rust::{Yaml, YamlLoader};
+
+/// Default location of the product definitions.
+pub const PRODUCTS_DIR: &str = "/usr/share/agama/products.d";
+/// Registration server used when the product requires registration.
+pub const REGISTRATION_URL: &str = "https://scc.suse.com";
+
+/// Size of the ICMP payload to fill a 1500 bytes packet (IPv4 and ICMP headers take 28 bytes).
+const FULL_MTU_PAYLOAD: u32 = 1472;
+/// Size of the ICMP payload to fill a 1280 bytes packet (the minimum MTU for IPv6).
+const MIN_MTU_PAYLOAD: u32 = 1252;
+
+#[derive(Debug, Error)]
+pub enum DiagnosticsError {
+    #[error("Could not read {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Unknown product '{0}'")]
+    UnknownProduct(String),
+}
+
+/// Kind of check.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckKind {
+    /// Reachability of the default gateways.
+    Gateway,
+    /// Resolution of the server names.
+    Dns,
+    /// TCP connection to the servers.
+    Tcp,
+    /// Proxy detection and reachability.
+    Proxy,
+    /// Whether full-sized packets reach the servers without fragmentation.
+    Mtu,
+}
+
+/// Result of a check.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Passed,
+    Warning,
+    Failed,
+    /// The check could not be performed.
+    Skipped,
+}
+
+/// Result of checking a target.
+#[derive(Clone, Debug, Serialize, utoipa::ToSchema)]
+pub struct CheckResult {
+    pub check: CheckKind,
+    /// Checked target (gateway address, URL, host name, etc.), if any.
+    pub target: Option<String>,
+    pub status: CheckStatus,
+    /// Human readable explanation of the result.
+    pub details: String,
+}
+
+impl CheckResult {
+    fn new(check: CheckKind, target: Option<&str>, status: CheckStatus, details: String) -> Self {
+        Self {
+            check,
+            target: target.map(|t| t.to_string()),
+            status,
+            details,
+        }
+    }
+}
+
+/// Results of all the checks.
+#[derive(Clone, Debug, Default, Serialize, utoipa::ToSchema)]
+pub struct DiagnosticsReport {
+    /// Product whose servers were checked, if any.
+    pub product: Option<String>,
+    pub checks: Vec<CheckResult>,
+}
+
+/// Server to check.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    pub url: String,
+    pub host: String,
+    pub port: u16,
+}
+
+impl Target {
+    /// Builds a target from a URL, using the default port of its scheme.
+    ///
+    /// Returns `None` for URLs without a host (e.g., local directories or ISO images).
+    pub fn from_url(url: &str) -> Option<Self> {
+        let parsed = Url::parse(url).ok()?;
+        let host = parsed.host_str()?.trim_matches(['[', ']']).to_string();
+        let port = parsed.port_or_known_default()?;
+        Some(Self {
+            url: url.to_string(),
+            host,
+            port,
+        })
+    }
+}
+
+/// Returns the proxy URLs, without duplicates.
+fn proxy_urls(proxy: &ProxySettings) -> Vec<&str> {
+    let mut urls: Vec<&str> = vec![];
+    for url in [&proxy.http, &proxy.https].into_iter().flatten() {
+        if !urls.contains(&url.as_str()) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// Runs the connectivity checks.
+pub struct Diagnostics {
+    products_dir: PathBuf,
+    proxy_path: PathBuf,
+    timeout: Duration,
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self::new(PRODUCTS_DIR, PROXY_PATH)
+    }
+}
+
+impl Diagnostics {
+    /// * `products_dir`: directory containing the product definitions.
+    /// * `proxy_path`: proxy configuration file.
+    pub fn new(products_dir: impl AsRef<Path>, proxy_path: impl AsRef<Path>) -> Self {
+        Self {
+            products_dir: products_dir.as_ref().to_path_buf(),
+            proxy_path: proxy_path.as_ref().to_path_buf(),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Runs all the checks.
+    ///
+    /// * `product`: product whose repositories and registration server are checked.
+    /// * `devices`: network devices, used to find out the default gateways.
+    pub async fn run(
+        &self,
+        product: Option<&str>,
+        devices: &[Device],
+    ) -> Result<DiagnosticsReport, DiagnosticsError> {
+        let targets = match product {
+            Some(product) => product_targets(&self.products_dir, product, current_arch())?,
+            None => vec![],
+        };
+        let proxy = ProxySettings::read(&self.proxy_path).unwrap_or_default();
+
+        let mut checks = self.check_gateways(devices).await;
+        checks.extend(self.check_proxy(&proxy).await);
+
+        if targets.is_empty() {
+            checks.push(CheckResult::new(
+                CheckKind::Tcp,
+                None,
+                CheckStatus::Skipped,
+                "There are no servers to check (is a product selected?)".to_string(),
+            ));
+        }
+
+        let mut mtu_checked = false;
+        for target in &targets {
+            let (dns, addresses) = self.check_dns(target).await;
+            checks.push(dns);
+            if addresses.is_empty() {
+                continue;
+            }
+
+            let tcp = self.check_tcp(target, &addresses, proxy.is_enabled()).await;
+            let reachable = tcp.status == CheckStatus::Passed;
+            checks.push(tcp);
+
+            if reachable && !mtu_checked {
+                checks.push(self.check_mtu(&target.host).await);
+                mtu_checked = true;
+            }
+        }
+
+        Ok(DiagnosticsReport {
+            product: product.map(|p| p.to_string()),
+            checks,
+        })
+    }
+
+    /// Pings the default gateways of the active devices.
+    async fn check_gateways(&self, devices: &[Device]) -> Vec<CheckResult> {
+        let mut gateways: Vec<IpAddr> = vec![];
+        let active = devices
+            .iter()
+            .filter(|d| d.state == DeviceState::Activated)
+            .filter_map(|d| d.ip_config.as_ref());
+        for ip_config in active {
+            for gateway in [ip_config.gateway4, ip_config.gateway6]
+                .into_iter()
+                .flatten()
+            {
+                if !gateways.contains(&gateway) {
+                    gateways.push(gateway);
+                }
+            }
+        }
+
+        if gateways.is_empty() {
+            return vec![CheckResult::new(
+                CheckKind::Gateway,
+                None,
+                CheckStatus::Failed,
+                "There is no default gateway".to_string(),
+            )];
+        }
+
+        let mut checks = vec![];
+        for gateway in gateways {
+            let gateway = gateway.to_string();
+            let check = match ping(&gateway, None).await {
+                Ok(true) => CheckResult::new(
+                    CheckKind::Gateway,
+                    Some(&gateway),
+                    CheckStatus::Passed,
+                    "The gateway answers to ping".to_string(),
+                ),
+                Ok(false) => CheckResult::new(
+                    CheckKind::Gateway,
+                    Some(&gateway),
+                    CheckStatus::Failed,
+                    "The gateway does not answer to ping".to_string(),
+                ),
+                Err(error) => CheckResult::new(
+                    CheckKind::Gateway,
+                    Some(&gateway),
+                    CheckStatus::Skipped,
+                    format!("Could not run ping: {}", error),
+                ),
+            };
+            checks.push(check);
+        }
+        checks
+    }
+
+    /// Reports the configured proxies and whether they accept connections.
+    async fn check_proxy(&self, proxy: &ProxySettings) -> Vec<CheckResult> {
+        if !proxy.is_enabled() {
+            return vec![CheckResult::new(
+                CheckKind::Proxy,
+                None,
+                CheckStatus::Passed,
+                "No proxy is configured".to_string(),
+            )];
+        }
+
+        let mut checks = vec![];
+        for url in proxy_urls(proxy) {
+            let Some(target) = Target::from_url(url) else {
+                checks.push(CheckResult::new(
+                    CheckKind::Proxy,
+                    Some(url),
+                    CheckStatus::Failed,
+                    "The proxy URL is not valid".to_string(),
+                ));
+                continue;
+            };
+
+            let (dns, addresses) = self.check_dns(&target).await;
+            let check = if addresses.is_empty() {
+                CheckResult {
+                    check: CheckKind::Proxy,
+                    ..dns
+                }
+            } else {
+                CheckResult {
+                    check: CheckKind::Proxy,
+                    ..self.check_tcp(&target, &addresses, false).await
+                }
+            };
+            checks.push(check);
+        }
+        checks
+    }
+
+    /// Resolves the name of the target, returning the check result and the found addresses.
+    async fn check_dns(&self, target: &Target) -> (CheckResult, Vec<SocketAddr>) {
+        let lookup = tokio::net::lookup_host((target.host.as_str(), target.port));
+        let (status, details, addresses) = match timeout(self.timeout, lookup).await {
+            Ok(Ok(addresses)) => {
+                let addresses: Vec<SocketAddr> = addresses.collect();
+                let ips: Vec<String> = addresses.iter().map(|a| a.ip().to_string()).collect();
+                (
+                    CheckStatus::Passed,
+                    format!("Resolved to {}", ips.join(", ")),
+                    addresses,
+                )
+            }
+            Ok(Err(error)) => (
+                CheckStatus::Failed,
+                format!("Could not resolve the name: {}", error),
+                vec![],
+            ),
+            Err(_) => (
+                CheckStatus::Failed,
+                "Timed out while resolving the name".to_string(),
+                vec![],
+            ),
+        };
+        let check = CheckResult::new(CheckKind::Dns, Some(&target.host), status, details);
+        (check, addresses)
+    }
+
+    /// Opens a TCP connection to the target.
+    ///
+    /// * `target`: server to connect to.
+    /// * `addresses`: resolved addresses of the server.
+    /// * `proxied`: whether a proxy is configured. In that case, a failure is just a warning, as
+    ///   the server might be reachable only through the proxy.
+    async fn check_tcp(
+        &self,
+        target: &Target,
+        addresses: &[SocketAddr],
+        proxied: bool,
+    ) -> CheckResult {
+        let connection = TcpStream::connect(addresses);
+        let error = match timeout(self.timeout, connection).await {
+            Ok(Ok(_)) => {
+                return CheckResult::new(
+                    CheckKind::Tcp,
+                    Some(&target.url),
+                    CheckStatus::Passed,
+                    format!("Connected to {}:{}", target.host, target.port),
+                )
+            }
+            Ok(Err(error)) => error.to_string(),
+            Err(_) => "timed out".to_string(),
+        };
+
+        let (status, hint) = if proxied {
+            (
+                CheckStatus::Warning,
+                " (it might be reachable through the proxy)",
+            )
+        } else {
+            (CheckStatus::Failed, "")
+        };
+        CheckResult::new(
+            CheckKind::Tcp,
+            Some(&target.url),
+            status,
+            format!(
+                "Could not connect to {}:{}: {}{}",
+                target.host, target.port, error, hint
+            ),
+        )
+    }
+
+    /// Checks whether full-sized packets reach the host without being fragmented.
+    ///
+    /// If they do not, it checks whether packets of the minimum IPv6 MTU do, to tell a small path
+    /// MTU apart from a host which does not answer to ping.
+    async fn check_mtu(&self, host: &str) -> CheckResult {
+        let (status, details) = match ping(host, Some(FULL_MTU_PAYLOAD)).await {
+            Ok(true) => (
+                CheckStatus::Passed,
+                "Packets of 1500 bytes are not fragmented".to_string(),
+            ),
+            Ok(false) => match ping(host, Some(MIN_MTU_PAYLOAD)).await {
+                Ok(true) => (
+                    CheckStatus::Warning,
+                    "The path MTU is lower than 1500 bytes".to_string(),
+                ),
+                Ok(false) => (
+                    CheckStatus::Skipped,
+                    "The host does not answer to ping".to_string(),
+                ),
+                Err(error) => (
+                    CheckStatus::Skipped,
+                    format!("Could not run ping: {}", error),
+                ),
+            },
+            Err(error) => (
+                CheckStatus::Skipped,
+                format!("Could not run ping: {}", error),
+            ),
+        };
+        CheckResult::new(CheckKind::Mtu, Some(host), status, details)
+    }
+}
+
+/// Sends an ICMP echo request to the host and returns whether it answered.
+///
+/// * `host`: host name or IP address.
+/// * `payload`: if given, payload size of a packet that must not be fragmented.
+async fn ping(host: &str, payload: Option<u32>) -> std::io::Result<bool> {
+    let mut command = Command::new("ping");
+    command.args(["-c", "1", "-W", "2"]);
+    if let Some(payload) = payload {
+        command.args(["-M", "do", "-s", &payload.to_string()]);
+    }
+    let output = command.arg(host).output().await?;
+    Ok(output.status.success())
+}
+
+/// Returns the name of the current architecture as used in the product definitions.
+fn current_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "s390x" => "s390",
+        "powerpc64" => "ppc",
+        arch => arch,
+    }
+}
+
+/// Returns the servers the given product needs: its installation repositories for the given
+/// architecture and, if it requires registration, the registration server.
+///
+/// * `products_dir`: directory containing the product definitions.
+/// * `product`: product ID.
+/// * `arch`: architecture, as used in the product definitions.
+pub fn product_targets(
+    products_dir: &Path,
+    product: &str,
+    arch: &str,
+) -> Result<Vec<Target>, DiagnosticsError> {
+    let io_error = |e| DiagnosticsError::Io(products_dir.to_path_buf(), e);
+    let mut entries: Vec<PathBuf> = fs::read_dir(products_dir)
+        .map_err(io_error)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "yaml" || e == "yml"))
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        let content =
+            fs::read_to_string(&path).map_err(|e| DiagnosticsError::Io(path.clone(), e))?;
+        let Ok(docs) = YamlLoader::load_from_str(&content) else {
+            log::warn!("Could not parse the product definition {}", path.display());
+            continue;
+        };
+        let Some(doc) = docs.first() else {
+            continue;
+        };
+        if doc["id"].as_str() == Some(product) {
+            return Ok(targets_from_definition(doc, arch));
+        }
+    }
+
+    Err(DiagnosticsError::UnknownProduct(product.to_string()))
+}
+
+/// Extracts the servers from a product definition.
+fn targets_from_definition(doc: &Yaml, arch: &str) -> Vec<Target> {
+    let mut targets = vec![];
+    let repositories = doc["software"]["installation_repositories"]
+        .as_vec()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    for repository in repositories {
+        let matches_arch = match repository["archs"].as_str() {
+            Some(archs) => archs.split(',').any(|a| a.trim() == arch),
+            None => true,
+        };
+        if !matches_arch {
+            continue;
+        }
+        if let Some(target) = repository["url"].as_str().and_then(Target::from_url) {
+            targets.push(target);
+        }
+    }
+
+    if matches!(doc["registration"].as_str(), Some("mandatory" | "optional")) {
+        targets.extend(Target::from_url(REGISTRATION_URL));
+    }
+
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{product_targets, CheckKind, CheckStatus, Diagnostics, DiagnosticsError, Target};
+    use std::fs;
+    use tokio::net::TcpListener;
+
+    const PRODUCT: &str = r#"
+id: SLES
+registration: mandatory
+software:
+  installation_repositories:
+    - url: https://download.example.com/x86_64/
+      archs: x86_64
+    - url: http://download.example.com/multi/
+      archs: aarch64, ppc
+    - url: dir:///run/install/repo
+"#;
+
+    #[test]
+    fn test_target_from_url() {
+        let target = Target::from_url("https://download.opensuse.org/tumbleweed/").unwrap();
+        assert_eq!(target.host, "download.opensuse.org");
+        assert_eq!(target.port, 443);
+
+        let target = Target::from_url("http://[fd00::1]:8080/repo").unwrap();
+        assert_eq!(target.host, "fd00::1");
+        assert_eq!(target.port, 8080);
+
+        assert!(Target::from_url("dir:///run/install/repo").is_none());
+    }
+
+    #[test]
+    fn test_product_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("sles.yaml"), PRODUCT).unwrap();
+        fs::write(dir.path().join("other.yaml"), "id: Other\n").unwrap();
+
+        let targets = product_targets(dir.path(), "SLES", "aarch64").unwrap();
+        let hosts: Vec<(&str, u16)> = targets.iter().map(|t| (t.host.as_str(), t.port)).collect();
+        assert_eq!(
+            hosts,
+            vec![("download.example.com", 80), ("scc.suse.com", 443)]
+        );
+
+        let targets = product_targets(dir.path(), "Other", "x86_64").unwrap();
+        assert!(targets.is_empty());
+
+        assert!(matches!(
+            product_targets(dir.path(), "Unknown", "x86_64"),
+            Err(DiagnosticsError::UnknownProduct(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_check_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let target = Target::from_url(&format!("http://127.0.0.1:{}/", address.port())).unwrap();
+
+        let diagnostics = Diagnostics::default();
+        let (dns, addresses) = diagnostics.check_dns(&target).await;
+        assert_eq!(dns.status, CheckStatus::Passed);
+
+        let check = diagnostics.check_tcp(&target, &addresses, false).await;
+        assert_eq!(check.check, CheckKind::Tcp);
+        assert_eq!(check.status, CheckStatus::Passed);
+
+        drop(listener);
+        let check = diagnostics.check_tcp(&target, &addresses, true).await;
+        assert_eq!(check.status, CheckStatus::Warning);
+    }
+}
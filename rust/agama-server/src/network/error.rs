@@ -70,6 +70,22 @@ pub enum NetworkStateError {
     InvalidWirelessBand(String),
     #[error("Invalid bssid: '{0}'")]
     InvalidBssid(String),
+    #[error("Invalid MAC address: '{0}'")]
+    InvalidMacAddress(String),
+    #[error("Invalid VLAN protocol: '{0}'")]
+    InvalidVlanProtocol(String),
+    #[error("Invalid InfiniBand transport mode: '{0}'")]
+    InvalidInfinibandTransportMode(String),
+    #[error("Invalid TUN mode: '{0}'")]
+    InvalidTunMode(String),
+    #[error("Invalid WireGuard endpoint: '{0}'")]
+    InvalidWireguardEndpoint(String),
+    #[error("Invalid host name: '{0}'")]
+    InvalidHostname(String),
+    #[error("Invalid search domain: '{0}'")]
+    InvalidSearchDomain(String),
+    #[error("The global search domains require global name servers")]
+    MissingGlobalNameservers,
 }
 
 impl From<NetworkStateError> for zbus::fdo::Error {
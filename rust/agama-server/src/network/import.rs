@@ -0,0 +1,303 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Imports the network configuration used to boot the installer.
+//!
+//! The network of the installer can be set up using the kernel command line (the dracut `ip=`,
+//! `nameserver=`, `vlan=` and `bond=` parameters or the linuxrc `ifcfg=` one) or the legacy
+//! `ifcfg-*` files (as used by wicked). This module translates that configuration to
+//! [Connection] objects, so it can be reviewed and written to NetworkManager.
+//!
+//! The `ifcfg-*` files are read first, so the kernel command line takes precedence. The
+//! connections are identified by their interface name.
+
+mod cmdline;
+mod ifcfg;
+
+use super::model::{Connection, Ipv4Method, Ipv6Method};
+use cidr::IpInet;
+use std::{
+    fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// Default location of the kernel command line.
+pub const CMDLINE_PATH: &str = "/proc/cmdline";
+/// Default location of the `ifcfg-*` files.
+pub const IFCFG_DIR: &str = "/etc/sysconfig/network";
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Could not read {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+}
+
+/// Network configuration found by the importer.
+#[derive(Debug, Default)]
+pub struct ImportedNetwork {
+    /// Imported connections.
+    pub connections: Vec<Connection>,
+    /// Host name, if any.
+    pub hostname: Option<String>,
+    /// Settings which could not be imported.
+    pub warnings: Vec<String>,
+}
+
+impl ImportedNetwork {
+    /// Returns the connection for the given interface.
+    ///
+    /// * `name`: interface name (or pattern, if it is not bound to an interface).
+    pub fn get_connection(&self, name: &str) -> Option<&Connection> {
+        self.connections.iter().find(|c| connection_name(c) == name)
+    }
+
+    /// Returns the connection for the given interface, adding it if it does not exist.
+    ///
+    /// * `name`: interface name (or pattern, if it is not bound to an interface).
+    fn connection_mut(&mut self, name: &str) -> &mut Connection {
+        let position = match self
+            .connections
+            .iter()
+            .position(|c| connection_name(c) == name)
+        {
+            Some(position) => position,
+            None => {
+                self.connections.push(Connection {
+                    id: name.to_string(),
+                    interface: Some(name.to_string()),
+                    ..Default::default()
+                });
+                self.connections.len() - 1
+            }
+        };
+        &mut self.connections[position]
+    }
+
+    /// Makes the given interfaces the ports of a controller, adding them if needed.
+    ///
+    /// * `controller`: controller interface name.
+    /// * `ports`: ports interface names.
+    fn set_ports(&mut self, controller: &str, ports: &[String]) {
+        let uuid = self.connection_mut(controller).uuid;
+        for port in ports {
+            let conn = self.connection_mut(port);
+            conn.controller = Some(uuid);
+            conn.ip_config.method4 = Ipv4Method::Disabled;
+            conn.ip_config.method6 = Ipv6Method::Disabled;
+        }
+    }
+
+    /// Adds the name servers and the search domains to all the connections but the ports.
+    fn add_dns(&mut self, nameservers: &[IpAddr], searchlist: &[String]) {
+        for conn in self.connections.iter_mut() {
+            if conn.controller.is_some() {
+                continue;
+            }
+            let ip_config = &mut conn.ip_config;
+            for nameserver in nameservers {
+                if !ip_config.nameservers.contains(nameserver) {
+                    ip_config.nameservers.push(*nameserver);
+                }
+            }
+            for domain in searchlist {
+                if !ip_config.dns_searchlist.contains(domain) {
+                    ip_config.dns_searchlist.push(domain.clone());
+                }
+            }
+        }
+    }
+
+    fn warn<T: Into<String>>(&mut self, warning: T) {
+        let warning = warning.into();
+        log::warn!("Network import: {}", &warning);
+        self.warnings.push(warning);
+    }
+}
+
+fn connection_name(conn: &Connection) -> &str {
+    conn.interface.as_deref().unwrap_or(&conn.id)
+}
+
+/// Reads the network configuration from the kernel command line and the `ifcfg-*` files.
+pub struct NetworkImporter {
+    cmdline_path: PathBuf,
+    ifcfg_dir: PathBuf,
+}
+
+impl Default for NetworkImporter {
+    fn default() -> Self {
+        Self::new(CMDLINE_PATH, IFCFG_DIR)
+    }
+}
+
+impl NetworkImporter {
+    /// Creates a new importer.
+    ///
+    /// * `cmdline_path`: file containing the kernel command line.
+    /// * `ifcfg_dir`: directory containing the `ifcfg-*` files.
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(cmdline_path: P, ifcfg_dir: Q) -> Self {
+        Self {
+            cmdline_path: cmdline_path.as_ref().to_path_buf(),
+            ifcfg_dir: ifcfg_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Imports the network configuration.
+    ///
+    /// Missing files are ignored.
+    pub fn import(&self) -> Result<ImportedNetwork, ImportError> {
+        let mut network = ImportedNetwork::default();
+
+        if self.ifcfg_dir.is_dir() {
+            ifcfg::import(&self.ifcfg_dir, &mut network)?;
+        }
+
+        if self.cmdline_path.exists() {
+            let cmdline = fs::read_to_string(&self.cmdline_path)
+                .map_err(|e| ImportError::Io(self.cmdline_path.clone(), e))?;
+            cmdline::import(&cmdline, &mut network);
+        }
+
+        Ok(network)
+    }
+}
+
+/// Parses an address and its network mask.
+///
+/// * `address`: IP address, optionally including the prefix length (e.g., `192.168.1.1/24`).
+/// * `netmask`: prefix length or IPv4 network mask (e.g., `255.255.255.0`). It is ignored if the
+///   address includes the prefix length. If both are missing, it uses a host prefix.
+fn parse_inet(address: &str, netmask: Option<&str>) -> Option<IpInet> {
+    if address.contains('/') {
+        return IpInet::from_str(address).ok();
+    }
+
+    let address = IpAddr::from_str(address).ok()?;
+    let prefix = match netmask.filter(|m| !m.is_empty()) {
+        None => match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        },
+        Some(netmask) => match netmask.parse::<u8>() {
+            Ok(prefix) => prefix,
+            Err(_) => {
+                let IpAddr::V4(mask) = IpAddr::from_str(netmask).ok()? else {
+                    return None;
+                };
+                let mask = u32::from(mask);
+                if mask.leading_ones() + mask.trailing_zeros() != 32 {
+                    return None;
+                }
+                mask.leading_ones() as u8
+            }
+        },
+    };
+    IpInet::new(address, prefix).ok()
+}
+
+/// Adds a static address to a connection, setting the configuration method of its family.
+fn add_address(conn: &mut Connection, address: IpInet) {
+    if address.is_ipv4() {
+        conn.ip_config.method4 = Ipv4Method::Manual;
+    } else {
+        conn.ip_config.method6 = Ipv6Method::Manual;
+    }
+    if !conn.ip_config.addresses.contains(&address) {
+        conn.ip_config.addresses.push(address);
+    }
+}
+
+/// Sets the gateway of the corresponding family.
+fn set_gateway(conn: &mut Connection, gateway: IpAddr) {
+    match gateway {
+        IpAddr::V4(_) => conn.ip_config.gateway4 = Some(gateway),
+        IpAddr::V6(_) => conn.ip_config.gateway6 = Some(gateway),
+    }
+}
+
+/// Returns the VLAN ID from the name of the interface (e.g., `eth0.5` or `vlan0005`).
+fn vlan_id_from_name(name: &str) -> Option<u32> {
+    let id = match name.rsplit_once('.') {
+        Some((_, id)) => id,
+        None => name.strip_prefix("vlan")?,
+    };
+    id.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_inet, vlan_id_from_name, NetworkImporter};
+    use crate::network::model::{ConnectionConfig, Ipv4Method};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_inet() {
+        let inet = parse_inet("192.168.1.10", Some("255.255.255.0")).unwrap();
+        assert_eq!(inet.to_string(), "192.168.1.10/24");
+
+        let inet = parse_inet("192.168.1.10/16", Some("24")).unwrap();
+        assert_eq!(inet.to_string(), "192.168.1.10/16");
+
+        let inet = parse_inet("2001:db8::1", Some("64")).unwrap();
+        assert_eq!(inet.to_string(), "2001:db8::1/64");
+
+        let inet = parse_inet("10.0.0.1", None).unwrap();
+        assert_eq!(inet.network_length(), 32);
+
+        assert!(parse_inet("192.168.1.10", Some("255.0.255.0")).is_none());
+        assert!(parse_inet("eth0", None).is_none());
+    }
+
+    #[test]
+    fn test_vlan_id_from_name() {
+        assert_eq!(vlan_id_from_name("eth0.5"), Some(5));
+        assert_eq!(vlan_id_from_name("vlan0010"), Some(10));
+        assert_eq!(vlan_id_from_name("eth0"), None);
+    }
+
+    #[test]
+    fn test_cmdline_takes_precedence() {
+        let tmp = TempDir::new().unwrap();
+        let cmdline = tmp.path().join("cmdline");
+        fs::write(&cmdline, "root=/dev/sda1 ip=eth0:dhcp\n").unwrap();
+        fs::write(
+            tmp.path().join("ifcfg-eth0"),
+            "BOOTPROTO='static'\nIPADDR='192.168.1.10/24'\n",
+        )
+        .unwrap();
+
+        let network = NetworkImporter::new(&cmdline, tmp.path()).import().unwrap();
+        let eth0 = network.get_connection("eth0").unwrap();
+        assert_eq!(eth0.ip_config.method4, Ipv4Method::Auto);
+        assert!(matches!(eth0.config, ConnectionConfig::Ethernet));
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let tmp = TempDir::new().unwrap();
+        let importer = NetworkImporter::new(tmp.path().join("cmdline"), tmp.path().join("none"));
+        let network = importer.import().unwrap();
+        assert!(network.connections.is_empty());
+    }
+}
@@ -0,0 +1,472 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Imports the network configuration from the kernel command line.
+//!
+//! It supports the dracut `ip=`, `nameserver=`, `vlan=` and `bond=` parameters (see
+//! dracut.cmdline(7)) and the linuxrc `ifcfg=` one.
+
+use super::{add_address, ifcfg, parse_inet, set_gateway, vlan_id_from_name, ImportedNetwork};
+use crate::network::model::{
+    BondConfig, BondOptions, Connection, ConnectionConfig, Ipv4Method, Ipv6Method, MacAddress,
+    VlanConfig,
+};
+use agama_lib::network::types::BondMode;
+use std::{collections::HashMap, net::IpAddr, str::FromStr};
+
+/// Imports the network configuration from the kernel command line.
+///
+/// * `cmdline`: kernel command line.
+/// * `network`: imported network configuration.
+pub fn import(cmdline: &str, network: &mut ImportedNetwork) {
+    let params = kernel_params(cmdline);
+    let mut nameservers = vec![];
+
+    for (name, value) in &params {
+        match name.as_str() {
+            "bond" => import_bond(value, network),
+            "vlan" => import_vlan(value, network),
+            "ip" => import_ip(value, network),
+            "ifcfg" => import_ifcfg(value, network),
+            "nameserver" => match IpAddr::from_str(value.trim_matches(['[', ']'])) {
+                Ok(nameserver) => nameservers.push(nameserver),
+                Err(_) => network.warn(format!("Invalid name server: {value}")),
+            },
+            _ => {}
+        }
+    }
+
+    network.add_dns(&nameservers, &[]);
+}
+
+/// Splits the kernel command line into parameters, honoring the double quotes.
+fn kernel_params(cmdline: &str) -> Vec<(String, String)> {
+    let mut params = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in cmdline.chars().chain([' ']) {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if let Some((name, value)) = current.split_once('=') {
+                    params.push((name.to_string(), value.to_string()));
+                }
+                current.clear();
+            }
+            c => current.push(c),
+        }
+    }
+    params
+}
+
+/// Splits a dracut parameter into its colon separated fields.
+///
+/// IPv6 addresses are enclosed in brackets, so colons within brackets are kept.
+fn split_fields(value: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut current = String::new();
+    let mut in_brackets = false;
+
+    for c in value.chars() {
+        match c {
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            ':' if !in_brackets => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// Imports an `ip=` parameter.
+///
+/// * `ip=<interface>:<autoconf>[:[<mtu>][:<macaddr>]]`
+/// * `ip=<client-IP>:[<peer>]:<gateway-IP>:<netmask>:<hostname>:<interface>:<autoconf>`
+///   followed by `[:[<mtu>][:<macaddr>]]` or `[:[<dns1>][:<dns2>]]`.
+///
+/// The global form (e.g., `ip=dhcp`) does not define any connection, as NetworkManager already
+/// configures all the interfaces automatically.
+fn import_ip(value: &str, network: &mut ImportedNetwork) {
+    let fields = split_fields(value);
+    if fields.len() == 1 {
+        return;
+    }
+
+    let is_static = IpAddr::from_str(&fields[0]).is_ok() || fields[0].contains('/');
+    let (interface, autoconf, rest) = if is_static {
+        if fields.len() < 7 || fields[5].is_empty() {
+            network.warn(format!("Unsupported ip= parameter: {value}"));
+            return;
+        }
+        (&fields[5], &fields[6], &fields[7..])
+    } else {
+        (&fields[0], &fields[1], &fields[2..])
+    };
+
+    let mut warnings = vec![];
+    let conn = network.connection_mut(interface);
+    if let Err(method) = set_autoconf(conn, autoconf) {
+        warnings.push(format!(
+            "Unsupported configuration method '{method}' for {interface}"
+        ));
+    }
+
+    if is_static {
+        match parse_inet(&fields[0], Some(&fields[3])) {
+            Some(address) => add_address(conn, address),
+            None => warnings.push(format!("Invalid address for {interface}: {value}")),
+        }
+        if let Ok(gateway) = IpAddr::from_str(&fields[2]) {
+            set_gateway(conn, gateway);
+        }
+    }
+
+    let first = rest.first().map(String::as_str).unwrap_or_default();
+    if IpAddr::from_str(first).is_ok() {
+        for dns in rest.iter().filter_map(|d| IpAddr::from_str(d).ok()) {
+            conn.ip_config.nameservers.push(dns);
+        }
+    } else {
+        if let Ok(mtu) = first.parse::<u32>() {
+            conn.mtu = mtu;
+        }
+        let mac = rest.get(1..).unwrap_or_default().join(":");
+        if !mac.is_empty() {
+            match MacAddress::from_str(&mac) {
+                Ok(mac) => conn.mac_address = mac,
+                Err(_) => warnings.push(format!("Invalid MAC address for {interface}: {mac}")),
+            }
+        }
+    }
+
+    if is_static && !fields[4].is_empty() {
+        network.hostname = Some(fields[4].clone());
+    }
+    for warning in warnings {
+        network.warn(warning);
+    }
+}
+
+/// Sets the configuration methods from a dracut autoconf value (e.g., `dhcp,dhcp6`).
+///
+/// Returns the first unsupported method as an error.
+fn set_autoconf(conn: &mut Connection, autoconf: &str) -> Result<(), String> {
+    for method in autoconf.split(',') {
+        match method {
+            "dhcp" | "on" | "any" => {
+                conn.ip_config.method4 = Ipv4Method::Auto;
+                conn.ip_config.method6 = Ipv6Method::Auto;
+            }
+            "dhcp6" => conn.ip_config.method6 = Ipv6Method::Dhcp,
+            "auto6" => conn.ip_config.method6 = Ipv6Method::Auto,
+            "link6" => conn.ip_config.method6 = Ipv6Method::LinkLocal,
+            "none" | "off" | "" => {}
+            _ => return Err(method.to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// Imports a `bond=<bondname>[:<ports>[:<options>[:<mtu>]]]` parameter.
+///
+/// The ports and the options are comma separated (e.g., `mode=active-backup,miimon=100`).
+fn import_bond(value: &str, network: &mut ImportedNetwork) {
+    let fields: Vec<&str> = value.split(':').collect();
+    let name = fields.first().filter(|n| !n.is_empty()).unwrap_or(&"bond0");
+    let ports: Vec<String> = fields
+        .get(1)
+        .unwrap_or(&"eth0,eth1")
+        .split(',')
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect();
+    let options: Vec<&str> = fields
+        .get(2)
+        .map(|o| o.split(',').collect())
+        .unwrap_or_default();
+
+    match bond_config(&options) {
+        Ok(config) => {
+            let conn = network.connection_mut(name);
+            conn.config = ConnectionConfig::Bond(config);
+            if let Some(mtu) = fields.get(3).and_then(|m| m.parse().ok()) {
+                conn.mtu = mtu;
+            }
+            network.set_ports(name, &ports);
+        }
+        Err(error) => network.warn(format!("Invalid bond options for {name}: {error}")),
+    }
+}
+
+/// Builds the bond configuration from a list of `key=value` options.
+///
+/// The `mode` option can be a name (e.g., `active-backup`) or a number (e.g., `1`).
+pub(super) fn bond_config(options: &[&str]) -> Result<BondConfig, String> {
+    let mut config = BondConfig::default();
+    let mut bond_options = HashMap::new();
+
+    for option in options.iter().filter(|o| !o.is_empty()) {
+        let Some((key, value)) = option.split_once('=') else {
+            return Err(option.to_string());
+        };
+        if key == "mode" {
+            let mode = match value.parse::<u8>() {
+                Ok(number) => BondMode::try_from(number),
+                Err(_) => BondMode::try_from(value),
+            };
+            config.mode = mode.map_err(|_| option.to_string())?;
+        } else {
+            bond_options.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    config.options = BondOptions(bond_options);
+    Ok(config)
+}
+
+/// Imports a `vlan=<vlanname>:<parent>` parameter.
+fn import_vlan(value: &str, network: &mut ImportedNetwork) {
+    let Some((name, parent)) = value.split_once(':') else {
+        network.warn(format!("Invalid vlan= parameter: {value}"));
+        return;
+    };
+    let Some(id) = vlan_id_from_name(name) else {
+        network.warn(format!("Could not find out the VLAN ID of {name}"));
+        return;
+    };
+
+    let conn = network.connection_mut(name);
+    conn.config = ConnectionConfig::Vlan(VlanConfig {
+        parent: parent.to_string(),
+        id,
+        ..Default::default()
+    });
+}
+
+/// Imports a linuxrc `ifcfg=<interface>=<config>` parameter.
+///
+/// The configuration can be:
+///
+/// * `dhcp`, `dhcp4` or `dhcp6`.
+/// * `<addresses>[,<gateway>[,<nameservers>[,<domains>]]]`, where the lists are space separated.
+/// * A comma separated list of `ifcfg` variables (e.g., `BOOTPROTO=static,IPADDR=...`).
+///
+/// The interface can be a name or a pattern (e.g., `eth*`).
+fn import_ifcfg(value: &str, network: &mut ImportedNetwork) {
+    let Some((interface, config)) = value.split_once('=') else {
+        network.warn(format!("Invalid ifcfg= parameter: {value}"));
+        return;
+    };
+
+    if macaddr::MacAddr6::from_str(interface).is_ok() {
+        network.warn(format!(
+            "Matching interfaces by MAC address is not supported: {value}"
+        ));
+        return;
+    }
+
+    let is_pattern = interface.contains(['*', '?']);
+    let conn = network.connection_mut(interface);
+    if is_pattern {
+        conn.interface = None;
+        conn.match_config.interface = vec![interface.to_string()];
+    }
+
+    if config.contains('=') {
+        let variables = config
+            .split(',')
+            .filter_map(|v| v.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.trim_matches(['"', '\'']).to_string()))
+            .collect();
+        ifcfg::apply_variables(interface, &variables, network);
+        return;
+    }
+
+    match config {
+        "dhcp" => {
+            conn.ip_config.method4 = Ipv4Method::Auto;
+            conn.ip_config.method6 = Ipv6Method::Auto;
+        }
+        "dhcp4" => conn.ip_config.method4 = Ipv4Method::Auto,
+        "dhcp6" => conn.ip_config.method6 = Ipv6Method::Dhcp,
+        _ => {
+            let fields: Vec<&str> = config.split(',').collect();
+            let mut invalid = vec![];
+            for address in fields[0].split_whitespace() {
+                let netmask = if address.contains(':') { "64" } else { "24" };
+                match parse_inet(address, Some(netmask)) {
+                    Some(address) => add_address(conn, address),
+                    None => invalid.push(address.to_string()),
+                }
+            }
+            if let Some(gateway) = fields.get(1).and_then(|g| IpAddr::from_str(g).ok()) {
+                set_gateway(conn, gateway);
+            }
+            for nameserver in fields.get(2).unwrap_or(&"").split_whitespace() {
+                match IpAddr::from_str(nameserver) {
+                    Ok(nameserver) => conn.ip_config.nameservers.push(nameserver),
+                    Err(_) => invalid.push(nameserver.to_string()),
+                }
+            }
+            for domain in fields.get(3).unwrap_or(&"").split_whitespace() {
+                conn.ip_config.dns_searchlist.push(domain.to_string());
+            }
+            for value in invalid {
+                network.warn(format!("Invalid address for {interface}: {value}"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{import, kernel_params, split_fields};
+    use crate::network::{
+        import::ImportedNetwork,
+        model::{ConnectionConfig, Ipv4Method, Ipv6Method},
+    };
+    use agama_lib::network::types::BondMode;
+
+    fn import_cmdline(cmdline: &str) -> ImportedNetwork {
+        let mut network = ImportedNetwork::default();
+        import(cmdline, &mut network);
+        network
+    }
+
+    #[test]
+    fn test_kernel_params() {
+        let params =
+            kernel_params(r#"quiet ifcfg="eth0=10.0.0.2/8,10.0.0.1,1.1.1.1 8.8.8.8" ip=eth1:dhcp"#);
+        assert_eq!(
+            params,
+            vec![
+                (
+                    "ifcfg".to_string(),
+                    "eth0=10.0.0.2/8,10.0.0.1,1.1.1.1 8.8.8.8".to_string()
+                ),
+                ("ip".to_string(), "eth1:dhcp".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_fields() {
+        let fields = split_fields("[2001:db8::2]::[2001:db8::1]:64::eth0:none");
+        assert_eq!(fields[0], "2001:db8::2");
+        assert_eq!(fields[2], "2001:db8::1");
+        assert_eq!(fields[5], "eth0");
+    }
+
+    #[test]
+    fn test_import_static_ip() {
+        let network = import_cmdline(
+            "ip=192.168.1.10::192.168.1.1:255.255.255.0:agama:eth0:none:1500:52:54:00:12:34:56 \
+             nameserver=192.168.1.2",
+        );
+
+        let eth0 = network.get_connection("eth0").unwrap();
+        assert_eq!(eth0.ip_config.method4, Ipv4Method::Manual);
+        assert_eq!(eth0.ip_config.addresses[0].to_string(), "192.168.1.10/24");
+        assert_eq!(
+            eth0.ip_config.gateway4,
+            Some("192.168.1.1".parse().unwrap())
+        );
+        assert_eq!(
+            eth0.ip_config.nameservers,
+            vec!["192.168.1.2".parse::<std::net::IpAddr>().unwrap()]
+        );
+        assert_eq!(eth0.mtu, 1500);
+        assert_eq!(eth0.mac_address.to_string(), "52:54:00:12:34:56");
+        assert_eq!(network.hostname, Some("agama".to_string()));
+    }
+
+    #[test]
+    fn test_import_ipv6_with_dns() {
+        let network =
+            import_cmdline("ip=[2001:db8::2]::[2001:db8::1]:64::eth0:none:[2001:db8::53]");
+
+        let eth0 = network.get_connection("eth0").unwrap();
+        assert_eq!(eth0.ip_config.method6, Ipv6Method::Manual);
+        assert_eq!(
+            eth0.ip_config.gateway6,
+            Some("2001:db8::1".parse().unwrap())
+        );
+        assert_eq!(eth0.ip_config.nameservers.len(), 1);
+    }
+
+    #[test]
+    fn test_import_bonded_vlan() {
+        let network = import_cmdline(
+            "bond=bond0:eth0,eth1:mode=802.3ad,miimon=100 vlan=bond0.10:bond0 \
+             ip=bond0.10:dhcp ip=dhcp",
+        );
+
+        let bond0 = network.get_connection("bond0").unwrap();
+        let ConnectionConfig::Bond(bond) = &bond0.config else {
+            panic!("Unexpected configuration: {:?}", bond0.config);
+        };
+        assert_eq!(bond.mode, BondMode::LACP);
+        assert_eq!(bond.options.0.get("miimon"), Some(&"100".to_string()));
+
+        let eth1 = network.get_connection("eth1").unwrap();
+        assert_eq!(eth1.controller, Some(bond0.uuid));
+
+        let vlan = network.get_connection("bond0.10").unwrap();
+        let ConnectionConfig::Vlan(config) = &vlan.config else {
+            panic!("Unexpected configuration: {:?}", vlan.config);
+        };
+        assert_eq!(config.parent, "bond0");
+        assert_eq!(config.id, 10);
+        assert_eq!(vlan.ip_config.method4, Ipv4Method::Auto);
+        assert_eq!(network.connections.len(), 4);
+    }
+
+    #[test]
+    fn test_import_linuxrc_ifcfg() {
+        let network = import_cmdline(
+            r#"ifcfg="eth0=192.168.1.10/24,192.168.1.1,192.168.1.2 192.168.1.3,example.net" ifcfg=eth*=dhcp ifcfg=eth2=BOOTPROTO=dhcp4,MTU=9000"#,
+        );
+
+        let eth0 = network.get_connection("eth0").unwrap();
+        assert_eq!(eth0.ip_config.method4, Ipv4Method::Manual);
+        assert_eq!(eth0.ip_config.nameservers.len(), 2);
+        assert_eq!(
+            eth0.ip_config.dns_searchlist,
+            vec!["example.net".to_string()]
+        );
+
+        let pattern = network.get_connection("eth*").unwrap();
+        assert_eq!(pattern.interface, None);
+        assert_eq!(pattern.match_config.interface, vec!["eth*".to_string()]);
+        assert_eq!(pattern.ip_config.method4, Ipv4Method::Auto);
+
+        let eth2 = network.get_connection("eth2").unwrap();
+        assert_eq!(eth2.ip_config.method4, Ipv4Method::Auto);
+        assert_eq!(eth2.mtu, 9000);
+    }
+
+    #[test]
+    fn test_import_unsupported() {
+        let network = import_cmdline("ip=eth0:ibft nameserver=foo vlan=eth0:eth1");
+        assert_eq!(network.warnings.len(), 3);
+    }
+}
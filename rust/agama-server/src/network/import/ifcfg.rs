@@ -0,0 +1,487 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Imports the network configuration from the `ifcfg-*` files (see ifcfg(5)).
+//!
+//! Besides the `ifcfg-*` files, it reads the routes (`routes` and `ifroute-*`) and the static DNS
+//! settings from the `config` file.
+
+use super::{
+    add_address, cmdline::bond_config, connection_name, parse_inet, set_gateway, vlan_id_from_name,
+    ImportError, ImportedNetwork,
+};
+use crate::network::model::{
+    BridgeConfig, Connection, ConnectionConfig, IpRoute, Ipv4Method, Ipv6Method, MacAddress,
+    VlanConfig,
+};
+use std::{collections::HashMap, fs, net::IpAddr, path::Path, str::FromStr};
+
+/// Suffixes of the files which must be ignored (backups and package leftovers).
+const IGNORED_SUFFIXES: [&str; 6] = ["~", ".rpmnew", ".rpmsave", ".bak", ".old", ".orig"];
+
+/// Imports the network configuration from an `ifcfg-*` files directory.
+///
+/// * `dir`: directory containing the files (usually `/etc/sysconfig/network`).
+/// * `network`: imported network configuration.
+pub fn import(dir: &Path, network: &mut ImportedNetwork) -> Result<(), ImportError> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| ImportError::Io(dir.to_path_buf(), e))?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter_map(|f| f.strip_prefix("ifcfg-").map(str::to_string))
+        .filter(|n| n != "lo" && !IGNORED_SUFFIXES.iter().any(|s| n.ends_with(s)))
+        .collect();
+    names.sort();
+
+    for name in &names {
+        let variables = parse_variables(&read_file(&dir.join(format!("ifcfg-{name}")))?);
+        apply_variables(name, &variables, network);
+    }
+
+    for name in &names {
+        let path = dir.join(format!("ifroute-{name}"));
+        if path.exists() {
+            import_routes(&read_file(&path)?, Some(name), network);
+        }
+    }
+
+    let path = dir.join("routes");
+    if path.exists() {
+        import_routes(&read_file(&path)?, None, network);
+    }
+
+    let path = dir.join("config");
+    if path.exists() {
+        let config = parse_variables(&read_file(&path)?);
+        let nameservers: Vec<IpAddr> = split_variable(&config, "NETCONFIG_DNS_STATIC_SERVERS")
+            .filter_map(|n| IpAddr::from_str(n).ok())
+            .collect();
+        let searchlist: Vec<String> = split_variable(&config, "NETCONFIG_DNS_STATIC_SEARCHLIST")
+            .map(str::to_string)
+            .collect();
+        network.add_dns(&nameservers, &searchlist);
+    }
+
+    Ok(())
+}
+
+fn read_file(path: &Path) -> Result<String, ImportError> {
+    fs::read_to_string(path).map_err(|e| ImportError::Io(path.to_path_buf(), e))
+}
+
+/// Parses the shell variables of a sysconfig file.
+fn parse_variables(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| l.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), unquote(value.trim()).to_string()))
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['\'', '"'] {
+        if let Some(unquoted) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return unquoted;
+        }
+    }
+    value
+}
+
+/// Returns the whitespace separated values of a variable.
+fn split_variable<'a>(
+    variables: &'a HashMap<String, String>,
+    name: &str,
+) -> impl Iterator<Item = &'a str> {
+    variables
+        .get(name)
+        .map(String::as_str)
+        .unwrap_or_default()
+        .split_whitespace()
+}
+
+/// Returns the values of the variables starting with the given prefix, sorted by name.
+///
+/// ifcfg files use suffixes to define several values (e.g., `IPADDR`, `IPADDR_1`, etc.).
+fn indexed_variables<'a>(
+    variables: &'a HashMap<String, String>,
+    prefix: &str,
+) -> Vec<(&'a str, &'a str)> {
+    let mut values: Vec<(&str, &str)> = variables
+        .iter()
+        .filter_map(|(k, v)| Some((k.strip_prefix(prefix)?, v.as_str())))
+        .filter(|(_, v)| !v.is_empty())
+        .collect();
+    values.sort();
+    values
+}
+
+fn is_enabled(variables: &HashMap<String, String>, name: &str) -> bool {
+    variables
+        .get(name)
+        .is_some_and(|v| matches!(v.to_lowercase().as_str(), "yes" | "on" | "true" | "1"))
+}
+
+/// Applies the variables of an `ifcfg-*` file to the connection of the given interface.
+///
+/// * `name`: interface name.
+/// * `variables`: ifcfg variables.
+/// * `network`: imported network configuration.
+pub(super) fn apply_variables(
+    name: &str,
+    variables: &HashMap<String, String>,
+    network: &mut ImportedNetwork,
+) {
+    let mut warnings = vec![];
+    let conn = network.connection_mut(name);
+
+    let bootproto = variables
+        .get("BOOTPROTO")
+        .map(|b| b.to_lowercase())
+        .unwrap_or_default();
+    match bootproto.as_str() {
+        "dhcp" | "dhcp+autoip" => {
+            conn.ip_config.method4 = Ipv4Method::Auto;
+            conn.ip_config.method6 = Ipv6Method::Auto;
+        }
+        "dhcp4" => conn.ip_config.method4 = Ipv4Method::Auto,
+        "dhcp6" => conn.ip_config.method6 = Ipv6Method::Dhcp,
+        "autoip" => conn.ip_config.method4 = Ipv4Method::LinkLocal,
+        "static" | "none" | "" => {}
+        _ => warnings.push(format!("Unsupported BOOTPROTO for {name}: {bootproto}")),
+    }
+
+    for (suffix, address) in indexed_variables(variables, "IPADDR") {
+        let netmask = variables
+            .get(&format!("PREFIXLEN{suffix}"))
+            .or_else(|| variables.get(&format!("NETMASK{suffix}")));
+        match parse_inet(address, netmask.map(String::as_str)) {
+            Some(address) => add_address(conn, address),
+            None => warnings.push(format!("Invalid address for {name}: {address}")),
+        }
+    }
+
+    if let Some(startmode) = variables.get("STARTMODE") {
+        if matches!(startmode.as_str(), "off" | "manual") {
+            conn.autoconnect = false;
+        }
+    }
+
+    if let Some(mtu) = variables.get("MTU").and_then(|m| m.parse().ok()) {
+        conn.mtu = mtu;
+    }
+
+    if let Some(mac) = variables.get("LLADDR").filter(|m| !m.is_empty()) {
+        match MacAddress::from_str(mac) {
+            Ok(mac) => conn.mac_address = mac,
+            Err(_) => warnings.push(format!("Invalid MAC address for {name}: {mac}")),
+        }
+    }
+
+    if let Some(zone) = variables.get("ZONE").filter(|z| !z.is_empty()) {
+        conn.firewall_zone = Some(zone.clone());
+    }
+
+    if let Some(parent) = variables.get("ETHERDEVICE").filter(|p| !p.is_empty()) {
+        let id = variables
+            .get("VLAN_ID")
+            .and_then(|i| i.parse().ok())
+            .or_else(|| vlan_id_from_name(name));
+        match id {
+            Some(id) => {
+                conn.config = ConnectionConfig::Vlan(VlanConfig {
+                    parent: parent.clone(),
+                    id,
+                    ..Default::default()
+                })
+            }
+            None => warnings.push(format!("Could not find out the VLAN ID of {name}")),
+        }
+    }
+
+    let mut ports = vec![];
+    if is_enabled(variables, "BONDING_MASTER") {
+        let options: Vec<&str> = split_variable(variables, "BONDING_MODULE_OPTS").collect();
+        match bond_config(&options) {
+            Ok(config) => conn.config = ConnectionConfig::Bond(config),
+            Err(error) => warnings.push(format!("Invalid bond options for {name}: {error}")),
+        }
+        ports = indexed_variables(variables, "BONDING_SLAVE")
+            .into_iter()
+            .map(|(_, p)| p.to_string())
+            .collect();
+    }
+
+    if is_enabled(variables, "BRIDGE") {
+        let number = |key: &str| variables.get(key).and_then(|v| v.parse::<f64>().ok());
+        conn.config = ConnectionConfig::Bridge(BridgeConfig {
+            stp: is_enabled(variables, "BRIDGE_STP"),
+            priority: number("BRIDGE_PRIORITY").map(|v| v as u32),
+            forward_delay: number("BRIDGE_FORWARDDELAY").map(|v| v as u32),
+            hello_time: number("BRIDGE_HELLOTIME").map(|v| v as u32),
+            max_age: number("BRIDGE_MAXAGE").map(|v| v as u32),
+            ageing_time: number("BRIDGE_AGEINGTIME").map(|v| v as u32),
+        });
+        ports = split_variable(variables, "BRIDGE_PORTS")
+            .map(str::to_string)
+            .collect();
+    }
+
+    if variables
+        .iter()
+        .any(|(k, v)| k.starts_with("WIRELESS") && !v.is_empty())
+    {
+        warnings.push(format!("Wireless settings for {name} are not supported"));
+    }
+
+    if let Some(tunnel) = variables.get("TUNNEL").filter(|t| !t.is_empty()) {
+        warnings.push(format!("Tunnels are not supported ({name}: {tunnel})"));
+    }
+
+    if !ports.is_empty() {
+        network.set_ports(name, &ports);
+    }
+    for warning in warnings {
+        network.warn(warning);
+    }
+}
+
+/// Imports the routes from a `routes` or `ifroute-*` file.
+///
+/// Each line contains the destination, the gateway, the network mask, the interface and,
+/// optionally, some options (e.g., `metric 100`). A dash means that the value is not set.
+///
+/// * `content`: file content.
+/// * `interface`: interface of the routes (for `ifroute-*` files).
+/// * `network`: imported network configuration.
+fn import_routes(content: &str, interface: Option<&str>, network: &mut ImportedNetwork) {
+    let lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'));
+
+    for line in lines {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let field = |index: usize| fields.get(index).copied().filter(|f| *f != "-");
+
+        let gateway = field(1).and_then(|g| IpAddr::from_str(g).ok());
+        let name = field(3).or(interface);
+        let metric = fields
+            .iter()
+            .skip(4)
+            .skip_while(|f| **f != "metric")
+            .nth(1)
+            .and_then(|m| m.parse().ok());
+
+        let route = if fields[0] == "default" {
+            if gateway.is_none() {
+                network.warn(format!("Missing gateway in the default route: {line}"));
+                continue;
+            }
+            None
+        } else {
+            let Some(destination) = parse_inet(fields[0], field(2)) else {
+                network.warn(format!("Invalid route: {line}"));
+                continue;
+            };
+            Some(IpRoute {
+                destination,
+                next_hop: gateway,
+                metric,
+            })
+        };
+
+        let conn = match (name, gateway) {
+            (Some(name), _) => find_connection(network, name),
+            (None, Some(gateway)) => connection_for_gateway(network, gateway),
+            (None, None) => None,
+        };
+        let Some(conn) = conn else {
+            network.warn(format!(
+                "Could not find out the interface of the route: {line}"
+            ));
+            continue;
+        };
+
+        match route {
+            Some(route) if route.destination.is_ipv4() => conn.ip_config.routes4.push(route),
+            Some(route) => conn.ip_config.routes6.push(route),
+            None => set_gateway(conn, gateway.unwrap()),
+        }
+    }
+}
+
+fn find_connection<'a>(network: &'a mut ImportedNetwork, name: &str) -> Option<&'a mut Connection> {
+    network
+        .connections
+        .iter_mut()
+        .find(|c| connection_name(c) == name)
+}
+
+/// Returns the connection whose addresses are in the same network as the gateway.
+fn connection_for_gateway(
+    network: &mut ImportedNetwork,
+    gateway: IpAddr,
+) -> Option<&mut Connection> {
+    network.connections.iter_mut().find(|c| {
+        c.ip_config
+            .addresses
+            .iter()
+            .any(|a| a.network().contains(&gateway))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{import, parse_variables};
+    use crate::network::{
+        import::ImportedNetwork,
+        model::{ConnectionConfig, Ipv4Method, Ipv6Method},
+    };
+    use agama_lib::network::types::BondMode;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn import_files(files: &[(&str, &str)]) -> ImportedNetwork {
+        let tmp = TempDir::new().unwrap();
+        for (name, content) in files {
+            fs::write(tmp.path().join(name), content).unwrap();
+        }
+        let mut network = ImportedNetwork::default();
+        import(tmp.path(), &mut network).unwrap();
+        network
+    }
+
+    #[test]
+    fn test_parse_variables() {
+        let variables = parse_variables(
+            "# comment\nBOOTPROTO='static'\nIPADDR=\"192.168.1.10/24\"\nSTARTMODE=auto\n",
+        );
+        assert_eq!(variables.get("BOOTPROTO"), Some(&"static".to_string()));
+        assert_eq!(
+            variables.get("IPADDR"),
+            Some(&"192.168.1.10/24".to_string())
+        );
+        assert_eq!(variables.get("STARTMODE"), Some(&"auto".to_string()));
+    }
+
+    #[test]
+    fn test_import_static() {
+        let network = import_files(&[
+            (
+                "ifcfg-eth0",
+                "BOOTPROTO='static'\nIPADDR='192.168.1.10'\nNETMASK='255.255.255.0'\n\
+                 IPADDR_1='2001:db8::10'\nPREFIXLEN_1='64'\nMTU='9000'\nZONE='public'\n\
+                 STARTMODE='manual'\n",
+            ),
+            ("ifcfg-lo", "BOOTPROTO='static'\nIPADDR='127.0.0.1/8'\n"),
+            ("ifcfg-eth0.bak", "BOOTPROTO='dhcp'\n"),
+            (
+                "routes",
+                "default 192.168.1.1 - -\n10.0.0.0/8 192.168.1.2 - eth0 metric 100\n",
+            ),
+            (
+                "config",
+                "NETCONFIG_DNS_STATIC_SERVERS='192.168.1.2 192.168.1.3'\n\
+                 NETCONFIG_DNS_STATIC_SEARCHLIST='example.net'\n",
+            ),
+        ]);
+
+        assert_eq!(network.connections.len(), 1);
+        let eth0 = network.get_connection("eth0").unwrap();
+        assert_eq!(eth0.ip_config.method4, Ipv4Method::Manual);
+        assert_eq!(eth0.ip_config.method6, Ipv6Method::Manual);
+        assert_eq!(eth0.ip_config.addresses[0].to_string(), "192.168.1.10/24");
+        assert_eq!(eth0.ip_config.addresses[1].to_string(), "2001:db8::10/64");
+        assert_eq!(
+            eth0.ip_config.gateway4,
+            Some("192.168.1.1".parse().unwrap())
+        );
+        assert_eq!(eth0.ip_config.routes4.len(), 1);
+        assert_eq!(eth0.ip_config.routes4[0].metric, Some(100));
+        assert_eq!(eth0.ip_config.nameservers.len(), 2);
+        assert_eq!(
+            eth0.ip_config.dns_searchlist,
+            vec!["example.net".to_string()]
+        );
+        assert_eq!(eth0.mtu, 9000);
+        assert_eq!(eth0.firewall_zone, Some("public".to_string()));
+        assert!(!eth0.autoconnect);
+    }
+
+    #[test]
+    fn test_import_bond_and_bridge() {
+        let network = import_files(&[
+            (
+                "ifcfg-bond0",
+                "BOOTPROTO='none'\nBONDING_MASTER='yes'\n\
+                 BONDING_MODULE_OPTS='mode=active-backup miimon=100'\n\
+                 BONDING_SLAVE0='eth0'\nBONDING_SLAVE1='eth1'\n",
+            ),
+            (
+                "ifcfg-br0",
+                "BOOTPROTO='dhcp'\nBRIDGE='yes'\nBRIDGE_STP='on'\nBRIDGE_FORWARDDELAY='15'\n\
+                 BRIDGE_PORTS='bond0'\n",
+            ),
+            ("ifcfg-eth0", "BOOTPROTO='none'\n"),
+            ("ifroute-br0", "default 192.168.1.1 - -\n"),
+        ]);
+
+        let bond0 = network.get_connection("bond0").unwrap();
+        let ConnectionConfig::Bond(bond) = &bond0.config else {
+            panic!("Unexpected configuration: {:?}", bond0.config);
+        };
+        assert_eq!(bond.mode, BondMode::ActiveBackup);
+
+        let br0 = network.get_connection("br0").unwrap();
+        let ConnectionConfig::Bridge(bridge) = &br0.config else {
+            panic!("Unexpected configuration: {:?}", br0.config);
+        };
+        assert!(bridge.stp);
+        assert_eq!(bridge.forward_delay, Some(15));
+        assert_eq!(br0.ip_config.method4, Ipv4Method::Auto);
+        assert_eq!(br0.ip_config.gateway4, Some("192.168.1.1".parse().unwrap()));
+
+        assert_eq!(bond0.controller, Some(br0.uuid));
+        let eth1 = network.get_connection("eth1").unwrap();
+        assert_eq!(eth1.controller, Some(bond0.uuid));
+        assert_eq!(eth1.ip_config.method4, Ipv4Method::Disabled);
+    }
+
+    #[test]
+    fn test_import_vlan_and_unsupported() {
+        let network = import_files(&[
+            ("ifcfg-vlan10", "BOOTPROTO='dhcp4'\nETHERDEVICE='eth0'\n"),
+            ("ifcfg-wlan0", "BOOTPROTO='dhcp'\nWIRELESS_ESSID='agama'\n"),
+        ]);
+
+        let vlan = network.get_connection("vlan10").unwrap();
+        let ConnectionConfig::Vlan(config) = &vlan.config else {
+            panic!("Unexpected configuration: {:?}", vlan.config);
+        };
+        assert_eq!(config.parent, "eth0");
+        assert_eq!(config.id, 10);
+        assert_eq!(network.warnings.len(), 1);
+    }
+}
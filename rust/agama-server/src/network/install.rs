@@ -0,0 +1,282 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Writes the network configuration for the installed system.
+//!
+//! Unlike [NetworkManagerAdapter](crate::network::NetworkManagerAdapter), which applies the
+//! configuration to the running system, [TargetAdapter] renders the configuration files into a
+//! target root. It supports NetworkManager keyfiles and systemd-networkd files, so the installed
+//! system does not need to use the same configuration (or even the same network service) than the
+//! installer.
+
+mod keyfile;
+mod networkd;
+
+use super::{
+    adapter::{Adapter, NetworkAdapterError},
+    model::{GeneralState, StateConfig},
+    nm::dns,
+    NetworkState,
+};
+use agama_lib::error::ServiceError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Write},
+    fs, io,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+/// Static host name file, relative to the target root.
+const HOSTNAME_PATH: &str = "etc/hostname";
+
+/// Network service of the installed system.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetBackend {
+    /// NetworkManager keyfiles (`/etc/NetworkManager/system-connections`).
+    #[default]
+    Keyfile,
+    /// systemd-networkd files (`/etc/systemd/network`).
+    Networkd,
+}
+
+/// Adapter to write the network configuration into a target root.
+///
+/// It cannot read the configuration back, so [TargetAdapter::read] returns an empty state.
+pub struct TargetAdapter {
+    root: PathBuf,
+    backend: TargetBackend,
+}
+
+impl TargetAdapter {
+    /// Creates a new adapter.
+    ///
+    /// * `root`: target root (e.g., `/mnt`).
+    /// * `backend`: network service of the installed system.
+    pub fn new<P: AsRef<Path>>(root: P, backend: TargetBackend) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            backend,
+        }
+    }
+
+    /// Writes the configuration files.
+    fn write_files(&self, network: &NetworkState) -> io::Result<()> {
+        let files = match self.backend {
+            TargetBackend::Keyfile => keyfile::render(network),
+            TargetBackend::Networkd => networkd::render(network),
+        };
+        let dir = self.root.join(match self.backend {
+            TargetBackend::Keyfile => keyfile::CONNECTIONS_DIR,
+            TargetBackend::Networkd => networkd::NETWORK_DIR,
+        });
+        fs::create_dir_all(&dir)?;
+
+        for file in files {
+            let path = dir.join(&file.name);
+            match file.content {
+                Some(content) => {
+                    fs::write(&path, content)?;
+                    // keyfiles might contain secrets and NetworkManager ignores them unless
+                    // they are only readable by root.
+                    fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+                }
+                None if path.exists() => fs::remove_file(&path)?,
+                None => {}
+            }
+        }
+        self.write_general(&network.general_state)
+    }
+
+    /// Writes the host name and the global DNS configuration.
+    fn write_general(&self, general: &GeneralState) -> io::Result<()> {
+        if !general.hostname.is_empty() {
+            let path = self.root.join(HOSTNAME_PATH);
+            fs::create_dir_all(self.root.join("etc"))?;
+            fs::write(path, format!("{}\n", general.hostname))?;
+        }
+
+        match self.backend {
+            TargetBackend::Keyfile => dns::write(&self.root, &general.dns),
+            TargetBackend::Networkd => {
+                let path = self.root.join(networkd::RESOLVED_CONF_PATH);
+                match networkd::render_dns(&general.dns) {
+                    Some(file) => {
+                        if let Some(parent) = path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::write(path, file.to_string())
+                    }
+                    None if path.exists() => fs::remove_file(path),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Adapter for TargetAdapter {
+    async fn read(&self, _config: StateConfig) -> Result<NetworkState, NetworkAdapterError> {
+        Ok(NetworkState::default())
+    }
+
+    async fn write(&self, network: &NetworkState) -> Result<(), NetworkAdapterError> {
+        self.write_files(network)
+            .map_err(|e| NetworkAdapterError::Write(ServiceError::Anyhow(e.into())))
+    }
+}
+
+/// File to write (or to remove, if it has no content) in the target directory.
+struct TargetFile {
+    name: String,
+    content: Option<String>,
+}
+
+/// INI-like file, as used by NetworkManager keyfiles and systemd-networkd.
+///
+/// The sections can be repeated (e.g., `[Route]` in systemd-networkd files).
+#[derive(Default)]
+struct IniFile {
+    sections: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl IniFile {
+    /// Adds a section, returning its position.
+    fn add_section(&mut self, name: &str) -> usize {
+        self.sections.push((name.to_string(), vec![]));
+        self.sections.len() - 1
+    }
+
+    /// Adds a value to the last section with the given name, adding the section if needed.
+    fn set<T: ToString>(&mut self, section: &str, key: &str, value: T) {
+        let position = match self.sections.iter().rposition(|(name, _)| name == section) {
+            Some(position) => position,
+            None => self.add_section(section),
+        };
+        self.add(position, key, value);
+    }
+
+    /// Adds a value to the section in the given position.
+    fn add<T: ToString>(&mut self, position: usize, key: &str, value: T) {
+        self.sections[position]
+            .1
+            .push((key.to_string(), value.to_string()));
+    }
+}
+
+impl fmt::Display for IniFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut output = String::new();
+        for (name, values) in &self.sections {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            writeln!(output, "[{}]", name)?;
+            for (key, value) in values {
+                writeln!(output, "{}={}", key, value)?;
+            }
+        }
+        write!(f, "{}", output)
+    }
+}
+
+/// Returns a name which can be used as a file name.
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c == '/' || c.is_control() { '_' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TargetAdapter, TargetBackend};
+    use crate::network::{
+        model::{Connection, GeneralState, GlobalDns, Ipv4Method},
+        Adapter, NetworkState,
+    };
+    use agama_lib::network::types::{DeviceType, DnsMode};
+    use std::{fs, os::unix::fs::PermissionsExt};
+    use tempfile::TempDir;
+
+    fn network_state() -> NetworkState {
+        let mut eth0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        eth0.interface = Some("eth0".to_string());
+        eth0.ip_config.method4 = Ipv4Method::Manual;
+        eth0.ip_config.addresses = vec!["192.168.1.10/24".parse().unwrap()];
+        let mut old = Connection::new("old".to_string(), DeviceType::Dummy);
+        old.remove();
+        let general = GeneralState {
+            hostname: "server".to_string(),
+            dns: GlobalDns {
+                nameservers: vec!["192.168.1.1".parse().unwrap()],
+                searchlist: vec!["example.com".to_string()],
+                mode: Some(DnsMode::Dnsmasq),
+            },
+            ..Default::default()
+        };
+        NetworkState::new(general, vec![], vec![], vec![eth0, old])
+    }
+
+    #[tokio::test]
+    async fn test_write_keyfiles() {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join("etc/NetworkManager/system-connections");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("old.nmconnection"), "").unwrap();
+
+        let adapter = TargetAdapter::new(root.path(), TargetBackend::Keyfile);
+        adapter.write(&network_state()).await.unwrap();
+
+        let path = dir.join("eth0.nmconnection");
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("address1=192.168.1.10/24"));
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!dir.join("old.nmconnection").exists());
+
+        let hostname = fs::read_to_string(root.path().join("etc/hostname")).unwrap();
+        assert_eq!(hostname, "server\n");
+        let dns = root
+            .path()
+            .join("etc/NetworkManager/conf.d/90-agama-dns.conf");
+        let content = fs::read_to_string(dns).unwrap();
+        assert!(content.contains("dns=dnsmasq\n"));
+    }
+
+    #[tokio::test]
+    async fn test_write_networkd() {
+        let root = TempDir::new().unwrap();
+        let adapter = TargetAdapter::new(root.path(), TargetBackend::Networkd);
+        adapter.write(&network_state()).await.unwrap();
+
+        let dir = root.path().join("etc/systemd/network");
+        let content = fs::read_to_string(dir.join("50-eth0.network")).unwrap();
+        assert!(content.contains("Address=192.168.1.10/24"));
+
+        let dns = root
+            .path()
+            .join("etc/systemd/resolved.conf.d/90-agama-dns.conf");
+        let content = fs::read_to_string(dns).unwrap();
+        assert_eq!(content, "[Resolve]\nDNS=192.168.1.1\nDomains=example.com\n");
+    }
+}
@@ -0,0 +1,399 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Renders the connections as NetworkManager keyfiles (see nm-settings-keyfile(5)).
+
+use super::{file_name, IniFile, TargetFile};
+use crate::network::{
+    model::{
+        Connection, ConnectionConfig, IpRoute, MacAddress, PortConfig, SecurityProtocol,
+        WirelessConfig,
+    },
+    NetworkState,
+};
+use std::net::IpAddr;
+
+/// Directory of the keyfiles, relative to the target root.
+pub const CONNECTIONS_DIR: &str = "etc/NetworkManager/system-connections";
+
+/// Returns the keyfiles for the connections of the given state.
+///
+/// The keyfiles of the removed connections are returned without content.
+pub(super) fn render(network: &NetworkState) -> Vec<TargetFile> {
+    network
+        .connections
+        .iter()
+        .map(|conn| TargetFile {
+            name: format!("{}.nmconnection", file_name(&conn.id)),
+            content: (!conn.is_removed()).then(|| render_connection(conn, network).to_string()),
+        })
+        .collect()
+}
+
+fn render_connection(conn: &Connection, network: &NetworkState) -> IniFile {
+    let mut file = IniFile::default();
+    file.set("connection", "id", &conn.id);
+    file.set("connection", "uuid", conn.uuid);
+    file.set("connection", "type", connection_type(&conn.config));
+    if let Some(interface) = &conn.interface {
+        file.set("connection", "interface-name", interface);
+    }
+    if !conn.autoconnect {
+        file.set("connection", "autoconnect", "false");
+    }
+    if let Some(zone) = &conn.firewall_zone {
+        file.set("connection", "zone", zone);
+    }
+
+    let controller = conn
+        .controller
+        .and_then(|uuid| network.connections.iter().find(|c| c.uuid == uuid));
+    if let Some(controller) = controller {
+        file.set("connection", "controller", controller.uuid);
+        file.set(
+            "connection",
+            "port-type",
+            connection_type(&controller.config),
+        );
+    }
+
+    let link_section = match &conn.config {
+        ConnectionConfig::Wireless(_) => "wifi",
+        ConnectionConfig::Infiniband(_) => "infiniband",
+        _ => "ethernet",
+    };
+    if conn.mtu > 0 {
+        file.set(link_section, "mtu", conn.mtu);
+    }
+    if conn.mac_address != MacAddress::Unset {
+        file.set(link_section, "cloned-mac-address", &conn.mac_address);
+    }
+    if let Some(mac_address) = &conn.match_config.mac_address {
+        if link_section != "infiniband" {
+            file.set(link_section, "mac-address", mac_address);
+        }
+    }
+
+    render_config(&mut file, &conn.config);
+    if let PortConfig::Bridge(port) = &conn.port_config {
+        if let Some(priority) = port.priority {
+            file.set("bridge-port", "priority", priority);
+        }
+        if let Some(path_cost) = port.path_cost {
+            file.set("bridge-port", "path-cost", path_cost);
+        }
+    }
+    render_match(&mut file, conn);
+    render_8021x(&mut file, conn);
+
+    // ports do not have IP settings.
+    if controller.is_none() {
+        render_ip(&mut file, conn);
+    }
+    file
+}
+
+fn connection_type(config: &ConnectionConfig) -> &'static str {
+    match config {
+        ConnectionConfig::Ethernet => "ethernet",
+        ConnectionConfig::Wireless(_) => "wifi",
+        ConnectionConfig::Loopback => "loopback",
+        ConnectionConfig::Dummy => "dummy",
+        ConnectionConfig::Bond(_) => "bond",
+        ConnectionConfig::Vlan(_) => "vlan",
+        ConnectionConfig::Bridge(_) => "bridge",
+        ConnectionConfig::Infiniband(_) => "infiniband",
+        ConnectionConfig::Tun(_) => "tun",
+        ConnectionConfig::Wireguard(_) => "wireguard",
+    }
+}
+
+fn render_config(file: &mut IniFile, config: &ConnectionConfig) {
+    match config {
+        ConnectionConfig::Wireless(wireless) => render_wireless(file, wireless),
+        ConnectionConfig::Bond(bond) => {
+            file.set("bond", "mode", bond.mode);
+            let mut options: Vec<_> = bond.options.0.iter().collect();
+            options.sort();
+            for (key, value) in options {
+                file.set("bond", key, value);
+            }
+        }
+        ConnectionConfig::Bridge(bridge) => {
+            file.set("bridge", "stp", bridge.stp);
+            let values = [
+                ("priority", bridge.priority),
+                ("forward-delay", bridge.forward_delay),
+                ("hello-time", bridge.hello_time),
+                ("max-age", bridge.max_age),
+                ("ageing-time", bridge.ageing_time),
+            ];
+            for (key, value) in values {
+                if let Some(value) = value {
+                    file.set("bridge", key, value);
+                }
+            }
+        }
+        ConnectionConfig::Vlan(vlan) => {
+            file.set("vlan", "parent", &vlan.parent);
+            file.set("vlan", "id", vlan.id);
+            file.set("vlan", "protocol", &vlan.protocol);
+        }
+        ConnectionConfig::Infiniband(infiniband) => {
+            file.set("infiniband", "transport-mode", &infiniband.transport_mode);
+            if let Some(p_key) = infiniband.p_key {
+                file.set("infiniband", "p-key", p_key);
+            }
+            if let Some(parent) = &infiniband.parent {
+                file.set("infiniband", "parent", parent);
+            }
+        }
+        ConnectionConfig::Tun(tun) => {
+            file.set("tun", "mode", tun.mode.clone() as u32);
+            if let Some(owner) = &tun.owner {
+                file.set("tun", "owner", owner);
+            }
+            if let Some(group) = &tun.group {
+                file.set("tun", "group", group);
+            }
+        }
+        ConnectionConfig::Wireguard(wireguard) => {
+            file.add_section("wireguard");
+            if let Some(private_key) = &wireguard.private_key {
+                file.set("wireguard", "private-key", private_key);
+            }
+            if let Some(listen_port) = wireguard.listen_port {
+                file.set("wireguard", "listen-port", listen_port);
+            }
+            if let Some(fwmark) = wireguard.fwmark {
+                file.set("wireguard", "fwmark", fwmark);
+            }
+            for peer in &wireguard.peers {
+                let section = format!("wireguard-peer.{}", peer.public_key);
+                if let Some(endpoint) = &peer.endpoint {
+                    file.set(&section, "endpoint", endpoint);
+                }
+                if let Some(preshared_key) = &peer.preshared_key {
+                    file.set(&section, "preshared-key", preshared_key);
+                    file.set(&section, "preshared-key-flags", 0);
+                }
+                if let Some(keepalive) = peer.persistent_keepalive {
+                    file.set(&section, "persistent-keepalive", keepalive);
+                }
+                file.set(&section, "allowed-ips", list(&peer.allowed_ips));
+            }
+        }
+        ConnectionConfig::Ethernet | ConnectionConfig::Loopback | ConnectionConfig::Dummy => {}
+    }
+}
+
+fn render_wireless(file: &mut IniFile, wireless: &WirelessConfig) {
+    file.set("wifi", "ssid", &wireless.ssid);
+    file.set("wifi", "mode", wireless.mode);
+    if wireless.hidden {
+        file.set("wifi", "hidden", "true");
+    }
+    if let Some(band) = &wireless.band {
+        file.set("wifi", "band", band);
+        file.set("wifi", "channel", wireless.channel);
+    }
+    if let Some(bssid) = &wireless.bssid {
+        file.set("wifi", "bssid", bssid);
+    }
+
+    if let Some(wep) = &wireless.wep_security {
+        file.set("wifi-security", "key-mgmt", wireless.security);
+        file.set("wifi-security", "auth-alg", &wep.auth_alg);
+        file.set("wifi-security", "wep-tx-keyidx", wep.wep_key_index);
+        for (index, key) in wep.keys.iter().enumerate() {
+            file.set("wifi-security", &format!("wep-key{}", index), key);
+        }
+    } else if wireless.security != SecurityProtocol::WEP {
+        file.set("wifi-security", "key-mgmt", wireless.security);
+        if let Some(password) = &wireless.password {
+            file.set("wifi-security", "psk", password);
+        }
+    }
+    if wireless.pmf != 0 {
+        file.set("wifi-security", "pmf", wireless.pmf);
+    }
+}
+
+fn render_match(file: &mut IniFile, conn: &Connection) {
+    let match_config = &conn.match_config;
+    let values = [
+        ("driver", &match_config.driver),
+        ("interface-name", &match_config.interface),
+        ("path", &match_config.path),
+        ("kernel-command-line", &match_config.kernel),
+    ];
+    for (key, value) in values {
+        if !value.is_empty() {
+            file.set("match", key, list(value));
+        }
+    }
+}
+
+fn render_8021x(file: &mut IniFile, conn: &Connection) {
+    let Some(config) = &conn.ieee_8021x_config else {
+        return;
+    };
+    file.set("802-1x", "eap", list(&config.eap));
+    if let Some(phase2_auth) = &config.phase2_auth {
+        file.set("802-1x", "phase2-auth", phase2_auth);
+    }
+    let values = [
+        ("identity", &config.identity),
+        ("password", &config.password),
+        ("anonymous-identity", &config.anonymous_identity),
+        ("ca-cert", &config.ca_cert),
+        ("ca-cert-password", &config.ca_cert_password),
+        ("client-cert", &config.client_cert),
+        ("client-cert-password", &config.client_cert_password),
+        ("private-key", &config.private_key),
+        ("private-key-password", &config.private_key_password),
+        ("phase1-peapver", &config.peap_version),
+    ];
+    for (key, value) in values {
+        if let Some(value) = value {
+            file.set("802-1x", key, value);
+        }
+    }
+    if config.peap_label {
+        file.set("802-1x", "phase1-peaplabel", 1);
+    }
+}
+
+fn render_ip(file: &mut IniFile, conn: &Connection) {
+    let ip_config = &conn.ip_config;
+    file.set("ipv4", "method", ip_config.method4);
+    file.set("ipv6", "method", ip_config.method6);
+
+    let families = [
+        ("ipv4", true, ip_config.gateway4, &ip_config.routes4),
+        ("ipv6", false, ip_config.gateway6, &ip_config.routes6),
+    ];
+    for (section, ipv4, gateway, routes) in families {
+        let addresses = ip_config.addresses.iter().filter(|a| a.is_ipv4() == ipv4);
+        for (index, address) in addresses.enumerate() {
+            file.set(section, &format!("address{}", index + 1), address);
+        }
+        if let Some(gateway) = gateway {
+            file.set(section, "gateway", gateway);
+        }
+        let nameservers: Vec<&IpAddr> = ip_config
+            .nameservers
+            .iter()
+            .filter(|n| n.is_ipv4() == ipv4)
+            .collect();
+        if !nameservers.is_empty() {
+            file.set(section, "dns", list(&nameservers));
+        }
+        if !ip_config.dns_searchlist.is_empty() {
+            file.set(section, "dns-search", list(&ip_config.dns_searchlist));
+        }
+        if ip_config.ignore_auto_dns {
+            file.set(section, "ignore-auto-dns", "true");
+        }
+        for (index, route) in routes.iter().enumerate() {
+            file.set(section, &format!("route{}", index + 1), route_value(route));
+        }
+    }
+}
+
+/// Returns the value of a route (`<destination>[,<next-hop>[,<metric>]]`).
+fn route_value(route: &IpRoute) -> String {
+    let mut value = route.destination.to_string();
+    match (&route.next_hop, route.metric) {
+        (Some(next_hop), Some(metric)) => value.push_str(&format!(",{},{}", next_hop, metric)),
+        (Some(next_hop), None) => value.push_str(&format!(",{}", next_hop)),
+        (None, Some(metric)) => {
+            let any = if route.destination.is_ipv4() {
+                "0.0.0.0"
+            } else {
+                "::"
+            };
+            value.push_str(&format!(",{},{}", any, metric))
+        }
+        (None, None) => {}
+    }
+    value
+}
+
+/// Returns a keyfile list (`value1;value2;`).
+fn list<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| format!("{};", v.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use crate::network::{
+        model::{
+            BondConfig, Connection, ConnectionConfig, GeneralState, IpRoute, Ipv4Method, Ipv6Method,
+        },
+        NetworkState,
+    };
+    use agama_lib::network::types::DeviceType;
+
+    #[test]
+    fn test_render_keyfiles() {
+        let mut bond0 = Connection::new("bond0".to_string(), DeviceType::Bond);
+        bond0.interface = Some("bond0".to_string());
+        bond0.config = ConnectionConfig::Bond(BondConfig::default());
+        bond0.ip_config.method4 = Ipv4Method::Manual;
+        bond0.ip_config.method6 = Ipv6Method::Auto;
+        bond0.ip_config.addresses = vec!["192.168.1.10/24".parse().unwrap()];
+        bond0.ip_config.gateway4 = Some("192.168.1.1".parse().unwrap());
+        bond0.ip_config.nameservers = vec!["192.168.1.1".parse().unwrap()];
+        bond0.ip_config.routes4 = vec![IpRoute {
+            destination: "10.0.0.0/8".parse().unwrap(),
+            next_hop: Some("192.168.1.2".parse().unwrap()),
+            metric: Some(100),
+        }];
+
+        let mut eth0 = Connection::new("eth0".to_string(), DeviceType::Ethernet);
+        eth0.interface = Some("eth0".to_string());
+        eth0.controller = Some(bond0.uuid);
+        eth0.mtu = 9000;
+
+        let network = NetworkState::new(GeneralState::default(), vec![], vec![], vec![bond0, eth0]);
+        let files = render(&network);
+
+        assert_eq!(files[0].name, "bond0.nmconnection");
+        let bond0 = files[0].content.as_ref().unwrap();
+        assert!(bond0.contains("[connection]\nid=bond0\n"));
+        assert!(bond0.contains("type=bond\n"));
+        assert!(bond0.contains("[bond]\nmode=balance-rr\n"));
+        assert!(bond0.contains(
+            "[ipv4]\nmethod=manual\naddress1=192.168.1.10/24\ngateway=192.168.1.1\n\
+             dns=192.168.1.1;\nroute1=10.0.0.0/8,192.168.1.2,100\n"
+        ));
+        assert!(bond0.contains("[ipv6]\nmethod=auto\n"));
+
+        let eth0 = files[1].content.as_ref().unwrap();
+        assert!(eth0.contains("port-type=bond\n"));
+        assert!(eth0.contains("[ethernet]\nmtu=9000\n"));
+        assert!(!eth0.contains("[ipv4]"));
+    }
+}
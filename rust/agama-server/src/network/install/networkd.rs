@@ -0,0 +1,487 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Renders the connections as systemd-networkd files (see systemd.network(5) and
+//! systemd.netdev(5)).
+//!
+//! Each connection is written to a `.network` file and, for virtual devices (bonds, bridges,
+//! VLANs, etc.), to a `.netdev` file. Wireless connections are not supported.
+
+use super::{file_name, IniFile, TargetFile};
+use crate::network::{
+    model::{
+        Connection, ConnectionConfig, GlobalDns, InfinibandTransportMode, Ipv4Method, Ipv6Method,
+        MacAddress, PortConfig, TunMode, VlanProtocol,
+    },
+    NetworkState,
+};
+
+/// Directory of the configuration files, relative to the target root.
+pub const NETWORK_DIR: &str = "etc/systemd/network";
+
+/// Global DNS configuration file, relative to the target root.
+pub const RESOLVED_CONF_PATH: &str = "etc/systemd/resolved.conf.d/90-agama-dns.conf";
+
+/// Prefix of the file names, which determines the order in which systemd-networkd reads them.
+const PREFIX: &str = "50";
+
+/// Returns the systemd-resolved configuration for the global DNS settings, if any.
+///
+/// The DNS processing mode only applies to NetworkManager, so it is ignored.
+pub(super) fn render_dns(dns: &GlobalDns) -> Option<IniFile> {
+    if dns.nameservers.is_empty() && dns.searchlist.is_empty() {
+        return None;
+    }
+
+    let mut file = IniFile::default();
+    let servers: Vec<String> = dns.nameservers.iter().map(|s| s.to_string()).collect();
+    file.set("Resolve", "DNS", servers.join(" "));
+    if !dns.searchlist.is_empty() {
+        file.set("Resolve", "Domains", dns.searchlist.join(" "));
+    }
+    Some(file)
+}
+
+/// Returns the networkd files for the connections of the given state.
+///
+/// The files of the removed connections are returned without content.
+pub(super) fn render(network: &NetworkState) -> Vec<TargetFile> {
+    let mut networks: Vec<(String, IniFile)> = vec![];
+    let mut files = vec![];
+
+    for conn in &network.connections {
+        let name = file_name(conn.interface.as_deref().unwrap_or(&conn.id));
+        let network_name = format!("{PREFIX}-{name}.network");
+        let netdev_name = format!("{PREFIX}-{name}.netdev");
+
+        if conn.is_removed() {
+            files.push(TargetFile {
+                name: network_name,
+                content: None,
+            });
+            files.push(TargetFile {
+                name: netdev_name,
+                content: None,
+            });
+            continue;
+        }
+
+        if let ConnectionConfig::Wireless(_) | ConnectionConfig::Loopback = conn.config {
+            log::warn!(
+                "Skipping connection '{}': not supported by systemd-networkd",
+                conn.id
+            );
+            continue;
+        }
+
+        if let Some(netdev) = render_netdev(conn) {
+            files.push(TargetFile {
+                name: netdev_name,
+                content: Some(netdev.to_string()),
+            });
+        }
+        networks.push((name, render_network(conn, network)));
+    }
+
+    // VLANs are attached from the .network file of the parent interface.
+    for conn in network.connections.iter().filter(|c| !c.is_removed()) {
+        let (ConnectionConfig::Vlan(vlan), Some(interface)) = (&conn.config, &conn.interface)
+        else {
+            continue;
+        };
+        let parent = file_name(&vlan.parent);
+        let position = match networks.iter().position(|(name, _)| name == &parent) {
+            Some(position) => position,
+            None => {
+                let mut file = IniFile::default();
+                file.set("Match", "Name", &vlan.parent);
+                networks.push((parent, file));
+                networks.len() - 1
+            }
+        };
+        networks[position].1.set("Network", "VLAN", interface);
+    }
+
+    for (name, file) in networks {
+        files.push(TargetFile {
+            name: format!("{PREFIX}-{name}.network"),
+            content: Some(file.to_string()),
+        });
+    }
+    files
+}
+
+/// Returns the .netdev file for virtual devices.
+fn render_netdev(conn: &Connection) -> Option<IniFile> {
+    let name = conn.interface.as_ref()?;
+    let kind = match &conn.config {
+        ConnectionConfig::Bond(_) => "bond",
+        ConnectionConfig::Bridge(_) => "bridge",
+        ConnectionConfig::Vlan(_) => "vlan",
+        ConnectionConfig::Dummy => "dummy",
+        ConnectionConfig::Tun(tun) if tun.mode == TunMode::Tap => "tap",
+        ConnectionConfig::Tun(_) => "tun",
+        ConnectionConfig::Wireguard(_) => "wireguard",
+        ConnectionConfig::Infiniband(infiniband) if infiniband.parent.is_some() => "ipoib",
+        _ => return None,
+    };
+
+    let mut file = IniFile::default();
+    file.set("NetDev", "Name", name);
+    file.set("NetDev", "Kind", kind);
+    if conn.mtu > 0 {
+        file.set("NetDev", "MTUBytes", conn.mtu);
+    }
+    if let MacAddress::MacAddress(mac) = &conn.mac_address {
+        file.set("NetDev", "MACAddress", mac);
+    }
+
+    match &conn.config {
+        ConnectionConfig::Bond(bond) => {
+            file.set("Bond", "Mode", bond.mode);
+            let mut options: Vec<_> = bond.options.0.iter().collect();
+            options.sort();
+            for (key, value) in options {
+                match bond_option(key) {
+                    Some(option) if option.ends_with("Sec") => {
+                        file.set("Bond", option, format!("{}ms", value))
+                    }
+                    Some(option) => file.set("Bond", option, value),
+                    None => log::warn!(
+                        "Bond option '{}' of '{}' is not supported by systemd-networkd",
+                        key,
+                        conn.id
+                    ),
+                }
+            }
+        }
+        ConnectionConfig::Bridge(bridge) => {
+            file.set("Bridge", "STP", if bridge.stp { "yes" } else { "no" });
+            let values = [
+                ("Priority", bridge.priority),
+                ("ForwardDelaySec", bridge.forward_delay),
+                ("HelloTimeSec", bridge.hello_time),
+                ("MaxAgeSec", bridge.max_age),
+                ("AgeingTimeSec", bridge.ageing_time),
+            ];
+            for (key, value) in values {
+                if let Some(value) = value {
+                    file.set("Bridge", key, value);
+                }
+            }
+        }
+        ConnectionConfig::Vlan(vlan) => {
+            file.set("VLAN", "Id", vlan.id);
+            if vlan.protocol == VlanProtocol::IEEE802_1ad {
+                file.set("VLAN", "Protocol", "802.1ad");
+            }
+        }
+        ConnectionConfig::Tun(tun) => {
+            let section = if tun.mode == TunMode::Tap {
+                "Tap"
+            } else {
+                "Tun"
+            };
+            if let Some(owner) = &tun.owner {
+                file.set(section, "User", owner);
+            }
+            if let Some(group) = &tun.group {
+                file.set(section, "Group", group);
+            }
+        }
+        ConnectionConfig::Infiniband(infiniband) => {
+            if let Some(p_key) = infiniband.p_key {
+                file.set("IPoIB", "PartitionKey", p_key);
+            }
+            let mode = match infiniband.transport_mode {
+                InfinibandTransportMode::Datagram => "datagram",
+                InfinibandTransportMode::Connected => "connected",
+            };
+            file.set("IPoIB", "Mode", mode);
+        }
+        ConnectionConfig::Wireguard(wireguard) => {
+            file.add_section("WireGuard");
+            if let Some(private_key) = &wireguard.private_key {
+                file.set("WireGuard", "PrivateKey", private_key);
+            }
+            if let Some(listen_port) = wireguard.listen_port {
+                file.set("WireGuard", "ListenPort", listen_port);
+            }
+            if let Some(fwmark) = wireguard.fwmark {
+                file.set("WireGuard", "FirewallMark", fwmark);
+            }
+            for peer in &wireguard.peers {
+                let section = file.add_section("WireGuardPeer");
+                file.add(section, "PublicKey", &peer.public_key);
+                if !peer.allowed_ips.is_empty() {
+                    let allowed_ips: Vec<_> =
+                        peer.allowed_ips.iter().map(|ip| ip.to_string()).collect();
+                    file.add(section, "AllowedIPs", allowed_ips.join(","));
+                }
+                if let Some(endpoint) = &peer.endpoint {
+                    file.add(section, "Endpoint", endpoint);
+                }
+                if let Some(preshared_key) = &peer.preshared_key {
+                    file.add(section, "PresharedKey", preshared_key);
+                }
+                if let Some(keepalive) = peer.persistent_keepalive {
+                    file.add(section, "PersistentKeepalive", keepalive);
+                }
+            }
+        }
+        _ => {}
+    }
+    Some(file)
+}
+
+/// Maps the bond options to their systemd-networkd names.
+fn bond_option(name: &str) -> Option<&'static str> {
+    let option = match name {
+        "miimon" => "MIIMonitorSec",
+        "updelay" => "UpDelaySec",
+        "downdelay" => "DownDelaySec",
+        "arp_interval" => "ARPIntervalSec",
+        "lacp_rate" => "LACPTransmitRate",
+        "xmit_hash_policy" => "TransmitHashPolicy",
+        "ad_select" => "AdSelect",
+        "primary_reselect" => "PrimaryReselectPolicy",
+        "fail_over_mac" => "FailOverMACPolicy",
+        "arp_validate" => "ARPValidate",
+        "arp_all_targets" => "ARPAllTargets",
+        "min_links" => "MinLinks",
+        "num_grat_arp" => "GratuitousARP",
+        "all_slaves_active" => "AllSlavesActive",
+        _ => return None,
+    };
+    Some(option)
+}
+
+/// Returns the .network file of a connection.
+fn render_network(conn: &Connection, network: &NetworkState) -> IniFile {
+    let mut file = IniFile::default();
+    render_match(&mut file, conn);
+
+    if conn.mtu > 0 {
+        file.set("Link", "MTUBytes", conn.mtu);
+    }
+    if let MacAddress::MacAddress(mac) = &conn.mac_address {
+        file.set("Link", "MACAddress", mac);
+    }
+    if !conn.autoconnect {
+        file.set("Link", "ActivationPolicy", "manual");
+    }
+
+    let controller = conn
+        .controller
+        .and_then(|uuid| network.connections.iter().find(|c| c.uuid == uuid));
+    if let Some(controller) = controller {
+        let controller_name = controller.interface.as_ref().unwrap_or(&controller.id);
+        match controller.config {
+            ConnectionConfig::Bond(_) => file.set("Network", "Bond", controller_name),
+            ConnectionConfig::Bridge(_) => file.set("Network", "Bridge", controller_name),
+            _ => {}
+        }
+        if let PortConfig::Bridge(port) = &conn.port_config {
+            if let Some(priority) = port.priority {
+                file.set("Bridge", "Priority", priority);
+            }
+            if let Some(path_cost) = port.path_cost {
+                file.set("Bridge", "Cost", path_cost);
+            }
+        }
+        return file;
+    }
+
+    render_ip(&mut file, conn);
+    file
+}
+
+fn render_match(file: &mut IniFile, conn: &Connection) {
+    let match_config = &conn.match_config;
+    let names = match &conn.interface {
+        Some(interface) => vec![interface.clone()],
+        None => match_config.interface.clone(),
+    };
+    let values = [
+        ("Name", &names),
+        ("Driver", &match_config.driver),
+        ("Path", &match_config.path),
+        ("KernelCommandLine", &match_config.kernel),
+    ];
+    for (key, value) in values {
+        if !value.is_empty() {
+            file.set("Match", key, value.join(" "));
+        }
+    }
+    if let Some(mac_address) = &match_config.mac_address {
+        file.set("Match", "PermanentMACAddress", mac_address);
+    }
+}
+
+fn render_ip(file: &mut IniFile, conn: &Connection) {
+    let ip_config = &conn.ip_config;
+    let dhcp4 = ip_config.method4 == Ipv4Method::Auto;
+    let dhcp6 = matches!(ip_config.method6, Ipv6Method::Auto | Ipv6Method::Dhcp);
+    let dhcp = match (dhcp4, dhcp6) {
+        (true, true) => "yes",
+        (true, false) => "ipv4",
+        (false, true) => "ipv6",
+        (false, false) => "no",
+    };
+    file.set("Network", "DHCP", dhcp);
+
+    let link_local4 = ip_config.method4 == Ipv4Method::LinkLocal;
+    let link_local6 = !matches!(ip_config.method6, Ipv6Method::Disabled);
+    let link_local = match (link_local4, link_local6) {
+        (true, true) => "yes",
+        (true, false) => "ipv4",
+        (false, true) => "ipv6",
+        (false, false) => "no",
+    };
+    file.set("Network", "LinkLocalAddressing", link_local);
+    let accept_ra = ip_config.method6 == Ipv6Method::Auto;
+    file.set(
+        "Network",
+        "IPv6AcceptRA",
+        if accept_ra { "yes" } else { "no" },
+    );
+
+    for address in &ip_config.addresses {
+        file.set("Network", "Address", address);
+    }
+    for gateway in [ip_config.gateway4, ip_config.gateway6].iter().flatten() {
+        file.set("Network", "Gateway", gateway);
+    }
+    for nameserver in &ip_config.nameservers {
+        file.set("Network", "DNS", nameserver);
+    }
+    if !ip_config.dns_searchlist.is_empty() {
+        file.set("Network", "Domains", ip_config.dns_searchlist.join(" "));
+    }
+    if ip_config.ignore_auto_dns {
+        file.set("DHCPv4", "UseDNS", "no");
+        file.set("DHCPv6", "UseDNS", "no");
+    }
+    if let Some(zone) = &conn.firewall_zone {
+        log::info!(
+            "Firewall zone '{}' of '{}' is ignored by systemd-networkd",
+            zone,
+            conn.id
+        );
+    }
+
+    for route in ip_config.routes4.iter().chain(ip_config.routes6.iter()) {
+        let section = file.add_section("Route");
+        file.add(section, "Destination", route.destination);
+        if let Some(next_hop) = route.next_hop {
+            file.add(section, "Gateway", next_hop);
+        }
+        if let Some(metric) = route.metric {
+            file.add(section, "Metric", metric);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use crate::network::{
+        model::{
+            BondConfig, BondOptions, Connection, ConnectionConfig, GeneralState, IpRoute,
+            Ipv4Method, Ipv6Method, VlanConfig,
+        },
+        NetworkState,
+    };
+    use agama_lib::network::types::{BondMode, DeviceType};
+    use std::collections::HashMap;
+
+    fn content<'a>(files: &'a [super::TargetFile], name: &str) -> &'a str {
+        files
+            .iter()
+            .find(|f| f.name == name)
+            .and_then(|f| f.content.as_deref())
+            .unwrap_or_else(|| panic!("{} not found", name))
+    }
+
+    #[test]
+    fn test_render_networkd() {
+        let mut bond0 = Connection::new("bond0".to_string(), DeviceType::Bond);
+        bond0.interface = Some("bond0".to_string());
+        bond0.config = ConnectionConfig::Bond(BondConfig {
+            mode: BondMode::ActiveBackup,
+            options: BondOptions(HashMap::from([("miimon".to_string(), "100".to_string())])),
+        });
+        bond0.ip_config.method4 = Ipv4Method::Auto;
+        bond0.ip_config.method6 = Ipv6Method::Disabled;
+
+        let mut eth0 = Connection::new("Wired".to_string(), DeviceType::Ethernet);
+        eth0.interface = Some("eth0".to_string());
+        eth0.controller = Some(bond0.uuid);
+
+        let mut vlan = Connection::new("bond0.10".to_string(), DeviceType::Vlan);
+        vlan.interface = Some("bond0.10".to_string());
+        vlan.config = ConnectionConfig::Vlan(VlanConfig {
+            parent: "bond0".to_string(),
+            id: 10,
+            ..Default::default()
+        });
+        vlan.ip_config.method4 = Ipv4Method::Manual;
+        vlan.ip_config.method6 = Ipv6Method::Auto;
+        vlan.ip_config.addresses = vec!["192.168.10.2/24".parse().unwrap()];
+        vlan.ip_config.gateway4 = Some("192.168.10.1".parse().unwrap());
+        vlan.ip_config.routes4 = vec![IpRoute {
+            destination: "10.0.0.0/8".parse().unwrap(),
+            next_hop: Some("192.168.10.254".parse().unwrap()),
+            metric: None,
+        }];
+
+        let network = NetworkState::new(
+            GeneralState::default(),
+            vec![],
+            vec![],
+            vec![bond0, eth0, vlan],
+        );
+        let files = render(&network);
+
+        assert_eq!(
+            content(&files, "50-bond0.netdev"),
+            "[NetDev]\nName=bond0\nKind=bond\n\n[Bond]\nMode=active-backup\nMIIMonitorSec=100ms\n"
+        );
+        assert_eq!(
+            content(&files, "50-bond0.network"),
+            "[Match]\nName=bond0\n\n[Network]\nDHCP=ipv4\nLinkLocalAddressing=no\n\
+             IPv6AcceptRA=no\nVLAN=bond0.10\n"
+        );
+        assert_eq!(
+            content(&files, "50-eth0.network"),
+            "[Match]\nName=eth0\n\n[Network]\nBond=bond0\n"
+        );
+        assert_eq!(
+            content(&files, "50-bond0.10.netdev"),
+            "[NetDev]\nName=bond0.10\nKind=vlan\n\n[VLAN]\nId=10\n"
+        );
+        assert_eq!(
+            content(&files, "50-bond0.10.network"),
+            "[Match]\nName=bond0.10\n\n[Network]\nDHCP=ipv6\nLinkLocalAddressing=ipv6\n\
+             IPv6AcceptRA=yes\nAddress=192.168.10.2/24\nGateway=192.168.10.1\n\n\
+             [Route]\nDestination=10.0.0.0/8\nGateway=192.168.10.254\n"
+        );
+    }
+}
@@ -24,9 +24,11 @@
 //!   agnostic from the real network service (e.g., NetworkManager).
 use crate::network::error::NetworkStateError;
 use agama_lib::network::settings::{
-    BondSettings, IEEE8021XSettings, NetworkConnection, WirelessSettings,
+    BondSettings, BridgePortSettings, BridgeSettings, GeneralSettings, IEEE8021XSettings,
+    InfinibandSettings, IpRouteSettings, MatchSettings, NetworkConnection, TunSettings,
+    VlanSettings, WireguardPeerSettings, WireguardSettings, WirelessSettings,
 };
-use agama_lib::network::types::{BondMode, DeviceState, DeviceType, Status, SSID};
+use agama_lib::network::types::{BondMode, DeviceState, DeviceType, DnsMode, Status, SSID};
 use agama_lib::openapi::schemas;
 use cidr::IpInet;
 use serde::{Deserialize, Serialize};
@@ -222,7 +224,7 @@ impl NetworkState {
         controller: &Connection,
         ports: Vec<String>,
     ) -> Result<(), NetworkStateError> {
-        if let ConnectionConfig::Bond(_) = &controller.config {
+        if let ConnectionConfig::Bond(_) | ConnectionConfig::Bridge(_) = &controller.config {
             let mut controlled = vec![];
             for port in ports {
                 let connection = self
@@ -416,6 +418,30 @@ mod tests {
         assert_eq!(eth0_found.controller, None);
     }
 
+    #[test]
+    fn test_set_bridge_ports() {
+        let mut state = NetworkState::default();
+        let eth0 = Connection {
+            id: "eth0".to_string(),
+            interface: Some("eth0".to_string()),
+            ..Default::default()
+        };
+        let br0 = Connection {
+            id: "br0".to_string(),
+            interface: Some("br0".to_string()),
+            config: ConnectionConfig::Bridge(Default::default()),
+            ..Default::default()
+        };
+
+        state.add_connection(eth0).unwrap();
+        state.add_connection(br0.clone()).unwrap();
+
+        state.set_ports(&br0, vec!["eth0".to_string()]).unwrap();
+
+        let eth0_found = state.get_connection("eth0").unwrap();
+        assert_eq!(eth0_found.controller, Some(br0.uuid));
+    }
+
     #[test]
     fn test_set_bonding_missing_port() {
         let mut state = NetworkState::default();
@@ -433,6 +459,204 @@ mod tests {
         assert!(matches!(error, NetworkStateError::UnknownConnection(_)));
     }
 
+    #[test]
+    fn test_vlan_settings_round_trip() {
+        let settings = NetworkConnection {
+            id: "bond0.10".to_string(),
+            vlan: Some(VlanSettings {
+                parent: "bond0".to_string(),
+                id: 10,
+                protocol: Some("802.1ad".to_string()),
+            }),
+            routes4: vec![IpRouteSettings {
+                destination: "10.0.0.0/8".parse().unwrap(),
+                next_hop: Some("192.168.1.1".parse().unwrap()),
+                metric: Some(100),
+            }],
+            firewall_zone: Some("internal".to_string()),
+            ..Default::default()
+        };
+
+        let conn = Connection::try_from(settings).unwrap();
+        let ConnectionConfig::Vlan(vlan) = &conn.config else {
+            panic!("Unexpected configuration: {:?}", conn.config);
+        };
+        assert_eq!(vlan.parent, "bond0");
+        assert_eq!(vlan.protocol, VlanProtocol::IEEE802_1ad);
+        assert_eq!(conn.ip_config.routes4[0].metric, Some(100));
+        assert_eq!(conn.firewall_zone, Some("internal".to_string()));
+
+        let settings = NetworkConnection::try_from(conn).unwrap();
+        let vlan = settings.vlan.unwrap();
+        assert_eq!(vlan.id, 10);
+        assert_eq!(vlan.protocol, Some("802.1ad".to_string()));
+        assert_eq!(
+            settings.routes4[0].next_hop,
+            Some("192.168.1.1".parse().unwrap())
+        );
+        assert_eq!(settings.firewall_zone, Some("internal".to_string()));
+    }
+
+    #[test]
+    fn test_bridge_settings_round_trip() {
+        let settings = NetworkConnection {
+            id: "br0".to_string(),
+            bridge: Some(BridgeSettings {
+                stp: true,
+                forward_delay: Some(15),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        let settings = NetworkConnection::try_from(conn).unwrap();
+        let bridge = settings.bridge.unwrap();
+        assert!(bridge.stp);
+        assert_eq!(bridge.forward_delay, Some(15));
+
+        let settings = NetworkConnection {
+            id: "eth0".to_string(),
+            bridge_port: Some(BridgePortSettings {
+                priority: Some(32),
+                path_cost: Some(100),
+            }),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        assert!(matches!(conn.config, ConnectionConfig::Ethernet));
+        let settings = NetworkConnection::try_from(conn).unwrap();
+        assert_eq!(settings.bridge_port.unwrap().path_cost, Some(100));
+    }
+
+    #[test]
+    fn test_infiniband_and_tun_settings() {
+        let settings = NetworkConnection {
+            id: "ib0".to_string(),
+            infiniband: Some(InfinibandSettings {
+                p_key: Some(0x8001),
+                parent: Some("ib0".to_string()),
+                transport_mode: Some("connected".to_string()),
+            }),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        let settings = NetworkConnection::try_from(conn).unwrap();
+        let infiniband = settings.infiniband.unwrap();
+        assert_eq!(infiniband.transport_mode, Some("connected".to_string()));
+
+        let settings = NetworkConnection {
+            id: "tap0".to_string(),
+            tun: Some(TunSettings {
+                mode: "tap".to_string(),
+                owner: Some("1000".to_string()),
+                group: None,
+            }),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        let settings = NetworkConnection::try_from(conn).unwrap();
+        assert_eq!(settings.tun.unwrap().mode, "tap");
+
+        let settings = NetworkConnection {
+            id: "tun0".to_string(),
+            tun: Some(TunSettings {
+                mode: "unknown".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let error = Connection::try_from(settings).unwrap_err();
+        assert!(matches!(error, NetworkStateError::InvalidTunMode(_)));
+    }
+
+    #[test]
+    fn test_update_general_state() {
+        let mut state = GeneralState {
+            hostname: "old".to_string(),
+            ..Default::default()
+        };
+        let settings = GeneralSettings {
+            transient_hostname: Some("install".to_string()),
+            nameservers: vec!["192.168.1.1".parse().unwrap()],
+            dns_searchlist: vec!["example.com".to_string()],
+            dns_mode: Some(DnsMode::SystemdResolved),
+            ..Default::default()
+        };
+        state.update(settings).unwrap();
+        assert_eq!(state.hostname, "old");
+        assert_eq!(state.transient_hostname, Some("install".to_string()));
+        assert_eq!(state.dns.searchlist, vec!["example.com".to_string()]);
+        assert_eq!(state.dns.mode, Some(DnsMode::SystemdResolved));
+
+        let settings = GeneralSettings::from(state.clone());
+        assert_eq!(settings.hostname, Some("old".to_string()));
+        assert_eq!(settings.nameservers, state.dns.nameservers);
+
+        let invalid = GeneralSettings {
+            hostname: Some("-invalid_name".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            state.update(invalid),
+            Err(NetworkStateError::InvalidHostname(_))
+        ));
+
+        let mut state = GeneralState::default();
+        let missing = GeneralSettings {
+            dns_searchlist: vec!["example.com".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            state.update(missing),
+            Err(NetworkStateError::MissingGlobalNameservers)
+        ));
+    }
+
+    #[test]
+    fn test_wireguard_settings() {
+        let settings = NetworkConnection {
+            id: "wg0".to_string(),
+            wireguard: Some(WireguardSettings {
+                private_key: Some("cGFzc3dvcmQ=".to_string()),
+                listen_port: Some(51820),
+                peers: vec![WireguardPeerSettings {
+                    public_key: "cHVibGljIGtleQ==".to_string(),
+                    allowed_ips: vec!["10.0.0.0/8".parse().unwrap()],
+                    endpoint: Some("vpn.example.com:51820".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let conn = Connection::try_from(settings).unwrap();
+        assert!(matches!(conn.config, ConnectionConfig::Wireguard(_)));
+        let settings = NetworkConnection::try_from(conn).unwrap();
+        let wireguard = settings.wireguard.unwrap();
+        assert_eq!(wireguard.listen_port, Some(51820));
+        assert_eq!(
+            wireguard.peers[0].endpoint,
+            Some("vpn.example.com:51820".to_string())
+        );
+
+        let settings = NetworkConnection {
+            id: "wg0".to_string(),
+            wireguard: Some(WireguardSettings {
+                peers: vec![WireguardPeerSettings {
+                    endpoint: Some("vpn.example.com".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let error = Connection::try_from(settings).unwrap_err();
+        assert!(matches!(
+            error,
+            NetworkStateError::InvalidWireguardEndpoint(_)
+        ));
+    }
+
     #[test]
     fn test_set_non_controller_ports() {
         let mut state = NetworkState::default();
@@ -460,6 +684,99 @@ pub struct GeneralState {
     pub connectivity: bool,
     pub wireless_enabled: bool,
     pub networking_enabled: bool, // pub network_state: NMSTATE
+    #[serde(default)]
+    pub transient_hostname: Option<String>,
+    #[serde(default)]
+    pub dns: GlobalDns,
+}
+
+impl GeneralState {
+    /// Updates the state with the given settings.
+    ///
+    /// Only the given values are changed. It fails if any of them is not valid.
+    ///
+    /// * `settings`: settings to apply.
+    pub fn update(&mut self, settings: GeneralSettings) -> Result<(), NetworkStateError> {
+        for hostname in [&settings.hostname, &settings.transient_hostname]
+            .into_iter()
+            .flatten()
+        {
+            // the kernel does not accept host names longer than 64 characters
+            if hostname.len() > 64 || !is_valid_hostname(hostname) {
+                return Err(NetworkStateError::InvalidHostname(hostname.to_string()));
+            }
+        }
+        if let Some(domain) = settings
+            .dns_searchlist
+            .iter()
+            .find(|d| !is_valid_hostname(d.trim_end_matches('.')))
+        {
+            return Err(NetworkStateError::InvalidSearchDomain(domain.to_string()));
+        }
+
+        let mut dns = self.dns.clone();
+        if !settings.nameservers.is_empty() {
+            dns.nameservers = settings.nameservers;
+        }
+        if !settings.dns_searchlist.is_empty() {
+            dns.searchlist = settings.dns_searchlist;
+        }
+        if settings.dns_mode.is_some() {
+            dns.mode = settings.dns_mode;
+        }
+        if dns.nameservers.is_empty() && !dns.searchlist.is_empty() {
+            return Err(NetworkStateError::MissingGlobalNameservers);
+        }
+
+        if let Some(hostname) = settings.hostname {
+            self.hostname = hostname;
+        }
+        if settings.transient_hostname.is_some() {
+            self.transient_hostname = settings.transient_hostname;
+        }
+        self.dns = dns;
+        Ok(())
+    }
+}
+
+impl From<GeneralState> for GeneralSettings {
+    fn from(state: GeneralState) -> Self {
+        GeneralSettings {
+            hostname: Some(state.hostname).filter(|h| !h.is_empty()),
+            transient_hostname: state.transient_hostname,
+            nameservers: state.dns.nameservers,
+            dns_searchlist: state.dns.searchlist,
+            dns_mode: state.dns.mode,
+        }
+    }
+}
+
+/// Determines whether the given name is a valid host name (RFC 1123).
+fn is_valid_hostname(name: &str) -> bool {
+    name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Global DNS configuration, which is not bound to any connection.
+#[skip_serializing_none]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct GlobalDns {
+    #[schema(schema_with = schemas::ip_addr_array)]
+    pub nameservers: Vec<IpAddr>,
+    pub searchlist: Vec<String>,
+    pub mode: Option<DnsMode>,
+}
+
+impl GlobalDns {
+    pub fn is_empty(&self) -> bool {
+        self.nameservers.is_empty() && self.searchlist.is_empty() && self.mode.is_none()
+    }
 }
 
 /// Access Point
@@ -487,6 +804,10 @@ pub struct Device {
     pub type_: DeviceType,
     #[serde_as(as = "DisplayFromStr")]
     pub mac_address: MacAddress,
+    /// Kernel driver.
+    pub driver: Option<String>,
+    /// Path of the device (`ID_PATH` udev property, e.g., `pci-0000:00:03.0`).
+    pub path: Option<String>,
     pub ip_config: Option<IpConfig>,
     // Connection.id
     pub connection: Option<String>,
@@ -527,6 +848,9 @@ impl Connection {
             DeviceType::Bond => ConnectionConfig::Bond(Default::default()),
             DeviceType::Vlan => ConnectionConfig::Vlan(Default::default()),
             DeviceType::Bridge => ConnectionConfig::Bridge(Default::default()),
+            DeviceType::Infiniband => ConnectionConfig::Infiniband(Default::default()),
+            DeviceType::Tun => ConnectionConfig::Tun(Default::default()),
+            DeviceType::Wireguard => ConnectionConfig::Wireguard(Default::default()),
         };
         Self {
             id,
@@ -626,10 +950,45 @@ impl TryFrom<NetworkConnection> for Connection {
             connection.config = config.into();
         }
 
+        if let Some(vlan_config) = conn.vlan {
+            let config = VlanConfig::try_from(vlan_config)?;
+            connection.config = config.into();
+        }
+
+        if let Some(bridge_config) = conn.bridge {
+            connection.config = BridgeConfig::from(bridge_config).into();
+        }
+
+        if let Some(infiniband_config) = conn.infiniband {
+            let config = InfinibandConfig::try_from(infiniband_config)?;
+            connection.config = config.into();
+        }
+
+        if let Some(tun_config) = conn.tun {
+            let config = TunConfig::try_from(tun_config)?;
+            connection.config = config.into();
+        }
+
+        if let Some(wireguard_config) = conn.wireguard {
+            let config = WireguardConfig::try_from(wireguard_config)?;
+            connection.config = config.into();
+        }
+
+        if let Some(bridge_port_config) = conn.bridge_port {
+            connection.port_config = PortConfig::Bridge(bridge_port_config.into());
+        }
+
         if let Some(ieee_8021x_config) = conn.ieee_8021x {
             connection.ieee_8021x_config = Some(IEEE8021XConfig::try_from(ieee_8021x_config)?);
         }
 
+        if let Some(match_settings) = conn.match_settings {
+            connection.match_config = MatchConfig::try_from(match_settings)?;
+        }
+
+        connection.ip_config.routes4 = conn.routes4.into_iter().map(IpRoute::from).collect();
+        connection.ip_config.routes6 = conn.routes6.into_iter().map(IpRoute::from).collect();
+        connection.firewall_zone = conn.firewall_zone;
         connection.ip_config.addresses = conn.addresses;
         connection.ip_config.nameservers = conn.nameservers;
         connection.ip_config.dns_searchlist = conn.dns_searchlist;
@@ -658,6 +1017,9 @@ impl TryFrom<Connection> for NetworkConnection {
         let addresses = conn.ip_config.addresses;
         let gateway4 = conn.ip_config.gateway4;
         let gateway6 = conn.ip_config.gateway6;
+        let routes4 = conn.ip_config.routes4.into_iter().map(Into::into).collect();
+        let routes6 = conn.ip_config.routes6.into_iter().map(Into::into).collect();
+        let firewall_zone = conn.firewall_zone;
         let interface = conn.interface;
         let status = Some(conn.status);
         let mtu = conn.mtu;
@@ -665,6 +1027,8 @@ impl TryFrom<Connection> for NetworkConnection {
             .ieee_8021x_config
             .and_then(|x| IEEE8021XSettings::try_from(x).ok());
         let autoconnect = conn.autoconnect;
+        let match_settings = MatchSettings::from(conn.match_config);
+        let match_settings = (!match_settings.is_empty()).then_some(match_settings);
 
         let mut connection = NetworkConnection {
             id,
@@ -682,6 +1046,10 @@ impl TryFrom<Connection> for NetworkConnection {
             mtu,
             ieee_8021x,
             autoconnect,
+            routes4,
+            routes6,
+            firewall_zone,
+            match_settings,
             ..Default::default()
         };
 
@@ -692,9 +1060,28 @@ impl TryFrom<Connection> for NetworkConnection {
             ConnectionConfig::Bond(config) => {
                 connection.bond = Some(BondSettings::try_from(config)?);
             }
+            ConnectionConfig::Vlan(config) => {
+                connection.vlan = Some(config.into());
+            }
+            ConnectionConfig::Bridge(config) => {
+                connection.bridge = Some(config.into());
+            }
+            ConnectionConfig::Infiniband(config) => {
+                connection.infiniband = Some(config.into());
+            }
+            ConnectionConfig::Tun(config) => {
+                connection.tun = Some(config.into());
+            }
+            ConnectionConfig::Wireguard(config) => {
+                connection.wireguard = Some(config.into());
+            }
             _ => {}
         }
 
+        if let PortConfig::Bridge(config) = conn.port_config {
+            connection.bridge_port = Some(config.into());
+        }
+
         Ok(connection)
     }
 }
@@ -711,6 +1098,7 @@ pub enum ConnectionConfig {
     Bridge(BridgeConfig),
     Infiniband(InfinibandConfig),
     Tun(TunConfig),
+    Wireguard(WireguardConfig),
 }
 
 #[derive(Default, Debug, PartialEq, Clone, Serialize, utoipa::ToSchema)]
@@ -732,6 +1120,36 @@ impl From<WirelessConfig> for ConnectionConfig {
     }
 }
 
+impl From<VlanConfig> for ConnectionConfig {
+    fn from(value: VlanConfig) -> Self {
+        Self::Vlan(value)
+    }
+}
+
+impl From<BridgeConfig> for ConnectionConfig {
+    fn from(value: BridgeConfig) -> Self {
+        Self::Bridge(value)
+    }
+}
+
+impl From<InfinibandConfig> for ConnectionConfig {
+    fn from(value: InfinibandConfig) -> Self {
+        Self::Infiniband(value)
+    }
+}
+
+impl From<TunConfig> for ConnectionConfig {
+    fn from(value: TunConfig) -> Self {
+        Self::Tun(value)
+    }
+}
+
+impl From<WireguardConfig> for ConnectionConfig {
+    fn from(value: WireguardConfig) -> Self {
+        Self::Wireguard(value)
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("Invalid MAC address: {0}")]
 pub struct InvalidMacAddress(String);
@@ -825,6 +1243,9 @@ pub struct IpConfig {
 #[skip_serializing_none]
 #[derive(Debug, Default, PartialEq, Clone, Serialize, utoipa::ToSchema)]
 pub struct MatchConfig {
+    /// Permanent MAC address of the device.
+    #[schema(schema_with = schemas::mac_addr6)]
+    pub mac_address: Option<macaddr::MacAddr6>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub driver: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -835,6 +1256,40 @@ pub struct MatchConfig {
     pub kernel: Vec<String>,
 }
 
+impl TryFrom<MatchSettings> for MatchConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: MatchSettings) -> Result<Self, Self::Error> {
+        let mac_address = match &settings.mac_address {
+            Some(mac) => Some(
+                macaddr::MacAddr6::from_str(mac)
+                    .map_err(|_| NetworkStateError::InvalidMacAddress(mac.to_string()))?,
+            ),
+            None => None,
+        };
+
+        Ok(MatchConfig {
+            mac_address,
+            driver: settings.driver,
+            interface: settings.interface,
+            path: settings.path,
+            kernel: settings.kernel,
+        })
+    }
+}
+
+impl From<MatchConfig> for MatchSettings {
+    fn from(config: MatchConfig) -> Self {
+        MatchSettings {
+            mac_address: config.mac_address.map(|m| m.to_string()),
+            driver: config.driver,
+            interface: config.interface,
+            path: config.path,
+            kernel: config.kernel,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("Unknown IP configuration method name: {0}")]
 pub struct UnknownIpMethod(String);
@@ -935,6 +1390,26 @@ pub struct IpRoute {
     pub metric: Option<u32>,
 }
 
+impl From<IpRouteSettings> for IpRoute {
+    fn from(settings: IpRouteSettings) -> Self {
+        IpRoute {
+            destination: settings.destination,
+            next_hop: settings.next_hop,
+            metric: settings.metric,
+        }
+    }
+}
+
+impl From<IpRoute> for IpRouteSettings {
+    fn from(route: IpRoute) -> Self {
+        IpRouteSettings {
+            destination: route.destination,
+            next_hop: route.next_hop,
+            metric: route.metric,
+        }
+    }
+}
+
 impl From<&IpRoute> for HashMap<&str, Value<'_>> {
     fn from(route: &IpRoute) -> Self {
         let mut map: HashMap<&str, Value> = HashMap::from([
@@ -994,6 +1469,34 @@ pub struct VlanConfig {
     pub protocol: VlanProtocol,
 }
 
+impl TryFrom<VlanSettings> for VlanConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: VlanSettings) -> Result<Self, Self::Error> {
+        let protocol = match &settings.protocol {
+            Some(protocol) => VlanProtocol::from_str(protocol)
+                .map_err(|_| NetworkStateError::InvalidVlanProtocol(protocol.to_string()))?,
+            None => VlanProtocol::default(),
+        };
+
+        Ok(VlanConfig {
+            parent: settings.parent,
+            id: settings.id,
+            protocol,
+        })
+    }
+}
+
+impl From<VlanConfig> for VlanSettings {
+    fn from(vlan: VlanConfig) -> Self {
+        VlanSettings {
+            parent: vlan.parent,
+            id: vlan.id,
+            protocol: Some(vlan.protocol.to_string()),
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Default, PartialEq, Clone, Serialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -1509,6 +2012,33 @@ pub struct BridgeConfig {
     pub ageing_time: Option<u32>,
 }
 
+impl From<BridgeSettings> for BridgeConfig {
+    fn from(settings: BridgeSettings) -> Self {
+        BridgeConfig {
+            stp: settings.stp,
+            priority: settings.priority,
+            forward_delay: settings.forward_delay,
+            hello_time: settings.hello_time,
+            max_age: settings.max_age,
+            ageing_time: settings.ageing_time,
+        }
+    }
+}
+
+impl From<BridgeConfig> for BridgeSettings {
+    fn from(bridge: BridgeConfig) -> Self {
+        BridgeSettings {
+            stp: bridge.stp,
+            priority: bridge.priority,
+            forward_delay: bridge.forward_delay,
+            hello_time: bridge.hello_time,
+            max_age: bridge.max_age,
+            ageing_time: bridge.ageing_time,
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Clone, Serialize, utoipa::ToSchema)]
 pub struct BridgePortConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1517,6 +2047,24 @@ pub struct BridgePortConfig {
     pub path_cost: Option<u32>,
 }
 
+impl From<BridgePortSettings> for BridgePortConfig {
+    fn from(settings: BridgePortSettings) -> Self {
+        BridgePortConfig {
+            priority: settings.priority,
+            path_cost: settings.path_cost,
+        }
+    }
+}
+
+impl From<BridgePortConfig> for BridgePortSettings {
+    fn from(port: BridgePortConfig) -> Self {
+        BridgePortSettings {
+            priority: port.priority,
+            path_cost: port.path_cost,
+        }
+    }
+}
+
 #[derive(Default, Debug, PartialEq, Clone, Serialize, utoipa::ToSchema)]
 pub struct InfinibandConfig {
     pub p_key: Option<i32>,
@@ -1524,6 +2072,34 @@ pub struct InfinibandConfig {
     pub transport_mode: InfinibandTransportMode,
 }
 
+impl TryFrom<InfinibandSettings> for InfinibandConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: InfinibandSettings) -> Result<Self, Self::Error> {
+        let transport_mode = match &settings.transport_mode {
+            Some(mode) => InfinibandTransportMode::from_str(mode)
+                .map_err(|_| NetworkStateError::InvalidInfinibandTransportMode(mode.to_string()))?,
+            None => InfinibandTransportMode::default(),
+        };
+
+        Ok(InfinibandConfig {
+            p_key: settings.p_key,
+            parent: settings.parent,
+            transport_mode,
+        })
+    }
+}
+
+impl From<InfinibandConfig> for InfinibandSettings {
+    fn from(infiniband: InfinibandConfig) -> Self {
+        InfinibandSettings {
+            p_key: infiniband.p_key,
+            parent: infiniband.parent,
+            transport_mode: Some(infiniband.transport_mode.to_string()),
+        }
+    }
+}
+
 #[derive(Default, Debug, PartialEq, Clone, Serialize, utoipa::ToSchema)]
 pub enum InfinibandTransportMode {
     #[default]
@@ -1564,6 +2140,32 @@ pub enum TunMode {
     Tap = 2,
 }
 
+#[derive(Debug, Error)]
+#[error("Invalid TUN mode: {0}")]
+pub struct InvalidTunMode(String);
+
+impl FromStr for TunMode {
+    type Err = InvalidTunMode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tun" => Ok(Self::Tun),
+            "tap" => Ok(Self::Tap),
+            _ => Err(InvalidTunMode(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for TunMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self {
+            TunMode::Tun => "tun",
+            TunMode::Tap => "tap",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Default, Debug, PartialEq, Clone, Serialize, utoipa::ToSchema)]
 pub struct TunConfig {
     pub mode: TunMode,
@@ -1571,6 +2173,119 @@ pub struct TunConfig {
     pub owner: Option<String>,
 }
 
+impl TryFrom<TunSettings> for TunConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: TunSettings) -> Result<Self, Self::Error> {
+        let mode = TunMode::from_str(&settings.mode)
+            .map_err(|_| NetworkStateError::InvalidTunMode(settings.mode))?;
+
+        Ok(TunConfig {
+            mode,
+            group: settings.group,
+            owner: settings.owner,
+        })
+    }
+}
+
+impl From<TunConfig> for TunSettings {
+    fn from(tun: TunConfig) -> Self {
+        TunSettings {
+            mode: tun.mode.to_string(),
+            group: tun.group,
+            owner: tun.owner,
+        }
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WireguardConfig {
+    pub private_key: Option<String>,
+    pub listen_port: Option<u16>,
+    pub fwmark: Option<u32>,
+    pub peers: Vec<WireguardPeer>,
+}
+
+#[derive(Default, Debug, PartialEq, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WireguardPeer {
+    pub public_key: String,
+    #[schema(schema_with = schemas::ip_inet_array)]
+    pub allowed_ips: Vec<IpInet>,
+    /// Address and port of the peer (`host:port`).
+    pub endpoint: Option<String>,
+    pub preshared_key: Option<String>,
+    pub persistent_keepalive: Option<u32>,
+}
+
+impl TryFrom<WireguardSettings> for WireguardConfig {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: WireguardSettings) -> Result<Self, Self::Error> {
+        let peers = settings
+            .peers
+            .into_iter()
+            .map(WireguardPeer::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(WireguardConfig {
+            private_key: settings.private_key,
+            listen_port: settings.listen_port,
+            fwmark: settings.fwmark,
+            peers,
+        })
+    }
+}
+
+impl From<WireguardConfig> for WireguardSettings {
+    fn from(wireguard: WireguardConfig) -> Self {
+        WireguardSettings {
+            private_key: wireguard.private_key,
+            listen_port: wireguard.listen_port,
+            fwmark: wireguard.fwmark,
+            peers: wireguard.peers.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<WireguardPeerSettings> for WireguardPeer {
+    type Error = NetworkStateError;
+
+    fn try_from(settings: WireguardPeerSettings) -> Result<Self, Self::Error> {
+        if let Some(endpoint) = &settings.endpoint {
+            let valid = endpoint
+                .rsplit_once(':')
+                .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok());
+            if !valid {
+                return Err(NetworkStateError::InvalidWireguardEndpoint(
+                    endpoint.to_string(),
+                ));
+            }
+        }
+
+        Ok(WireguardPeer {
+            public_key: settings.public_key,
+            allowed_ips: settings.allowed_ips,
+            endpoint: settings.endpoint,
+            preshared_key: settings.preshared_key,
+            persistent_keepalive: settings.persistent_keepalive,
+        })
+    }
+}
+
+impl From<WireguardPeer> for WireguardPeerSettings {
+    fn from(peer: WireguardPeer) -> Self {
+        WireguardPeerSettings {
+            public_key: peer.public_key,
+            allowed_ips: peer.allowed_ips,
+            endpoint: peer.endpoint,
+            preshared_key: peer.preshared_key,
+            persistent_keepalive: peer.persistent_keepalive,
+        }
+    }
+}
+
 /// Represents a network change.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -1583,6 +2298,13 @@ pub enum NetworkChange {
     /// original device name, which is especially useful if the
     /// device gets renamed.
     DeviceUpdated(String, Device),
+    /// The configuration has been applied and it must be confirmed within the given number of
+    /// seconds. Otherwise, it is rolled back.
+    ApplyPending(u64),
+    /// The pending configuration has been confirmed.
+    ApplyConfirmed,
+    /// The pending configuration has been rolled back.
+    ApplyRolledBack,
 }
 
 #[derive(Default, Debug, PartialEq, Clone, Serialize, utoipa::ToSchema)]
@@ -28,6 +28,7 @@ mod adapter;
 mod builder;
 mod client;
 mod dbus;
+pub mod dns;
 mod error;
 mod model;
 mod proxies;
@@ -28,12 +28,15 @@ use agama_lib::error::ServiceError;
 use async_trait::async_trait;
 use core::time;
 use log;
-use std::thread;
+use std::{sync::Mutex, thread, time::Duration};
+use zbus::zvariant::OwnedObjectPath;
 
 /// An adapter for NetworkManager
 pub struct NetworkManagerAdapter<'a> {
     client: NetworkManagerClient<'a>,
     connection: zbus::Connection,
+    /// Checkpoint waiting for confirmation (see [Adapter::write_with_rollback]).
+    pending_checkpoint: Mutex<Option<OwnedObjectPath>>,
 }
 
 impl<'a> NetworkManagerAdapter<'a> {
@@ -41,7 +44,11 @@ impl<'a> NetworkManagerAdapter<'a> {
     pub async fn from_system() -> Result<NetworkManagerAdapter<'a>, ServiceError> {
         let connection = zbus::Connection::system().await?;
         let client = NetworkManagerClient::new(connection.clone()).await?;
-        Ok(Self { client, connection })
+        Ok(Self {
+            client,
+            connection,
+            pending_checkpoint: Mutex::new(None),
+        })
     }
 }
 
@@ -102,12 +109,106 @@ impl<'a> Adapter for NetworkManagerAdapter<'a> {
     ///
     /// * `network`: network model.
     async fn write(&self, network: &NetworkState) -> Result<(), NetworkAdapterError> {
-        let old_state = self.read(StateConfig::default()).await?;
+        if self.pending_checkpoint.lock().unwrap().is_some() {
+            return Err(NetworkAdapterError::PendingCheckpoint);
+        }
+
+        let checkpoint = self
+            .client
+            .create_checkpoint(0)
+            .await
+            .map_err(NetworkAdapterError::Checkpoint)?;
+        self.write_connections(network, &checkpoint).await?;
+        self.client
+            .destroy_checkpoint(&checkpoint.as_ref())
+            .await
+            .map_err(NetworkAdapterError::Checkpoint)?;
+        Ok(())
+    }
+
+    /// Writes the connections to NetworkManager, keeping the checkpoint alive.
+    ///
+    /// NetworkManager rolls the checkpoint back by itself when the timeout expires, so the
+    /// previous configuration is restored even if the service is not running anymore.
+    ///
+    /// * `network`: network model.
+    /// * `timeout`: time to confirm the changes.
+    async fn write_with_rollback(
+        &self,
+        network: &NetworkState,
+        timeout: Duration,
+    ) -> Result<(), NetworkAdapterError> {
+        if self.pending_checkpoint.lock().unwrap().is_some() {
+            return Err(NetworkAdapterError::PendingCheckpoint);
+        }
+
+        let timeout = u32::try_from(timeout.as_secs()).unwrap_or(u32::MAX).max(1);
+        let checkpoint = self
+            .client
+            .create_checkpoint(timeout)
+            .await
+            .map_err(NetworkAdapterError::Checkpoint)?;
+        self.write_connections(network, &checkpoint).await?;
+        *self.pending_checkpoint.lock().unwrap() = Some(checkpoint);
+        Ok(())
+    }
+
+    async fn confirm(&self) -> Result<(), NetworkAdapterError> {
+        let checkpoint = self
+            .pending_checkpoint
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or(NetworkAdapterError::NoPendingCheckpoint)?;
+        self.client
+            .destroy_checkpoint(&checkpoint.as_ref())
+            .await
+            .map_err(NetworkAdapterError::Checkpoint)
+    }
+
+    async fn rollback(&self) -> Result<(), NetworkAdapterError> {
         let checkpoint = self
+            .pending_checkpoint
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or(NetworkAdapterError::NoPendingCheckpoint)?;
+        let exists = self
             .client
-            .create_checkpoint()
+            .checkpoint_exists(&checkpoint.as_ref())
             .await
             .map_err(NetworkAdapterError::Checkpoint)?;
+        if !exists {
+            log::info!(
+                "The checkpoint {} was already rolled back",
+                checkpoint.as_str()
+            );
+            return Ok(());
+        }
+        self.client
+            .rollback_checkpoint(&checkpoint.as_ref())
+            .await
+            .map_err(NetworkAdapterError::Checkpoint)
+    }
+
+    fn watcher(&self) -> Option<Box<dyn Watcher + Send>> {
+        Some(Box::new(NetworkManagerWatcher::new(&self.connection)))
+    }
+}
+
+impl<'a> NetworkManagerAdapter<'a> {
+    /// Writes the general state and the connections to NetworkManager.
+    ///
+    /// If something goes wrong, it rolls back the given checkpoint.
+    ///
+    /// * `network`: network model.
+    /// * `checkpoint`: checkpoint's D-Bus path.
+    async fn write_connections(
+        &self,
+        network: &NetworkState,
+        checkpoint: &OwnedObjectPath,
+    ) -> Result<(), NetworkAdapterError> {
+        let old_state = self.read(StateConfig::default()).await?;
 
         log::info!("Updating the general state {:?}", &network.general_state);
 
@@ -164,16 +265,8 @@ impl<'a> Adapter for NetworkManagerAdapter<'a> {
             }
         }
 
-        self.client
-            .destroy_checkpoint(&checkpoint.as_ref())
-            .await
-            .map_err(NetworkAdapterError::Checkpoint)?;
         Ok(())
     }
-
-    fn watcher(&self) -> Option<Box<dyn Watcher + Send>> {
-        Some(Box::new(NetworkManagerWatcher::new(&self.connection)))
-    }
 }
 
 /// Returns the connections in the order they should be processed.
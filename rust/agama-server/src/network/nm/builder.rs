@@ -76,6 +76,8 @@ impl<'a> DeviceFromProxyBuilder<'a> {
         }
 
         device.mac_address = self.mac_address_from_dbus(self.proxy.hw_address().await?.as_str());
+        device.driver = Some(self.proxy.driver().await?).filter(|d| !d.is_empty());
+        device.path = Some(self.proxy.path().await?).filter(|p| !p.is_empty());
         if let Ok((connection, _)) = self.proxy.get_applied_connection(0).await {
             device.connection = self.connection_id(connection);
         }
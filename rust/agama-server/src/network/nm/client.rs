@@ -26,19 +26,24 @@ use super::dbus::{
     cleanup_dbus_connection, connection_from_dbus, connection_to_dbus, controller_from_dbus,
     merge_dbus_connections,
 };
+use super::dns;
 use super::model::NmDeviceType;
 use super::proxies::{
-    AccessPointProxy, ActiveConnectionProxy, ConnectionProxy, DeviceProxy, NetworkManagerProxy,
-    SettingsProxy, WirelessProxy,
+    AccessPointProxy, ActiveConnectionProxy, ConnectionProxy, DeviceProxy, Hostname1Proxy,
+    NetworkManagerProxy, SettingsProxy, WirelessProxy,
 };
 use crate::network::model::{AccessPoint, Connection, Device, GeneralState};
 use agama_lib::error::ServiceError;
 use agama_lib::network::types::{DeviceType, SSID};
 use log;
+use std::path::Path;
 use uuid::Uuid;
 use zbus;
 use zbus::zvariant::{ObjectPath, OwnedObjectPath};
 
+/// Reload flags to read the configuration again and restart the DNS plugin.
+const RELOAD_CONF_AND_DNS: u32 = 0x01 | 0x04;
+
 /// Simplified NetworkManager D-Bus client.
 ///
 /// Implements a minimal API to be used internally. At this point, it allows to query the list of
@@ -66,19 +71,35 @@ impl<'a> NetworkManagerClient<'a> {
         let hostname = proxy.hostname().await?;
         let wireless_enabled = self.nm_proxy.wireless_enabled().await?;
         let networking_enabled = self.nm_proxy.networking_enabled().await?;
-        // TODO:: Allow to set global DNS configuration
-        // let global_dns_configuration = self.nm_proxy.global_dns_configuration().await?;
         // Fixme: save as NMConnectivityState enum
         let connectivity = self.nm_proxy.connectivity().await? == 4;
+        let dns = dns::read(Path::new("/")).map_err(|e| ServiceError::Anyhow(e.into()))?;
 
         Ok(GeneralState {
             hostname,
             wireless_enabled,
             networking_enabled,
             connectivity,
+            transient_hostname: self.transient_hostname().await,
+            dns,
         })
     }
 
+    /// Returns the transient host name, if systemd-hostnamed is available.
+    async fn transient_hostname(&self) -> Option<String> {
+        let hostname = async {
+            let proxy = Hostname1Proxy::new(&self.connection).await?;
+            proxy.hostname().await
+        };
+        match hostname.await {
+            Ok(hostname) => Some(hostname).filter(|h| !h.is_empty()),
+            Err(error) => {
+                log::warn!("Could not read the transient host name: {}", error);
+                None
+            }
+        }
+    }
+
     /// Updates the general state
     pub async fn update_general_state(&self, state: &GeneralState) -> Result<(), ServiceError> {
         let wireless_enabled = self.nm_proxy.wireless_enabled().await?;
@@ -89,6 +110,25 @@ impl<'a> NetworkManagerClient<'a> {
                 .await?;
         };
 
+        let settings = SettingsProxy::new(&self.connection).await?;
+        if !state.hostname.is_empty() && state.hostname != settings.hostname().await? {
+            settings.save_hostname(&state.hostname).await?;
+        }
+
+        if let Some(hostname) = &state.transient_hostname {
+            if Some(hostname) != self.transient_hostname().await.as_ref() {
+                let proxy = Hostname1Proxy::new(&self.connection).await?;
+                proxy.set_hostname(hostname, false).await?;
+            }
+        }
+
+        let root = Path::new("/");
+        let current_dns = dns::read(root).map_err(|e| ServiceError::Anyhow(e.into()))?;
+        if current_dns != state.dns {
+            dns::write(root, &state.dns).map_err(|e| ServiceError::Anyhow(e.into()))?;
+            self.nm_proxy.reload(RELOAD_CONF_AND_DNS).await?;
+        }
+
         Ok(())
     }
 
@@ -278,11 +318,31 @@ impl<'a> NetworkManagerClient<'a> {
     }
 
     /// Creates a checkpoint.
-    pub async fn create_checkpoint(&self) -> Result<OwnedObjectPath, ServiceError> {
-        let path = self.nm_proxy.checkpoint_create(&[], 0, 0).await?;
+    ///
+    /// * `rollback_timeout`: seconds after which NetworkManager rolls the checkpoint back
+    ///   automatically. 0 means no automatic rollback.
+    pub async fn create_checkpoint(
+        &self,
+        rollback_timeout: u32,
+    ) -> Result<OwnedObjectPath, ServiceError> {
+        let path = self
+            .nm_proxy
+            .checkpoint_create(&[], rollback_timeout, 0)
+            .await?;
         Ok(path)
     }
 
+    /// Determines whether a checkpoint still exists.
+    ///
+    /// * `checkpoint`: checkpoint's D-Bus path.
+    pub async fn checkpoint_exists(
+        &self,
+        checkpoint: &ObjectPath<'_>,
+    ) -> Result<bool, ServiceError> {
+        let checkpoints = self.nm_proxy.checkpoints().await?;
+        Ok(checkpoints.iter().any(|c| c.as_ref() == *checkpoint))
+    }
+
     /// Destroys a checkpoint.
     ///
     /// * `checkpoint`: checkpoint's D-Bus path.
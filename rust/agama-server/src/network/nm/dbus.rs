@@ -45,6 +45,7 @@ const BRIDGE_KEY: &str = "bridge";
 const BRIDGE_PORT_KEY: &str = "bridge-port";
 const INFINIBAND_KEY: &str = "infiniband";
 const TUN_KEY: &str = "tun";
+const WIREGUARD_KEY: &str = "wireguard";
 const IEEE_8021X_KEY: &str = "802-1x";
 
 /// Converts a connection struct into a HashMap that can be sent over D-Bus.
@@ -94,13 +95,16 @@ pub fn connection_to_dbus<'a>(
     result.insert("match", match_config_to_dbus(&conn.match_config));
 
     if conn.is_ethernet() {
-        let ethernet_config = HashMap::from([
+        let mut ethernet_config = HashMap::from([
             (
                 "assigned-mac-address",
                 Value::new(conn.mac_address.to_string()),
             ),
             ("mtu", Value::new(conn.mtu)),
         ]);
+        if let Some(mac_address) = &conn.match_config.mac_address {
+            ethernet_config.insert("mac-address", mac_address.as_bytes().into());
+        }
         result.insert(ETHERNET_KEY, ethernet_config);
     }
 
@@ -116,6 +120,9 @@ pub fn connection_to_dbus<'a>(
                         Value::new(conn.mac_address.to_string()),
                     ),
                 ]));
+                if let Some(mac_address) = &conn.match_config.mac_address {
+                    wireless_dbus_key.insert("mac-address", mac_address.as_bytes().into());
+                }
             }
 
             result.extend(wireless_dbus);
@@ -149,6 +156,13 @@ pub fn connection_to_dbus<'a>(
             connection_dbus.insert("type", TUN_KEY.into());
             result.insert(TUN_KEY, tun_config_to_dbus(tun));
         }
+        ConnectionConfig::Wireguard(wireguard) => {
+            connection_dbus.insert("type", WIREGUARD_KEY.into());
+            if !connection_dbus.contains_key("interface-name") {
+                connection_dbus.insert("interface-name", conn.id.as_str().into());
+            }
+            result.insert(WIREGUARD_KEY, wireguard_config_to_dbus(wireguard));
+        }
         _ => {}
     }
 
@@ -211,6 +225,11 @@ pub fn connection_from_dbus(conn: OwnedNestedHash) -> Result<Connection, NmError
         return Ok(connection);
     }
 
+    if let Some(wireguard_config) = wireguard_config_from_dbus(&conn)? {
+        connection.config = ConnectionConfig::Wireguard(wireguard_config);
+        return Ok(connection);
+    }
+
     if conn.contains_key(DUMMY_KEY) {
         connection.config = ConnectionConfig::Dummy;
         return Ok(connection);
@@ -615,6 +634,90 @@ fn tun_config_from_dbus(conn: &OwnedNestedHash) -> Result<Option<TunConfig>, NmE
     }))
 }
 
+fn wireguard_config_to_dbus(config: &WireguardConfig) -> HashMap<&str, zvariant::Value<'_>> {
+    let peers: Vec<HashMap<&str, Value>> = config
+        .peers
+        .iter()
+        .map(|peer| {
+            let allowed_ips: Vec<String> =
+                peer.allowed_ips.iter().map(|ip| ip.to_string()).collect();
+            let mut peer_dbus = HashMap::from([
+                ("public-key", Value::new(peer.public_key.clone())),
+                ("allowed-ips", Value::new(allowed_ips)),
+            ]);
+            if let Some(endpoint) = &peer.endpoint {
+                peer_dbus.insert("endpoint", Value::new(endpoint.clone()));
+            }
+            if let Some(preshared_key) = &peer.preshared_key {
+                peer_dbus.insert("preshared-key", Value::new(preshared_key.clone()));
+            }
+            if let Some(keepalive) = peer.persistent_keepalive {
+                peer_dbus.insert("persistent-keepalive", Value::new(keepalive));
+            }
+            peer_dbus
+        })
+        .collect();
+
+    let mut wireguard_config: HashMap<&str, zvariant::Value> =
+        HashMap::from([("peers", peers.into())]);
+
+    if let Some(private_key) = &config.private_key {
+        wireguard_config.insert("private-key", private_key.into());
+    }
+
+    if let Some(listen_port) = config.listen_port {
+        wireguard_config.insert("listen-port", Value::new(listen_port as u32));
+    }
+
+    if let Some(fwmark) = config.fwmark {
+        wireguard_config.insert("fwmark", Value::new(fwmark));
+    }
+
+    wireguard_config
+}
+
+fn wireguard_config_from_dbus(conn: &OwnedNestedHash) -> Result<Option<WireguardConfig>, NmError> {
+    let Some(wireguard) = conn.get(WIREGUARD_KEY) else {
+        return Ok(None);
+    };
+
+    let mut config = WireguardConfig {
+        private_key: get_optional_property(wireguard, "private-key")?,
+        listen_port: get_optional_property::<u32>(wireguard, "listen-port")?
+            .and_then(|p| u16::try_from(p).ok())
+            .filter(|p| *p > 0),
+        fwmark: get_optional_property::<u32>(wireguard, "fwmark")?.filter(|m| *m > 0),
+        ..Default::default()
+    };
+
+    if let Some(peers) = wireguard.get("peers") {
+        let peers = peers.downcast_ref::<zbus::zvariant::Array>()?;
+        for peer in peers.iter() {
+            let dict = peer.downcast_ref::<zvariant::Dict>()?;
+            let map = <HashMap<String, zvariant::Value<'_>>>::try_from(dict)?;
+            let map = to_owned_hash(&map)?;
+
+            let mut allowed_ips = vec![];
+            if let Some(ips) = get_optional_property::<zvariant::Array>(&map, "allowed-ips")? {
+                for ip in ips.iter() {
+                    let ip: String = ip.try_into()?;
+                    allowed_ips.push(IpInet::from_str(&ip)?);
+                }
+            }
+
+            config.peers.push(WireguardPeer {
+                public_key: get_property(&map, "public-key")?,
+                allowed_ips,
+                endpoint: get_optional_property(&map, "endpoint")?,
+                preshared_key: get_optional_property(&map, "preshared-key")?,
+                persistent_keepalive: get_optional_property(&map, "persistent-keepalive")?,
+            });
+        }
+    }
+
+    Ok(Some(config))
+}
+
 /// Converts a MatchConfig struct into a HashMap that can be sent over D-Bus.
 ///
 /// * `match_config`: MatchConfig to convert.
@@ -661,9 +764,13 @@ fn base_connection_from_dbus(conn: &OwnedNestedHash) -> Result<Connection, NmErr
     if let Some(ethernet_config) = conn.get(ETHERNET_KEY) {
         base_connection.mac_address = mac_address_from_dbus(ethernet_config)?;
         base_connection.mtu = mtu_from_dbus(ethernet_config);
+        base_connection.match_config.mac_address =
+            permanent_mac_address_from_dbus(ethernet_config)?;
     } else if let Some(wireless_config) = conn.get(WIRELESS_KEY) {
         base_connection.mac_address = mac_address_from_dbus(wireless_config)?;
         base_connection.mtu = mtu_from_dbus(wireless_config);
+        base_connection.match_config.mac_address =
+            permanent_mac_address_from_dbus(wireless_config)?;
     }
 
     base_connection.ip_config = ip_config_from_dbus(conn)?;
@@ -679,6 +786,22 @@ fn mac_address_from_dbus(config: &HashMap<String, OwnedValue>) -> Result<MacAddr
     Ok(MacAddress::from_str(mac_address.as_str())?)
 }
 
+/// Returns the MAC address the connection is restricted to (`mac-address` property).
+fn permanent_mac_address_from_dbus(
+    config: &HashMap<String, OwnedValue>,
+) -> Result<Option<MacAddr6>, NmError> {
+    let Some(mac_address) = get_optional_property::<zvariant::Array>(config, "mac-address")? else {
+        return Ok(None);
+    };
+    let mac_address: Vec<u8> = mac_address
+        .iter()
+        .map(|u| u.downcast_ref::<u8>())
+        .collect::<Result<Vec<u8>, _>>()?;
+    Ok(<[u8; 6]>::try_from(mac_address.as_slice())
+        .ok()
+        .map(MacAddr6::from))
+}
+
 fn mtu_from_dbus(config: &HashMap<String, OwnedValue>) -> u32 {
     get_property(config, "mtu").unwrap_or(0)
 }
@@ -1196,7 +1319,10 @@ mod test {
     use crate::network::{
         model::*,
         nm::{
-            dbus::{BOND_KEY, ETHERNET_KEY, INFINIBAND_KEY, WIRELESS_KEY, WIRELESS_SECURITY_KEY},
+            dbus::{
+                BOND_KEY, ETHERNET_KEY, INFINIBAND_KEY, WIREGUARD_KEY, WIRELESS_KEY,
+                WIRELESS_SECURITY_KEY,
+            },
             error::NmError,
         },
     };
@@ -1282,6 +1408,10 @@ mod test {
         let ip_config = connection.ip_config;
         let match_config = connection.match_config;
         assert_eq!(match_config.kernel, vec!["pci-0000:00:19.0"]);
+        assert_eq!(
+            match_config.mac_address,
+            Some(macaddr::MacAddr6::from_str("52:54:00:12:34:56").unwrap())
+        );
 
         assert_eq!(connection.mac_address.to_string(), "12:34:56:78:9A:BC");
 
@@ -1453,6 +1583,80 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_connection_from_dbus_wireguard() -> anyhow::Result<()> {
+        let uuid = Uuid::new_v4().to_string();
+        let connection_section = HashMap::from([hi("id", "wg0")?, hi("uuid", uuid)?]);
+
+        let peer = HashMap::from([
+            ("public-key", Value::new("cHVibGljIGtleQ==")),
+            (
+                "allowed-ips",
+                Value::new(vec!["10.0.0.0/8", "192.168.1.0/24"]),
+            ),
+            ("endpoint", Value::new("vpn.example.com:51820")),
+            ("persistent-keepalive", Value::new(25_u32)),
+        ]);
+        let wireguard_section = HashMap::from([
+            hi("listen-port", 51820_u32)?,
+            hi("fwmark", 0_u32)?,
+            hi("peers", vec![peer])?,
+        ]);
+
+        let dbus_conn = HashMap::from([
+            ("connection".to_string(), connection_section),
+            (WIREGUARD_KEY.to_string(), wireguard_section),
+        ]);
+
+        let connection = connection_from_dbus(dbus_conn).unwrap();
+        let ConnectionConfig::Wireguard(wireguard) = &connection.config else {
+            panic!("Wrong connection type")
+        };
+        assert_eq!(wireguard.listen_port, Some(51820));
+        assert_eq!(wireguard.fwmark, None);
+        let peer = &wireguard.peers[0];
+        assert_eq!(peer.public_key, "cHVibGljIGtleQ==");
+        assert_eq!(peer.allowed_ips.len(), 2);
+        assert_eq!(peer.endpoint, Some("vpn.example.com:51820".to_string()));
+        assert_eq!(peer.persistent_keepalive, Some(25));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dbus_from_wireguard_connection() {
+        let config = WireguardConfig {
+            private_key: Some("cHJpdmF0ZSBrZXk=".to_string()),
+            peers: vec![WireguardPeer {
+                public_key: "cHVibGljIGtleQ==".to_string(),
+                allowed_ips: vec!["0.0.0.0/0".parse().unwrap()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut wireguard = build_base_connection();
+        wireguard.config = ConnectionConfig::Wireguard(config);
+        let wireguard_dbus = connection_to_dbus(&wireguard, None);
+
+        let connection = wireguard_dbus.get("connection").unwrap();
+        let interface: &str = connection
+            .get("interface-name")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(interface, "agama");
+
+        let wireguard = wireguard_dbus.get(WIREGUARD_KEY).unwrap();
+        let private_key: &str = wireguard
+            .get("private-key")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(private_key, "cHJpdmF0ZSBrZXk=");
+        let peers: zvariant::Array = wireguard.get("peers").unwrap().downcast_ref().unwrap();
+        assert_eq!(peers.len(), 1);
+    }
+
     #[test]
     fn test_connection_from_dbus_ieee_8021x() -> anyhow::Result<()> {
         let connection_section = HashMap::from([
@@ -1781,9 +1985,20 @@ mod test {
 
     #[test]
     fn test_dbus_from_ethernet_connection() {
-        let ethernet = build_base_connection();
+        let mut ethernet = build_base_connection();
+        ethernet.match_config.mac_address =
+            Some(macaddr::MacAddr6::from_str("52:54:00:12:34:56").unwrap());
         let ethernet_dbus = connection_to_dbus(&ethernet, None);
         check_dbus_base_connection(&ethernet_dbus);
+
+        let mac_address: zvariant::Array = ethernet_dbus
+            .get(ETHERNET_KEY)
+            .unwrap()
+            .get("mac-address")
+            .unwrap()
+            .downcast_ref()
+            .unwrap();
+        assert_eq!(mac_address.len(), 6);
     }
 
     #[test]
@@ -1882,6 +2097,7 @@ mod test {
         HashMap::from([
             hi("auto-negotiate", true).unwrap(),
             hi("assigned-mac-address", "12:34:56:78:9A:BC").unwrap(),
+            hi("mac-address", vec![0x52_u8, 0x54, 0x00, 0x12, 0x34, 0x56]).unwrap(),
             hi("mtu", 9000_u32).unwrap(),
         ])
     }
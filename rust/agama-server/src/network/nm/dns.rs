@@ -0,0 +1,148 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Global DNS configuration for NetworkManager.
+//!
+//! The DNS processing mode cannot be set through D-Bus, so the global DNS configuration is written
+//! to a configuration snippet (`conf.d/90-agama-dns.conf`) which is read back to find out the
+//! current values. The same file is written to the installed system.
+
+use crate::network::model::GlobalDns;
+use agama_lib::network::types::DnsMode;
+use std::{fmt::Write, fs, io, path::Path};
+
+/// Location of the configuration snippet, relative to the root directory.
+pub const DNS_CONF_PATH: &str = "etc/NetworkManager/conf.d/90-agama-dns.conf";
+
+/// Returns the content of the configuration snippet, or `None` if there is nothing to configure.
+///
+/// * `dns`: global DNS configuration.
+pub fn render(dns: &GlobalDns) -> Option<String> {
+    if dns.is_empty() {
+        return None;
+    }
+
+    let mut sections = vec![];
+    if let Some(mode) = dns.mode {
+        sections.push(format!("[main]\ndns={}\n", mode));
+    }
+    if !dns.nameservers.is_empty() {
+        let mut section = "[global-dns]\n".to_string();
+        if !dns.searchlist.is_empty() {
+            _ = writeln!(section, "searches={}", dns.searchlist.join(","));
+        }
+        let servers: Vec<String> = dns.nameservers.iter().map(|s| s.to_string()).collect();
+        _ = write!(
+            section,
+            "\n[global-dns-domain-*]\nservers={}\n",
+            servers.join(",")
+        );
+        sections.push(section);
+    }
+    Some(sections.join("\n"))
+}
+
+/// Parses the content of the configuration snippet.
+///
+/// * `content`: content of the snippet.
+pub fn parse(content: &str) -> GlobalDns {
+    let mut dns = GlobalDns::default();
+    let mut section = "";
+
+    for line in content.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let values = value
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        match (section, key.trim()) {
+            ("main", "dns") => dns.mode = DnsMode::try_from(value.trim()).ok(),
+            ("global-dns", "searches") => dns.searchlist = values.map(String::from).collect(),
+            ("global-dns-domain-*", "servers") => {
+                dns.nameservers = values.filter_map(|v| v.parse().ok()).collect()
+            }
+            _ => {}
+        }
+    }
+    dns
+}
+
+/// Reads the global DNS configuration from the given root directory.
+///
+/// * `root`: root directory.
+pub fn read(root: &Path) -> io::Result<GlobalDns> {
+    match fs::read_to_string(root.join(DNS_CONF_PATH)) {
+        Ok(content) => Ok(parse(&content)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(GlobalDns::default()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Writes the global DNS configuration into the given root directory.
+///
+/// The snippet is removed if there is nothing to configure.
+///
+/// * `root`: root directory.
+/// * `dns`: global DNS configuration.
+pub fn write(root: &Path, dns: &GlobalDns) -> io::Result<()> {
+    let path = root.join(DNS_CONF_PATH);
+    match render(dns) {
+        Some(content) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)
+        }
+        None if path.exists() => fs::remove_file(path),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, render};
+    use crate::network::model::GlobalDns;
+    use agama_lib::network::types::DnsMode;
+
+    #[test]
+    fn test_render_and_parse() {
+        let dns = GlobalDns {
+            nameservers: vec!["192.168.1.1".parse().unwrap(), "fd00::1".parse().unwrap()],
+            searchlist: vec!["example.com".to_string(), "example.net".to_string()],
+            mode: Some(DnsMode::SystemdResolved),
+        };
+
+        let content = render(&dns).unwrap();
+        assert_eq!(
+            content,
+            "[main]\ndns=systemd-resolved\n\n[global-dns]\nsearches=example.com,example.net\n\n\
+             [global-dns-domain-*]\nservers=192.168.1.1,fd00::1\n"
+        );
+        assert_eq!(parse(&content), dns);
+
+        assert_eq!(render(&GlobalDns::default()), None);
+    }
+}
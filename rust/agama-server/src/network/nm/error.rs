@@ -60,6 +60,8 @@ pub enum NmError {
     InvalidNetworkPrefix(#[from] NetworkLengthTooLongError),
     #[error("Invalid network address: '{0}'")]
     InvalidNetworkAddress(#[from] AddrParseError),
+    #[error("Invalid network: '{0}'")]
+    InvalidNetwork(#[from] cidr::errors::NetworkParseError),
 }
 
 impl From<NmError> for NetworkStateError {
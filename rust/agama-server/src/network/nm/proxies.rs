@@ -902,3 +902,21 @@ pub trait IP6Config {
     #[zbus(property)]
     fn searches(&self) -> zbus::Result<Vec<String>>;
 }
+
+/// # D-Bus interface proxy for: `org.freedesktop.hostname1`
+///
+/// It is not part of NetworkManager, but it is needed to set the transient host name.
+#[proxy(
+    interface = "org.freedesktop.hostname1",
+    default_service = "org.freedesktop.hostname1",
+    default_path = "/org/freedesktop/hostname1",
+    gen_blocking = false
+)]
+pub trait Hostname1 {
+    /// SetHostname method
+    fn set_hostname(&self, hostname: &str, interactive: bool) -> zbus::Result<()>;
+
+    /// Hostname property
+    #[zbus(property)]
+    fn hostname(&self) -> zbus::Result<String>;
+}
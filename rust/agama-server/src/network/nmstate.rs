@@ -0,0 +1,1022 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Converts the network configuration from/to the [nmstate](https://nmstate.io) declarative
+//! YAML format.
+//!
+//! Only the subset of nmstate which has an equivalent in the network model is supported:
+//! ethernet, bond, linux-bridge, VLAN, InfiniBand, dummy and loopback interfaces, their IP
+//! settings, the routes and the DNS configuration. The DNS configuration is global in nmstate, so
+//! it is exported as the union of the connections settings and imported to all the connections
+//! but the ports.
+//!
+//! As in nmstate, the interfaces which are not included in the document are left untouched and
+//! the ones whose state is `absent` are removed.
+
+use super::model::{
+    BondConfig, BondOptions, BridgeConfig, BridgePortConfig, Connection, ConnectionConfig,
+    InfinibandConfig, InfinibandTransportMode, IpRoute, Ipv4Method, Ipv6Method, MacAddress,
+    PortConfig, VlanConfig, VlanProtocol,
+};
+use agama_lib::network::types::{BondMode, Status};
+use cidr::IpInet;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+use thiserror::Error;
+use yaml_rust::{yaml::Hash, Yaml, YamlEmitter, YamlLoader};
+
+#[derive(Debug, Error)]
+pub enum NmstateError {
+    #[error("Invalid YAML document: {0}")]
+    InvalidYaml(String),
+    #[error("Invalid nmstate document: {0}")]
+    InvalidDocument(#[from] serde_json::Error),
+    #[error("Unsupported type for interface '{0}'")]
+    UnsupportedType(String),
+    #[error("Missing '{1}' section for interface '{0}'")]
+    MissingSection(String, &'static str),
+    #[error("Invalid value for interface '{0}': {1}")]
+    InvalidValue(String, String),
+    #[error("Unknown interface '{0}'")]
+    UnknownInterface(String),
+}
+
+/// nmstate document.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateDocument {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dns_resolver: Option<DnsResolver>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    routes: Option<Routes>,
+    #[serde(default)]
+    interfaces: Vec<Interface>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DnsResolver {
+    #[serde(default)]
+    config: DnsConfig,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DnsConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    server: Vec<IpAddr>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    search: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Routes {
+    #[serde(default)]
+    config: Vec<Route>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Route {
+    destination: IpInet,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next_hop_interface: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next_hop_address: Option<IpAddr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metric: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state: Option<InterfaceState>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum InterfaceState {
+    #[default]
+    Up,
+    Down,
+    Absent,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum InterfaceType {
+    #[default]
+    Ethernet,
+    Bond,
+    LinuxBridge,
+    Vlan,
+    Infiniband,
+    Dummy,
+    Loopback,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Interface {
+    name: String,
+    #[serde(rename = "type", default)]
+    kind: InterfaceType,
+    #[serde(default)]
+    state: InterfaceState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mtu: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mac_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ipv4: Option<IpSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ipv6: Option<IpSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    link_aggregation: Option<LinkAggregation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bridge: Option<Bridge>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vlan: Option<Vlan>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    infiniband: Option<Infiniband>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct IpSettings {
+    enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dhcp: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    autoconf: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    address: Vec<IpAddress>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct IpAddress {
+    ip: IpAddr,
+    prefix_length: u8,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LinkAggregation {
+    mode: String,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    options: Map<String, Value>,
+    #[serde(default)]
+    port: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Bridge {
+    #[serde(default)]
+    options: BridgeOptions,
+    #[serde(default)]
+    port: Vec<BridgePort>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct BridgeOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mac_ageing_time: Option<u32>,
+    #[serde(default)]
+    stp: Stp,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Stp {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    forward_delay: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hello_time: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_age: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct BridgePort {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stp_priority: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stp_path_cost: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Vlan {
+    base_iface: String,
+    id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    protocol: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Infiniband {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base_iface: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pkey: Option<Value>,
+    #[serde(default)]
+    mode: String,
+}
+
+/// Network configuration described by an nmstate document.
+#[derive(Debug, Default)]
+pub struct NmstateConfig {
+    /// Connections to add or update.
+    pub connections: Vec<Connection>,
+    /// Interfaces whose connections must be removed.
+    pub removed: Vec<String>,
+}
+
+impl NmstateDocument {
+    /// Parses an nmstate YAML document.
+    pub fn from_yaml(yaml: &str) -> Result<Self, NmstateError> {
+        let docs = YamlLoader::load_from_str(yaml)
+            .map_err(|e| NmstateError::InvalidYaml(e.to_string()))?;
+        let value = match docs.into_iter().next() {
+            Some(doc) => yaml_to_json(doc)?,
+            None => Value::Object(Map::new()),
+        };
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Writes the document as YAML.
+    pub fn to_yaml(&self) -> Result<String, NmstateError> {
+        let yaml = json_to_yaml(serde_json::to_value(self)?);
+        let mut output = String::new();
+        YamlEmitter::new(&mut output)
+            .dump(&yaml)
+            .map_err(|e| NmstateError::InvalidYaml(format!("{:?}", e)))?;
+        output.push('\n');
+        Ok(output)
+    }
+
+    /// Builds the document from a list of connections.
+    ///
+    /// Wireless and TUN connections, which are not supported by nmstate, are skipped.
+    pub fn from_connections(connections: &[Connection]) -> Self {
+        let connections: Vec<&Connection> =
+            connections.iter().filter(|c| !c.is_removed()).collect();
+        let mut document = Self::default();
+        let mut routes = vec![];
+        let mut dns = DnsConfig::default();
+
+        for conn in &connections {
+            let Some(iface) = interface_from_connection(conn, &connections) else {
+                log::info!(
+                    "Skipping connection '{}': not supported by nmstate",
+                    conn.id
+                );
+                continue;
+            };
+            routes.extend(routes_from_connection(&iface.name, conn));
+
+            for server in &conn.ip_config.nameservers {
+                if !dns.server.contains(server) {
+                    dns.server.push(*server);
+                }
+            }
+            for search in &conn.ip_config.dns_searchlist {
+                if !dns.search.contains(search) {
+                    dns.search.push(search.clone());
+                }
+            }
+            document.interfaces.push(iface);
+        }
+
+        if !routes.is_empty() {
+            document.routes = Some(Routes { config: routes });
+        }
+        if !dns.server.is_empty() || !dns.search.is_empty() {
+            document.dns_resolver = Some(DnsResolver { config: dns });
+        }
+        document
+    }
+
+    /// Converts the document into a list of connections.
+    ///
+    /// The ports which are not defined in the document get a connection with IP disabled.
+    pub fn to_config(&self) -> Result<NmstateConfig, NmstateError> {
+        let mut config = NmstateConfig::default();
+        let mut controllers: Vec<(String, Vec<ControllerPort>)> = vec![];
+
+        for iface in &self.interfaces {
+            if iface.state == InterfaceState::Absent {
+                config.removed.push(iface.name.clone());
+                continue;
+            }
+            config.connections.push(connection_from_interface(iface)?);
+            if let Some(bond) = &iface.link_aggregation {
+                let ports = bond.port.iter().map(|p| (p.clone(), None)).collect();
+                controllers.push((iface.name.clone(), ports));
+            }
+            if let Some(bridge) = &iface.bridge {
+                let ports = bridge
+                    .port
+                    .iter()
+                    .map(|p| {
+                        let port_config = BridgePortConfig {
+                            priority: p.stp_priority,
+                            path_cost: p.stp_path_cost,
+                        };
+                        (p.name.clone(), Some(port_config))
+                    })
+                    .collect();
+                controllers.push((iface.name.clone(), ports));
+            }
+        }
+
+        for (controller, ports) in controllers {
+            let uuid = find_connection(&mut config.connections, &controller)
+                .map(|c| c.uuid)
+                .ok_or(NmstateError::UnknownInterface(controller))?;
+            for (port, port_config) in ports {
+                let conn = match find_connection(&mut config.connections, &port) {
+                    Some(conn) => conn,
+                    None => {
+                        config.connections.push(Connection {
+                            id: port.clone(),
+                            interface: Some(port),
+                            ..Default::default()
+                        });
+                        config.connections.last_mut().unwrap()
+                    }
+                };
+                conn.controller = Some(uuid);
+                conn.ip_config.method4 = Ipv4Method::Disabled;
+                conn.ip_config.method6 = Ipv6Method::Disabled;
+                if let Some(port_config) = port_config {
+                    conn.port_config = PortConfig::Bridge(port_config);
+                }
+            }
+        }
+
+        let routes = self.routes.as_ref().map(|r| r.config.as_slice());
+        for route in routes.unwrap_or_default() {
+            if route.state == Some(InterfaceState::Absent) {
+                continue;
+            }
+            let name = route.next_hop_interface.clone().ok_or_else(|| {
+                NmstateError::InvalidValue(
+                    route.destination.to_string(),
+                    "missing next-hop-interface".to_string(),
+                )
+            })?;
+            let conn = find_connection(&mut config.connections, &name)
+                .ok_or(NmstateError::UnknownInterface(name))?;
+            add_route(conn, route);
+        }
+
+        if let Some(dns) = &self.dns_resolver {
+            for conn in config.connections.iter_mut() {
+                if conn.controller.is_some() {
+                    continue;
+                }
+                conn.ip_config.nameservers = dns.config.server.clone();
+                conn.ip_config.dns_searchlist = dns.config.search.clone();
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Port name and, for bridges, its configuration.
+type ControllerPort = (String, Option<BridgePortConfig>);
+
+fn connection_name(conn: &Connection) -> &str {
+    conn.interface.as_deref().unwrap_or(&conn.id)
+}
+
+fn find_connection<'a>(
+    connections: &'a mut [Connection],
+    name: &str,
+) -> Option<&'a mut Connection> {
+    connections.iter_mut().find(|c| connection_name(c) == name)
+}
+
+fn interface_from_connection(conn: &Connection, connections: &[&Connection]) -> Option<Interface> {
+    let name = connection_name(conn).to_string();
+    let ports: Vec<&&Connection> = connections
+        .iter()
+        .filter(|c| c.controller == Some(conn.uuid))
+        .collect();
+
+    let mut iface = Interface {
+        name,
+        state: match conn.status {
+            Status::Down => InterfaceState::Down,
+            _ => InterfaceState::Up,
+        },
+        mtu: (conn.mtu > 0).then_some(conn.mtu),
+        mac_address: match &conn.mac_address {
+            MacAddress::MacAddress(mac) => Some(mac.to_string()),
+            _ => None,
+        },
+        ipv4: Some(ipv4_settings(conn)),
+        ipv6: ipv6_settings(conn),
+        ..Default::default()
+    };
+
+    iface.kind = match &conn.config {
+        ConnectionConfig::Ethernet => InterfaceType::Ethernet,
+        ConnectionConfig::Dummy => InterfaceType::Dummy,
+        ConnectionConfig::Loopback => InterfaceType::Loopback,
+        ConnectionConfig::Bond(bond) => {
+            iface.link_aggregation = Some(LinkAggregation {
+                mode: bond.mode.to_string(),
+                options: bond
+                    .options
+                    .0
+                    .iter()
+                    .map(|(k, v)| (k.clone(), option_value(v)))
+                    .collect(),
+                port: ports
+                    .iter()
+                    .map(|p| connection_name(p).to_string())
+                    .collect(),
+            });
+            InterfaceType::Bond
+        }
+        ConnectionConfig::Bridge(bridge) => {
+            let port = ports
+                .iter()
+                .map(|p| {
+                    let mut port = BridgePort {
+                        name: connection_name(p).to_string(),
+                        stp_priority: None,
+                        stp_path_cost: None,
+                    };
+                    if let PortConfig::Bridge(config) = &p.port_config {
+                        port.stp_priority = config.priority;
+                        port.stp_path_cost = config.path_cost;
+                    }
+                    port
+                })
+                .collect();
+            iface.bridge = Some(Bridge {
+                options: BridgeOptions {
+                    mac_ageing_time: bridge.ageing_time,
+                    stp: Stp {
+                        enabled: bridge.stp,
+                        forward_delay: bridge.forward_delay,
+                        hello_time: bridge.hello_time,
+                        max_age: bridge.max_age,
+                        priority: bridge.priority,
+                    },
+                },
+                port,
+            });
+            InterfaceType::LinuxBridge
+        }
+        ConnectionConfig::Vlan(vlan) => {
+            iface.vlan = Some(Vlan {
+                base_iface: vlan.parent.clone(),
+                id: vlan.id,
+                protocol: Some(vlan.protocol.to_string().to_lowercase()),
+            });
+            InterfaceType::Vlan
+        }
+        ConnectionConfig::Infiniband(infiniband) => {
+            iface.infiniband = Some(Infiniband {
+                base_iface: infiniband.parent.clone(),
+                pkey: infiniband
+                    .p_key
+                    .map(|k| Value::String(format!("{:#06x}", k))),
+                mode: infiniband.transport_mode.to_string(),
+            });
+            InterfaceType::Infiniband
+        }
+        ConnectionConfig::Wireless(_)
+        | ConnectionConfig::Tun(_)
+        | ConnectionConfig::Wireguard(_) => return None,
+    };
+
+    Some(iface)
+}
+
+/// Exports numeric bond options as numbers, as nmstate expects.
+fn option_value(value: &str) -> Value {
+    match value.parse::<u64>() {
+        Ok(number) => Value::Number(number.into()),
+        Err(_) => Value::String(value.to_string()),
+    }
+}
+
+fn ipv4_settings(conn: &Connection) -> IpSettings {
+    let ip_config = &conn.ip_config;
+    let mut settings = IpSettings {
+        enabled: ip_config.method4 != Ipv4Method::Disabled,
+        ..Default::default()
+    };
+    if settings.enabled {
+        settings.dhcp = Some(ip_config.method4 == Ipv4Method::Auto);
+        settings.address = addresses(conn, true);
+    }
+    settings
+}
+
+fn ipv6_settings(conn: &Connection) -> Option<IpSettings> {
+    let ip_config = &conn.ip_config;
+    let mut settings = IpSettings::default();
+    match ip_config.method6 {
+        Ipv6Method::Ignore => return None,
+        Ipv6Method::Disabled => return Some(settings),
+        Ipv6Method::Auto => {
+            settings.dhcp = Some(true);
+            settings.autoconf = Some(true);
+        }
+        Ipv6Method::Dhcp => {
+            settings.dhcp = Some(true);
+            settings.autoconf = Some(false);
+        }
+        Ipv6Method::Manual | Ipv6Method::LinkLocal => {
+            settings.dhcp = Some(false);
+            settings.autoconf = Some(false);
+        }
+    }
+    settings.enabled = true;
+    settings.address = addresses(conn, false);
+    Some(settings)
+}
+
+fn addresses(conn: &Connection, ipv4: bool) -> Vec<IpAddress> {
+    conn.ip_config
+        .addresses
+        .iter()
+        .filter(|a| a.is_ipv4() == ipv4)
+        .map(|a| IpAddress {
+            ip: a.address(),
+            prefix_length: a.network_length(),
+        })
+        .collect()
+}
+
+fn routes_from_connection(name: &str, conn: &Connection) -> Vec<Route> {
+    let ip_config = &conn.ip_config;
+    let mut routes = vec![];
+
+    let gateways = [
+        (ip_config.gateway4, IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        (ip_config.gateway6, IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+    ];
+    for (gateway, any) in gateways {
+        if let Some(gateway) = gateway {
+            routes.push(Route {
+                destination: IpInet::new(any, 0).unwrap(),
+                next_hop_interface: Some(name.to_string()),
+                next_hop_address: Some(gateway),
+                metric: None,
+                state: None,
+            });
+        }
+    }
+
+    for route in ip_config.routes4.iter().chain(ip_config.routes6.iter()) {
+        routes.push(Route {
+            destination: route.destination,
+            next_hop_interface: Some(name.to_string()),
+            next_hop_address: route.next_hop,
+            metric: route.metric,
+            state: None,
+        });
+    }
+    routes
+}
+
+fn connection_from_interface(iface: &Interface) -> Result<Connection, NmstateError> {
+    let name = &iface.name;
+    let invalid = |value: String| NmstateError::InvalidValue(name.clone(), value);
+
+    let mut conn = Connection {
+        id: name.clone(),
+        interface: Some(name.clone()),
+        mtu: iface.mtu.unwrap_or_default(),
+        ..Default::default()
+    };
+    if iface.state == InterfaceState::Down {
+        conn.status = Status::Down;
+    }
+    if let Some(mac) = &iface.mac_address {
+        conn.mac_address = MacAddress::from_str(mac).map_err(|_| invalid(mac.clone()))?;
+    }
+
+    conn.config = match iface.kind {
+        InterfaceType::Ethernet => ConnectionConfig::Ethernet,
+        InterfaceType::Dummy => ConnectionConfig::Dummy,
+        InterfaceType::Loopback => ConnectionConfig::Loopback,
+        InterfaceType::Bond => {
+            let bond = iface
+                .link_aggregation
+                .as_ref()
+                .ok_or(NmstateError::MissingSection(
+                    name.clone(),
+                    "link-aggregation",
+                ))?;
+            let mode =
+                BondMode::try_from(bond.mode.as_str()).map_err(|e| invalid(e.to_string()))?;
+            let options: HashMap<String, String> = bond
+                .options
+                .iter()
+                .map(|(k, v)| {
+                    let value = match v {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (k.clone(), value)
+                })
+                .collect();
+            ConnectionConfig::Bond(BondConfig {
+                mode,
+                options: BondOptions(options),
+            })
+        }
+        InterfaceType::LinuxBridge => {
+            let options = iface.bridge.as_ref().map(|b| &b.options);
+            let stp = options.map(|o| &o.stp);
+            ConnectionConfig::Bridge(BridgeConfig {
+                stp: stp.is_some_and(|s| s.enabled),
+                priority: stp.and_then(|s| s.priority),
+                forward_delay: stp.and_then(|s| s.forward_delay),
+                hello_time: stp.and_then(|s| s.hello_time),
+                max_age: stp.and_then(|s| s.max_age),
+                ageing_time: options.and_then(|o| o.mac_ageing_time),
+            })
+        }
+        InterfaceType::Vlan => {
+            let vlan = iface
+                .vlan
+                .as_ref()
+                .ok_or(NmstateError::MissingSection(name.clone(), "vlan"))?;
+            let protocol = match &vlan.protocol {
+                Some(protocol) if protocol.eq_ignore_ascii_case("802.1ad") => {
+                    VlanProtocol::IEEE802_1ad
+                }
+                Some(protocol) if protocol.eq_ignore_ascii_case("802.1q") => {
+                    VlanProtocol::IEEE802_1Q
+                }
+                Some(protocol) => return Err(invalid(protocol.clone())),
+                None => VlanProtocol::default(),
+            };
+            ConnectionConfig::Vlan(VlanConfig {
+                parent: vlan.base_iface.clone(),
+                id: vlan.id,
+                protocol,
+            })
+        }
+        InterfaceType::Infiniband => {
+            let infiniband = iface
+                .infiniband
+                .as_ref()
+                .ok_or(NmstateError::MissingSection(name.clone(), "infiniband"))?;
+            let p_key = match &infiniband.pkey {
+                None => None,
+                Some(Value::Number(number)) => number.as_i64().and_then(|n| i32::try_from(n).ok()),
+                Some(Value::String(pkey)) => {
+                    let parsed = match pkey.strip_prefix("0x") {
+                        Some(hex) => i32::from_str_radix(hex, 16).ok(),
+                        None => pkey.parse().ok(),
+                    };
+                    Some(parsed.ok_or_else(|| invalid(pkey.clone()))?)
+                }
+                Some(other) => return Err(invalid(other.to_string())),
+            };
+            let transport_mode = if infiniband.mode.is_empty() {
+                InfinibandTransportMode::default()
+            } else {
+                InfinibandTransportMode::from_str(&infiniband.mode)
+                    .map_err(|e| invalid(e.to_string()))?
+            };
+            ConnectionConfig::Infiniband(InfinibandConfig {
+                p_key,
+                parent: infiniband.base_iface.clone(),
+                transport_mode,
+            })
+        }
+        InterfaceType::Unknown => return Err(NmstateError::UnsupportedType(name.clone())),
+    };
+
+    if let Some(ipv4) = &iface.ipv4 {
+        conn.ip_config.method4 = match ipv4 {
+            IpSettings { enabled: false, .. } => Ipv4Method::Disabled,
+            IpSettings {
+                dhcp: Some(true), ..
+            } => Ipv4Method::Auto,
+            IpSettings { address, .. } if !address.is_empty() => Ipv4Method::Manual,
+            _ => Ipv4Method::LinkLocal,
+        };
+        add_addresses(&mut conn, ipv4, &invalid)?;
+    }
+
+    if let Some(ipv6) = &iface.ipv6 {
+        let dhcp = ipv6.dhcp.unwrap_or_default();
+        let autoconf = ipv6.autoconf.unwrap_or_default();
+        conn.ip_config.method6 = match ipv6 {
+            IpSettings { enabled: false, .. } => Ipv6Method::Disabled,
+            _ if autoconf => Ipv6Method::Auto,
+            _ if dhcp => Ipv6Method::Dhcp,
+            IpSettings { address, .. } if !address.is_empty() => Ipv6Method::Manual,
+            _ => Ipv6Method::LinkLocal,
+        };
+        add_addresses(&mut conn, ipv6, &invalid)?;
+    }
+
+    Ok(conn)
+}
+
+fn add_addresses(
+    conn: &mut Connection,
+    settings: &IpSettings,
+    invalid: &impl Fn(String) -> NmstateError,
+) -> Result<(), NmstateError> {
+    for address in &settings.address {
+        let inet = IpInet::new(address.ip, address.prefix_length)
+            .map_err(|_| invalid(format!("{}/{}", address.ip, address.prefix_length)))?;
+        conn.ip_config.addresses.push(inet);
+    }
+    Ok(())
+}
+
+fn add_route(conn: &mut Connection, route: &Route) {
+    let ip_config = &mut conn.ip_config;
+    if route.destination.network_length() == 0 && route.metric.is_none() {
+        if let Some(gateway) = route.next_hop_address {
+            match gateway {
+                IpAddr::V4(_) => ip_config.gateway4 = Some(gateway),
+                IpAddr::V6(_) => ip_config.gateway6 = Some(gateway),
+            }
+            return;
+        }
+    }
+
+    let ip_route = IpRoute {
+        destination: route.destination,
+        next_hop: route.next_hop_address,
+        metric: route.metric,
+    };
+    if route.destination.is_ipv4() {
+        ip_config.routes4.push(ip_route);
+    } else {
+        ip_config.routes6.push(ip_route);
+    }
+}
+
+fn yaml_to_json(yaml: Yaml) -> Result<Value, NmstateError> {
+    let value = match yaml {
+        Yaml::Null => Value::Null,
+        Yaml::Boolean(value) => Value::Bool(value),
+        Yaml::Integer(value) => Value::Number(value.into()),
+        Yaml::Real(value) => value
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or(Value::String(value)),
+        Yaml::String(value) => Value::String(value),
+        Yaml::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(yaml_to_json)
+                .collect::<Result<_, _>>()?,
+        ),
+        Yaml::Hash(hash) => {
+            let mut map = Map::new();
+            for (key, value) in hash {
+                let key = match key {
+                    Yaml::String(key) | Yaml::Real(key) => key,
+                    Yaml::Integer(key) => key.to_string(),
+                    Yaml::Boolean(key) => key.to_string(),
+                    other => {
+                        return Err(NmstateError::InvalidYaml(format!(
+                            "invalid key {:?}",
+                            other
+                        )))
+                    }
+                };
+                map.insert(key, yaml_to_json(value)?);
+            }
+            Value::Object(map)
+        }
+        Yaml::Alias(_) | Yaml::BadValue => {
+            return Err(NmstateError::InvalidYaml("unsupported value".to_string()))
+        }
+    };
+    Ok(value)
+}
+
+/// Keys written first, so the documents are easier to read.
+const LEADING_KEYS: [&str; 3] = ["name", "type", "state"];
+
+fn json_to_yaml(value: Value) -> Yaml {
+    match value {
+        Value::Null => Yaml::Null,
+        Value::Bool(value) => Yaml::Boolean(value),
+        Value::Number(number) => match number.as_i64() {
+            Some(number) => Yaml::Integer(number),
+            None => Yaml::Real(number.to_string()),
+        },
+        Value::String(value) => Yaml::String(value),
+        Value::Array(values) => Yaml::Array(values.into_iter().map(json_to_yaml).collect()),
+        Value::Object(mut map) => {
+            let mut hash = Hash::new();
+            for key in LEADING_KEYS {
+                if let Some(value) = map.remove(key) {
+                    hash.insert(Yaml::String(key.to_string()), json_to_yaml(value));
+                }
+            }
+            for (key, value) in map {
+                hash.insert(Yaml::String(key), json_to_yaml(value));
+            }
+            Yaml::Hash(hash)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NmstateDocument;
+    use crate::network::model::{
+        BondConfig, BondOptions, Connection, ConnectionConfig, Ipv4Method, Ipv6Method, PortConfig,
+        VlanConfig,
+    };
+    use agama_lib::network::types::BondMode;
+    use std::collections::HashMap;
+
+    const DOCUMENT: &str = r#"
+dns-resolver:
+  config:
+    server:
+      - 192.168.1.1
+    search:
+      - example.net
+routes:
+  config:
+    - destination: 0.0.0.0/0
+      next-hop-interface: br0
+      next-hop-address: 192.168.1.1
+    - destination: 10.0.0.0/8
+      next-hop-interface: br0
+      next-hop-address: 192.168.1.254
+      metric: 100
+interfaces:
+  - name: br0
+    type: linux-bridge
+    state: up
+    ipv4:
+      enabled: true
+      dhcp: false
+      address:
+        - ip: 192.168.1.10
+          prefix-length: 24
+    ipv6:
+      enabled: true
+      dhcp: true
+      autoconf: true
+    bridge:
+      options:
+        stp:
+          enabled: true
+          forward-delay: 15
+      port:
+        - name: eth0
+          stp-priority: 32
+  - name: eth0.10
+    type: vlan
+    vlan:
+      base-iface: eth0
+      id: 10
+  - name: eth1
+    state: absent
+"#;
+
+    #[test]
+    fn test_to_config() {
+        let document = NmstateDocument::from_yaml(DOCUMENT).unwrap();
+        let config = document.to_config().unwrap();
+        assert_eq!(config.removed, vec!["eth1".to_string()]);
+        assert_eq!(config.connections.len(), 3);
+
+        let br0 = &config.connections[0];
+        let ConnectionConfig::Bridge(bridge) = &br0.config else {
+            panic!("Unexpected configuration: {:?}", br0.config);
+        };
+        assert!(bridge.stp);
+        assert_eq!(bridge.forward_delay, Some(15));
+        assert_eq!(br0.ip_config.method4, Ipv4Method::Manual);
+        assert_eq!(br0.ip_config.method6, Ipv6Method::Auto);
+        assert_eq!(br0.ip_config.addresses[0].to_string(), "192.168.1.10/24");
+        assert_eq!(br0.ip_config.gateway4, Some("192.168.1.1".parse().unwrap()));
+        assert_eq!(br0.ip_config.routes4[0].metric, Some(100));
+        assert_eq!(
+            br0.ip_config.dns_searchlist,
+            vec!["example.net".to_string()]
+        );
+
+        let vlan = &config.connections[1];
+        assert_eq!(
+            vlan.config,
+            ConnectionConfig::Vlan(VlanConfig {
+                parent: "eth0".to_string(),
+                id: 10,
+                ..Default::default()
+            })
+        );
+
+        let eth0 = &config.connections[2];
+        assert_eq!(eth0.controller, Some(br0.uuid));
+        assert_eq!(eth0.ip_config.method4, Ipv4Method::Disabled);
+        assert!(eth0.ip_config.nameservers.is_empty());
+        let PortConfig::Bridge(port) = &eth0.port_config else {
+            panic!("Unexpected port configuration: {:?}", eth0.port_config);
+        };
+        assert_eq!(port.priority, Some(32));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bond0 = Connection {
+            id: "bond0".to_string(),
+            interface: Some("bond0".to_string()),
+            config: ConnectionConfig::Bond(BondConfig {
+                mode: BondMode::ActiveBackup,
+                options: BondOptions(HashMap::from([("miimon".to_string(), "100".to_string())])),
+            }),
+            ..Default::default()
+        };
+        let mut bond0 = bond0;
+        bond0.ip_config.method4 = Ipv4Method::Auto;
+        bond0.ip_config.method6 = Ipv6Method::Dhcp;
+        bond0.ip_config.nameservers = vec!["192.168.1.1".parse().unwrap()];
+        let eth0 = Connection {
+            id: "Wired connection 1".to_string(),
+            interface: Some("eth0".to_string()),
+            controller: Some(bond0.uuid),
+            ..Default::default()
+        };
+        let wlan0 = Connection {
+            id: "wlan0".to_string(),
+            config: ConnectionConfig::Wireless(Default::default()),
+            ..Default::default()
+        };
+
+        let document = NmstateDocument::from_connections(&[bond0, eth0, wlan0]);
+        let yaml = document.to_yaml().unwrap();
+        assert!(yaml.contains("type: bond"));
+        assert!(yaml.contains("miimon: 100"));
+        assert!(!yaml.contains("wlan0"));
+
+        let config = NmstateDocument::from_yaml(&yaml)
+            .unwrap()
+            .to_config()
+            .unwrap();
+        assert_eq!(config.connections.len(), 2);
+        let bond0 = &config.connections[0];
+        let ConnectionConfig::Bond(bond) = &bond0.config else {
+            panic!("Unexpected configuration: {:?}", bond0.config);
+        };
+        assert_eq!(bond.mode, BondMode::ActiveBackup);
+        assert_eq!(bond.options.0.get("miimon"), Some(&"100".to_string()));
+        assert_eq!(bond0.ip_config.method4, Ipv4Method::Auto);
+        assert_eq!(bond0.ip_config.method6, Ipv6Method::Dhcp);
+        assert_eq!(bond0.ip_config.nameservers.len(), 1);
+        assert_eq!(config.connections[1].controller, Some(bond0.uuid));
+    }
+
+    #[test]
+    fn test_unsupported_type() {
+        let document =
+            NmstateDocument::from_yaml("interfaces:\n  - name: ovs0\n    type: ovs-bridge\n")
+                .unwrap();
+        assert!(document.to_config().is_err());
+    }
+}
@@ -28,7 +28,7 @@ use crate::network::{
     Action, Adapter, NetworkState,
 };
 use agama_lib::{error::ServiceError, network::types::DeviceType};
-use std::error::Error;
+use std::{error::Error, time::Duration};
 use tokio::sync::{
     broadcast::{self, Receiver},
     mpsc::{self, error::SendError, UnboundedReceiver, UnboundedSender},
@@ -108,12 +108,16 @@ impl<T: Adapter + Send + Sync + 'static> NetworkSystem<T> {
         }
 
         let updates_tx_clone = updates_tx.clone();
+        let actions_tx_clone = actions_tx.clone();
         tokio::spawn(async move {
             let mut server = NetworkSystemServer {
                 state,
                 input: actions_rx,
+                actions: actions_tx_clone,
                 output: updates_tx_clone,
                 adapter: self.adapter,
+                pending_apply: None,
+                applies: 0,
             };
 
             server.listen().await;
@@ -202,6 +206,22 @@ impl NetworkSystemClient {
         Ok(result?)
     }
 
+    /// Sets the ports of a controller connection (e.g., a bond or a bridge).
+    ///
+    /// * `uuid`: UUID of the controller connection.
+    /// * `ports`: IDs or interface names of the ports.
+    pub async fn set_ports(
+        &self,
+        uuid: Uuid,
+        ports: Vec<String>,
+    ) -> Result<(), NetworkSystemError> {
+        let (tx, rx) = oneshot::channel();
+        self.actions
+            .send(Action::SetPorts(uuid, Box::new(ports), tx))?;
+        let result = rx.await?;
+        Ok(result?)
+    }
+
     /// Removes the connection with the given ID.
     ///
     /// * `id`: Connection ID.
@@ -221,6 +241,32 @@ impl NetworkSystemClient {
         Ok(result?)
     }
 
+    /// Applies the network configuration, rolling it back unless it is confirmed in time.
+    ///
+    /// * `timeout`: time to confirm the configuration (see [Self::confirm_apply]).
+    pub async fn apply_with_rollback(&self, timeout: Duration) -> Result<(), NetworkSystemError> {
+        let (tx, rx) = oneshot::channel();
+        self.actions.send(Action::ApplyWithRollback(timeout, tx))?;
+        let result = rx.await?;
+        Ok(result?)
+    }
+
+    /// Confirms the network configuration applied with [Self::apply_with_rollback].
+    pub async fn confirm_apply(&self) -> Result<(), NetworkSystemError> {
+        let (tx, rx) = oneshot::channel();
+        self.actions.send(Action::ConfirmApply(tx))?;
+        let result = rx.await?;
+        Ok(result?)
+    }
+
+    /// Rolls back the network configuration applied with [Self::apply_with_rollback].
+    pub async fn rollback_apply(&self) -> Result<(), NetworkSystemError> {
+        let (tx, rx) = oneshot::channel();
+        self.actions.send(Action::RollbackApply(tx))?;
+        let result = rx.await?;
+        Ok(result?)
+    }
+
     /// Returns the collection of access points.
     pub async fn get_access_points(&self) -> Result<Vec<AccessPoint>, NetworkSystemError> {
         let (tx, rx) = oneshot::channel();
@@ -240,11 +286,17 @@ impl NetworkSystemClient {
 struct NetworkSystemServer<T: Adapter> {
     state: NetworkState,
     input: UnboundedReceiver<Action>,
+    /// Sending-half of the actions channel, used to schedule the rollback of pending changes.
+    actions: UnboundedSender<Action>,
     output: broadcast::Sender<NetworkChange>,
     adapter: T,
+    /// Number of the configuration waiting for confirmation, if any.
+    pending_apply: Option<u64>,
+    /// Number of configurations applied with rollback so far.
+    applies: u64,
 }
 
-impl<T: Adapter> NetworkSystemServer<T> {
+impl<T: Adapter + Sync> NetworkSystemServer<T> {
     /// Process incoming actions.
     ///
     /// This function is expected to be executed on a separate thread.
@@ -357,6 +409,34 @@ impl<T: Adapter> NetworkSystemServer<T> {
                 let result = self.write().await;
                 tx.send(result).unwrap();
             }
+            Action::ApplyWithRollback(timeout, tx) => {
+                let result = self.write_with_rollback(timeout).await;
+                let update = result
+                    .is_ok()
+                    .then_some(NetworkChange::ApplyPending(timeout.as_secs()));
+                tx.send(result).unwrap();
+                return Ok(update);
+            }
+            Action::ConfirmApply(tx) => {
+                let result = self.confirm().await;
+                let update = result.is_ok().then_some(NetworkChange::ApplyConfirmed);
+                tx.send(result).unwrap();
+                return Ok(update);
+            }
+            Action::RollbackApply(tx) => {
+                let result = self.rollback().await;
+                let update = result.is_ok().then_some(NetworkChange::ApplyRolledBack);
+                tx.send(result).unwrap();
+                return Ok(update);
+            }
+            Action::ApplyTimeout(number) => {
+                if self.pending_apply != Some(number) {
+                    return Ok(None);
+                }
+                tracing::warn!("The network configuration was not confirmed in time");
+                self.rollback().await?;
+                return Ok(Some(NetworkChange::ApplyRolledBack));
+            }
         }
 
         Ok(None)
@@ -407,8 +487,55 @@ impl<T: Adapter> NetworkSystemServer<T> {
 
     /// Writes the network configuration.
     pub async fn write(&mut self) -> Result<(), NetworkAdapterError> {
+        if self.pending_apply.is_some() {
+            return Err(NetworkAdapterError::PendingCheckpoint);
+        }
         self.adapter.write(&self.state).await?;
         self.state = self.adapter.read(StateConfig::default()).await?;
         Ok(())
     }
+
+    /// Writes the network configuration, scheduling a rollback after the given timeout.
+    ///
+    /// * `timeout`: time to confirm the configuration.
+    async fn write_with_rollback(&mut self, timeout: Duration) -> Result<(), NetworkAdapterError> {
+        if self.pending_apply.is_some() {
+            return Err(NetworkAdapterError::PendingCheckpoint);
+        }
+        self.adapter
+            .write_with_rollback(&self.state, timeout)
+            .await?;
+        self.state = self.adapter.read(StateConfig::default()).await?;
+
+        self.applies += 1;
+        let number = self.applies;
+        self.pending_apply = Some(number);
+        let actions = self.actions.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            _ = actions.send(Action::ApplyTimeout(number));
+        });
+        Ok(())
+    }
+
+    /// Confirms the pending configuration.
+    async fn confirm(&mut self) -> Result<(), NetworkAdapterError> {
+        if self.pending_apply.is_none() {
+            return Err(NetworkAdapterError::NoPendingCheckpoint);
+        }
+        self.adapter.confirm().await?;
+        self.pending_apply = None;
+        Ok(())
+    }
+
+    /// Rolls back the pending configuration.
+    async fn rollback(&mut self) -> Result<(), NetworkAdapterError> {
+        if self.pending_apply.is_none() {
+            return Err(NetworkAdapterError::NoPendingCheckpoint);
+        }
+        self.pending_apply = None;
+        self.adapter.rollback().await?;
+        self.state = self.adapter.read(StateConfig::default()).await?;
+        Ok(())
+    }
 }
@@ -22,28 +22,41 @@
 
 use crate::{
     error::Error,
-    web::{Event, EventsSender},
+    web::{common::Issue, Event, EventsSender},
 };
 use anyhow::Context;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     routing::{delete, get, patch, post},
     Json, Router,
 };
 
 use super::{
+    binding::{self, BindingPreview},
+    diagnostics::{Diagnostics, DiagnosticsError, DiagnosticsReport},
     error::NetworkStateError,
+    import::{ImportError, ImportedNetwork, NetworkImporter},
+    install::{TargetAdapter, TargetBackend},
     model::{AccessPoint, GeneralState},
+    nmstate::{NmstateDocument, NmstateError},
     system::{NetworkSystemClient, NetworkSystemError},
-    Adapter,
+    Adapter, NetworkAdapterError, NetworkState,
 };
 
 use crate::network::{model::Connection, model::Device, NetworkSystem};
-use agama_lib::{error::ServiceError, network::settings::NetworkConnection};
+use agama_lib::{
+    error::ServiceError,
+    network::{
+        http_proxy::PROXY_PATH, settings::NetworkConnection, GeneralSettings, ProxySettings,
+    },
+    product::ProductClient,
+};
 
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::{collections::HashMap, time::Duration};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -58,6 +71,18 @@ pub enum NetworkError {
     CannotUpdate(String),
     #[error("Cannot apply configuration")]
     CannotApplyConfig,
+    #[error("Invalid confirmation timeout")]
+    InvalidConfirmTimeout,
+    #[error("Cannot import the network configuration: {0}")]
+    Import(#[from] ImportError),
+    #[error("nmstate error: {0}")]
+    Nmstate(#[from] NmstateError),
+    #[error("Cannot write the configuration for the installed system: {0}")]
+    CannotWriteTarget(#[from] NetworkAdapterError),
+    #[error("Cannot write the proxy configuration: {0}")]
+    CannotWriteProxy(#[from] std::io::Error),
+    #[error("Cannot run the connectivity checks: {0}")]
+    Diagnostics(#[from] DiagnosticsError),
     // TODO: to be removed after adapting to the NetworkSystemServer API
     #[error("Network state error: {0}")]
     Error(#[from] NetworkStateError),
@@ -114,6 +139,10 @@ pub async fn network_service<T: Adapter + Send + Sync + 'static>(
 
     Ok(Router::new()
         .route("/state", get(general_state).put(update_general_state))
+        .route(
+            "/general",
+            get(general_settings).put(update_general_settings),
+        )
         .route("/connections", get(connections).post(add_connection))
         .route(
             "/connections/:id",
@@ -123,8 +152,17 @@ pub async fn network_service<T: Adapter + Send + Sync + 'static>(
         )
         .route("/connections/:id/connect", patch(connect))
         .route("/connections/:id/disconnect", patch(disconnect))
+        .route("/bindings", get(bindings))
         .route("/devices", get(devices))
+        .route("/diagnostics", get(diagnostics))
+        .route("/import", get(import_preview).post(import))
+        .route("/issues", get(issues))
+        .route("/nmstate", get(nmstate).put(update_nmstate))
+        .route("/proxy", get(proxy).put(update_proxy))
         .route("/system/apply", post(apply))
+        .route("/system/confirm", post(confirm))
+        .route("/system/install", post(install))
+        .route("/system/rollback", post(rollback))
         .route("/wifi", get(wifi_networks))
         .with_state(state))
 }
@@ -161,6 +199,41 @@ async fn update_general_state(
     Ok(Json(state))
 }
 
+#[utoipa::path(
+    get,
+    path = "/general",
+    context_path = "/api/network",
+    responses(
+      (status = 200, description = "Host name and global DNS settings", body = GeneralSettings)
+    )
+)]
+async fn general_settings(
+    State(state): State<NetworkServiceState>,
+) -> Result<Json<GeneralSettings>, NetworkError> {
+    let general_state = state.network.get_state().await?;
+    Ok(Json(general_state.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/general",
+    context_path = "/api/network",
+    request_body = GeneralSettings,
+    responses(
+      (status = 204, description = "Update the host name and global DNS settings"),
+      (status = 400, description = "The settings are not valid")
+    )
+)]
+async fn update_general_settings(
+    State(state): State<NetworkServiceState>,
+    Json(settings): Json<GeneralSettings>,
+) -> Result<impl IntoResponse, NetworkError> {
+    let mut general_state = state.network.get_state().await?;
+    general_state.update(settings)?;
+    state.network.update_state(general_state)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 #[utoipa::path(
     get,
     path = "/wifi",
@@ -199,6 +272,121 @@ async fn devices(
     Ok(Json(state.network.get_devices().await?))
 }
 
+#[utoipa::path(
+    get,
+    path = "/bindings",
+    context_path = "/api/network",
+    responses(
+      (status = 200, description = "Devices the connections bind to", body = BindingPreview)
+    )
+)]
+async fn bindings(
+    State(state): State<NetworkServiceState>,
+) -> Result<Json<BindingPreview>, NetworkError> {
+    Ok(Json(resolve_bindings(&state.network).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/issues",
+    context_path = "/api/network",
+    responses(
+      (status = 200, description = "Connections which do not match any device or match more than one", body = Vec<Issue>)
+    )
+)]
+async fn issues(
+    State(state): State<NetworkServiceState>,
+) -> Result<Json<Vec<Issue>>, NetworkError> {
+    Ok(Json(resolve_bindings(&state.network).await?.issues))
+}
+
+/// Computes the devices the current connections bind to.
+async fn resolve_bindings(network: &NetworkSystemClient) -> Result<BindingPreview, NetworkError> {
+    let connections = network.get_connections().await?;
+    let devices = network.get_devices().await?;
+    Ok(binding::resolve(&connections, &devices))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct DiagnosticsParams {
+    /// Product whose repositories and registration server are checked. By default, the selected
+    /// one.
+    product: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/diagnostics",
+    context_path = "/api/network",
+    params(DiagnosticsParams),
+    responses(
+      (status = 200, description = "Result of each connectivity check", body = DiagnosticsReport),
+      (status = 400, description = "The checks could not be run")
+    )
+)]
+async fn diagnostics(
+    State(state): State<NetworkServiceState>,
+    Query(params): Query<DiagnosticsParams>,
+) -> Result<Json<DiagnosticsReport>, NetworkError> {
+    let product = match params.product {
+        Some(product) => Some(product),
+        None => selected_product().await,
+    };
+    let devices = state.network.get_devices().await?;
+    let report = Diagnostics::default()
+        .run(product.as_deref(), &devices)
+        .await?;
+    Ok(Json(report))
+}
+
+/// Returns the selected product, if any.
+async fn selected_product() -> Option<String> {
+    let product = async {
+        let client = ProductClient::new(agama_lib::connection().await?).await?;
+        client.product().await
+    };
+    match product.await {
+        Ok(product) if !product.is_empty() => Some(product),
+        Ok(_) => None,
+        Err(error) => {
+            log::warn!("Could not find out the selected product: {}", error);
+            None
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/proxy",
+    context_path = "/api/network",
+    responses(
+      (status = 200, description = "HTTP(S) proxy settings", body = ProxySettings)
+    )
+)]
+async fn proxy() -> Result<Json<ProxySettings>, NetworkError> {
+    let proxy = match ProxySettings::read(PROXY_PATH) {
+        Ok(proxy) => proxy,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => ProxySettings::default(),
+        Err(error) => return Err(error.into()),
+    };
+    Ok(Json(proxy))
+}
+
+#[utoipa::path(
+    put,
+    path = "/proxy",
+    context_path = "/api/network",
+    request_body = ProxySettings,
+    responses(
+      (status = 204, description = "Write the HTTP(S) proxy settings to the installer system"),
+      (status = 400, description = "The proxy settings could not be written")
+    )
+)]
+async fn update_proxy(Json(proxy): Json<ProxySettings>) -> Result<impl IntoResponse, NetworkError> {
+    proxy.write(PROXY_PATH)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 #[utoipa::path(
     get,
     path = "/connections",
@@ -231,10 +419,15 @@ async fn add_connection(
     State(state): State<NetworkServiceState>,
     Json(conn): Json<NetworkConnection>,
 ) -> Result<Json<Connection>, NetworkError> {
+    let ports = controller_ports(&conn);
     let conn = Connection::try_from(conn)?;
     let id = conn.id.clone();
+    let uuid = conn.uuid;
 
     state.network.add_connection(conn).await?;
+    if let Some(ports) = ports {
+        state.network.set_ports(uuid, ports).await?;
+    }
     match state.network.get_connection(&id).await? {
         None => Err(NetworkError::CannotAddConnection(id.clone())),
         Some(conn) => Ok(Json(conn)),
@@ -243,7 +436,7 @@ async fn add_connection(
 
 #[utoipa::path(
     get,
-    path = "/connections/:id",
+    path = "/connections/{id}",
     context_path = "/api/network",
     responses(
       (status = 200, description = "Get connection given by its ID", body = NetworkConnection)
@@ -266,7 +459,7 @@ async fn connection(
 
 #[utoipa::path(
     delete,
-    path = "/connections/:id",
+    path = "/connections/{id}",
     context_path = "/api/network",
     responses(
       (status = 200, description = "Delete connection", body = Connection)
@@ -285,7 +478,7 @@ async fn delete_connection(
 
 #[utoipa::path(
     put,
-    path = "/connections/:id",
+    path = "/connections/{id}",
     context_path = "/api/network",
     responses(
       (status = 204, description = "Update connection", body = Connection)
@@ -301,6 +494,7 @@ async fn update_connection(
         .get_connection(&id)
         .await?
         .ok_or_else(|| NetworkError::UnknownConnection(id.clone()))?;
+    let ports = controller_ports(&conn);
     let mut conn = Connection::try_from(conn)?;
     if orig_conn.id != id {
         // FIXME: why?
@@ -310,12 +504,192 @@ async fn update_connection(
     }
 
     state.network.update_connection(conn).await?;
+    if let Some(ports) = ports {
+        state.network.set_ports(orig_conn.uuid, ports).await?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Returns the ports of a bond or a bridge connection, if any.
+fn controller_ports(conn: &NetworkConnection) -> Option<Vec<String>> {
+    let ports = if let Some(bond) = &conn.bond {
+        &bond.ports
+    } else if let Some(bridge) = &conn.bridge {
+        &bridge.ports
+    } else {
+        return None;
+    };
+    (!ports.is_empty()).then(|| ports.clone())
+}
+
+/// Network configuration found in the kernel command line and the `ifcfg-*` files.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ImportPreview {
+    /// Imported connections.
+    connections: Vec<NetworkConnection>,
+    /// Imported host name, if any.
+    hostname: Option<String>,
+    /// Settings which could not be imported.
+    warnings: Vec<String>,
+}
+
+impl TryFrom<ImportedNetwork> for ImportPreview {
+    type Error = NetworkError;
+
+    fn try_from(network: ImportedNetwork) -> Result<Self, Self::Error> {
+        let mut connections = vec![];
+        for conn in &network.connections {
+            let mut settings = NetworkConnection::try_from(conn.clone())?;
+            let ports: Vec<String> = network
+                .connections
+                .iter()
+                .filter(|c| c.controller == Some(conn.uuid))
+                .map(|c| c.interface.clone().unwrap_or(c.id.clone()))
+                .collect();
+            if let Some(bond) = settings.bond.as_mut() {
+                bond.ports = ports;
+            } else if let Some(bridge) = settings.bridge.as_mut() {
+                bridge.ports = ports;
+            }
+            connections.push(settings);
+        }
+
+        Ok(Self {
+            connections,
+            hostname: network.hostname,
+            warnings: network.warnings,
+        })
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/import",
+    context_path = "/api/network",
+    responses(
+      (status = 200, description = "Network configuration used to boot the installer", body = ImportPreview),
+      (status = 400, description = "The configuration could not be read")
+    )
+)]
+async fn import_preview() -> Result<Json<ImportPreview>, NetworkError> {
+    let network = NetworkImporter::default().import()?;
+    Ok(Json(network.try_into()?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/import",
+    context_path = "/api/network",
+    responses(
+      (status = 200, description = "Import the network configuration used to boot the installer", body = ImportPreview),
+      (status = 400, description = "The configuration could not be imported")
+    )
+)]
+async fn import(
+    State(state): State<NetworkServiceState>,
+) -> Result<Json<ImportPreview>, NetworkError> {
+    let mut network = NetworkImporter::default().import()?;
+    merge_connections(&state.network, &mut network.connections).await?;
+
+    if let Some(hostname) = &network.hostname {
+        let mut general_state = state.network.get_state().await?;
+        general_state.hostname = hostname.clone();
+        state.network.update_state(general_state)?;
+    }
+
+    Ok(Json(network.try_into()?))
+}
+
+/// Adds the given connections to the state, replacing the existing ones for the same interface.
+///
+/// The UUIDs of the replaced connections are kept, adapting the controller references.
+async fn merge_connections(
+    network: &NetworkSystemClient,
+    connections: &mut [Connection],
+) -> Result<(), NetworkError> {
+    let existing = network.get_connections().await?;
+
+    let mut uuids = HashMap::new();
+    let mut updates = vec![];
+    for conn in connections.iter_mut() {
+        let found = existing.iter().find(|c| {
+            !c.is_removed()
+                && match &conn.interface {
+                    Some(interface) => c.interface.as_ref() == Some(interface),
+                    None => c.id == conn.id,
+                }
+        });
+        updates.push(found.is_some());
+        if let Some(found) = found {
+            uuids.insert(conn.uuid, found.uuid);
+            conn.id = found.id.clone();
+            conn.uuid = found.uuid;
+        }
+    }
+
+    for conn in connections.iter_mut() {
+        if let Some(controller) = conn.controller.and_then(|c| uuids.get(&c)) {
+            conn.controller = Some(*controller);
+        }
+    }
+
+    for (conn, update) in connections.iter().zip(updates) {
+        if update {
+            network.update_connection(conn.clone()).await?;
+        } else {
+            network.add_connection(conn.clone()).await?;
+        }
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/nmstate",
+    context_path = "/api/network",
+    responses(
+      (status = 200, description = "Network configuration in nmstate format", body = String, content_type = "application/yaml")
+    )
+)]
+async fn nmstate(State(state): State<NetworkServiceState>) -> Result<Response, NetworkError> {
+    let connections = state.network.get_connections().await?;
+    let yaml = NmstateDocument::from_connections(&connections).to_yaml()?;
+    Ok(([(header::CONTENT_TYPE, "application/yaml")], yaml).into_response())
+}
+
+#[utoipa::path(
+    put,
+    path = "/nmstate",
+    context_path = "/api/network",
+    request_body(content = String, description = "nmstate document", content_type = "application/yaml"),
+    responses(
+      (status = 204, description = "Update the network configuration from an nmstate document"),
+      (status = 400, description = "The document is not valid or not supported")
+    )
+)]
+async fn update_nmstate(
+    State(state): State<NetworkServiceState>,
+    yaml: String,
+) -> Result<impl IntoResponse, NetworkError> {
+    let mut config = NmstateDocument::from_yaml(&yaml)?.to_config()?;
+
+    let existing = state.network.get_connections().await?;
+    for name in &config.removed {
+        let removed = existing
+            .iter()
+            .filter(|c| !c.is_removed() && c.interface.as_ref() == Some(name));
+        for conn in removed {
+            state.network.remove_connection(&conn.id).await?;
+        }
+    }
+
+    merge_connections(&state.network, &mut config.connections).await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
 #[utoipa::path(
     patch,
-    path = "/connections/:id/connect",
+    path = "/connections/{id}/connect",
     context_path = "/api/network",
     responses(
       (status = 204, description = "Connect to the given connection", body = String)
@@ -347,7 +721,7 @@ async fn connect(
 
 #[utoipa::path(
     patch,
-    path = "/connections/:id/disconnect",
+    path = "/connections/{id}/disconnect",
     context_path = "/api/network",
     responses(
       (status = 204, description = "Connect to the given connection", body = String)
@@ -377,22 +751,140 @@ async fn disconnect(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[derive(Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyParams {
+    /// Seconds to confirm the configuration (see `/system/confirm`). If it is not confirmed in
+    /// time, the previous configuration is restored.
+    confirm_timeout: Option<u64>,
+}
+
 #[utoipa::path(
     post,
     path = "/system/apply",
     context_path = "/api/network",
+    params(ApplyParams),
     responses(
-      (status = 204, description = "Apply configuration")
+      (status = 202, description = "Configuration applied, waiting for confirmation"),
+      (status = 204, description = "Apply configuration"),
+      (status = 400, description = "The configuration could not be applied")
     )
 )]
 async fn apply(
     State(state): State<NetworkServiceState>,
+    Query(params): Query<ApplyParams>,
 ) -> Result<impl IntoResponse, NetworkError> {
+    for issue in resolve_bindings(&state.network).await?.issues {
+        log::warn!("Network configuration: {:?}", issue);
+    }
+
+    let Some(timeout) = params.confirm_timeout else {
+        state
+            .network
+            .apply()
+            .await
+            .map_err(|_| NetworkError::CannotApplyConfig)?;
+        return Ok(StatusCode::NO_CONTENT);
+    };
+
+    if timeout == 0 {
+        return Err(NetworkError::InvalidConfirmTimeout);
+    }
     state
         .network
-        .apply()
-        .await
-        .map_err(|_| NetworkError::CannotApplyConfig)?;
+        .apply_with_rollback(Duration::from_secs(timeout))
+        .await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[utoipa::path(
+    post,
+    path = "/system/confirm",
+    context_path = "/api/network",
+    responses(
+      (status = 204, description = "Keep the configuration applied with a confirmation timeout"),
+      (status = 400, description = "There is no configuration waiting for confirmation")
+    )
+)]
+async fn confirm(
+    State(state): State<NetworkServiceState>,
+) -> Result<impl IntoResponse, NetworkError> {
+    state.network.confirm_apply().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/system/rollback",
+    context_path = "/api/network",
+    responses(
+      (status = 204, description = "Restore the configuration previous to the one waiting for confirmation"),
+      (status = 400, description = "There is no configuration waiting for confirmation")
+    )
+)]
+async fn rollback(
+    State(state): State<NetworkServiceState>,
+) -> Result<impl IntoResponse, NetworkError> {
+    state.network.rollback_apply().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Network configuration to write into the installed system.
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct InstallParams {
+    /// Target root. By default, `/mnt`.
+    root: Option<String>,
+    /// Network service of the installed system.
+    #[serde(default)]
+    backend: TargetBackend,
+    /// Connections to write. If they are not given, the current configuration is written.
+    connections: Option<Vec<NetworkConnection>>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/system/install",
+    context_path = "/api/network",
+    request_body = InstallParams,
+    responses(
+      (status = 204, description = "Write the network configuration into the installed system"),
+      (status = 400, description = "The configuration could not be written")
+    )
+)]
+async fn install(
+    State(state): State<NetworkServiceState>,
+    Json(params): Json<InstallParams>,
+) -> Result<impl IntoResponse, NetworkError> {
+    let general_state = state.network.get_state().await?;
+    let network = match params.connections {
+        Some(connections) => {
+            let mut network = NetworkState {
+                general_state,
+                ..Default::default()
+            };
+            let mut controllers = vec![];
+            for conn in connections {
+                let ports = controller_ports(&conn);
+                let conn = Connection::try_from(conn)?;
+                if let Some(ports) = ports {
+                    controllers.push((conn.clone(), ports));
+                }
+                network.add_connection(conn)?;
+            }
+            for (controller, ports) in controllers {
+                network.set_ports(&controller, ports)?;
+            }
+            network
+        }
+        None => {
+            let connections = state.network.get_connections().await?;
+            NetworkState::new(general_state, vec![], vec![], connections)
+        }
+    };
 
+    let root = params.root.unwrap_or_else(|| "/mnt".to_string());
+    TargetAdapter::new(root, params.backend)
+        .write(&network)
+        .await?;
     Ok(StatusCode::NO_CONTENT)
 }
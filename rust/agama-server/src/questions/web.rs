@@ -305,8 +305,8 @@ async fn list_questions(
 /// * `state`: service state.
 /// * `questions_id`: id of question
 #[utoipa::path(
-    put,
-    path = "/:id/answer",
+    get,
+    path = "/{id}/answer",
     context_path = "/api/questions",
     responses(
         (status = 200, description = "Answer"),
@@ -333,7 +333,7 @@ async fn get_answer(
 /// * `answer`: struct with answer and possible other data needed for answer like password
 #[utoipa::path(
     put,
-    path = "/:id/answer",
+    path = "/{id}/answer",
     context_path = "/api/questions",
     responses(
         (status = 200, description = "answer question"),
@@ -355,7 +355,7 @@ async fn answer_question(
 /// * `questions_id`: id of question
 #[utoipa::path(
     delete,
-    path = "/:id",
+    path = "/{id}",
     context_path = "/api/questions",
     responses(
         (status = 200, description = "question deleted"),
@@ -437,7 +437,7 @@ async fn probe(State(state): State<SoftwareState<'_>>) -> Result<Json<()>, Error
 /// Updates the resolvables list with the given `id`.
 #[utoipa::path(
     put,
-    path = "/resolvables/:id",
+    path = "/resolvables/{id}",
     context_path = "/api/software",
     responses(
         (status = 200, description = "Read repositories data"),
@@ -194,7 +194,7 @@ pub struct NodeParams {
 ///
 /// At this point, only the startup option can be changed.
 #[utoipa::path(
-    put,
+    patch,
     path="/nodes/{id}",
     context_path="/api/storage/iscsi",
     params(
@@ -181,7 +181,7 @@ async fn controllers(
 /// Activate given zFCP controller.
 #[utoipa::path(
     post,
-    path="/controllers/:controller_id/activate",
+    path="/controllers/{controller_id}/activate",
     context_path="/api/storage/zfcp",
     responses(
         (status = OK, description = "controller activated")
@@ -200,8 +200,8 @@ async fn activate_controller(
 
 /// List WWPNs for given controller.
 #[utoipa::path(
-    post,
-    path="/controllers/:controller_id/wwpns",
+    get,
+    path="/controllers/{controller_id}/wwpns",
     context_path="/api/storage/zfcp",
     responses(
         (status = OK, description = "List of WWPNs", body=Vec<String>)
@@ -217,8 +217,8 @@ async fn get_wwpns(
 
 /// List LUNS for given controller and wwpn.
 #[utoipa::path(
-    post,
-    path="/controllers/:controller_id/wwpns/:wwpn_id/luns",
+    get,
+    path="/controllers/{controller_id}/wwpns/{wwpn_id}/luns",
     context_path="/api/storage/zfcp",
     responses(
         (status = OK, description = "list of luns", body=Vec<String>)
@@ -235,7 +235,7 @@ async fn get_luns(
 /// Activates a disk on given controller with given WWPN id and LUN id.
 #[utoipa::path(
     post,
-    path="/controllers/:controller_id/wwpns/:wwpn_id/luns/:lun_id/activate_disk",
+    path="/controllers/{controller_id}/wwpns/{wwpn_id}/luns/{lun_id}/activate_disk",
     context_path="/api/storage/zfcp",
     responses(
         (status = OK, description = "The activation was succesful.")
@@ -255,7 +255,7 @@ async fn activate_disk(
 /// Deactivates disk on given controller with given WWPN id and LUN id.
 #[utoipa::path(
     post,
-    path="/controllers/:controller_id/wwpns/:wwpn_id/luns/:lun_id/deactivate_disk",
+    path="/controllers/{controller_id}/wwpns/{wwpn_id}/luns/{lun_id}/deactivate_disk",
     context_path="/api/storage/zfcp",
     responses(
         (status = OK, description = "The activation was succesful.")
@@ -21,11 +21,12 @@
 //! This module implements a web-based API for Agama. It is responsible for:
 //!
 //! * Exposing an HTTP API to interact with Agama.
-//! * Emit relevant events via websocket.
+//! * Emit relevant events via websocket (or Server-Sent Events).
 //! * Serve the code for the web user interface (not implemented yet).
 
 use crate::{
     bootloader::web::bootloader_service,
+    cert::CertificateStore,
     error::Error,
     l10n::web::l10n_service,
     manager::web::{manager_service, manager_stream},
@@ -35,7 +36,10 @@ use crate::{
     software::web::{software_service, software_streams},
     storage::web::{storage_service, storage_streams},
     users::web::{users_service, users_streams},
-    web::common::{issues_stream, jobs_stream, progress_stream, service_status_stream},
+    web::{
+        common::{issues_stream, jobs_stream, progress_stream, service_status_stream},
+        health::or_unavailable,
+    },
 };
 use axum::Router;
 
@@ -44,14 +48,25 @@ pub mod common;
 mod config;
 pub mod docs;
 mod event;
+mod health;
 mod http;
+mod idempotency;
+mod journal;
+mod metrics;
+mod proxy;
+mod recorder;
 mod service;
+mod sse;
 mod state;
 mod ws;
 
 use agama_lib::{connection, error::ServiceError};
+pub use auth::{csrf_token, PeerCredentials};
 pub use config::ServiceConfig;
-pub use event::{Event, EventsReceiver, EventsSender};
+pub use event::{Event, EventsBuffer, EventsReceiver, EventsSender, SequencedEvent};
+pub use journal::EventsJournal;
+pub use proxy::{ClientInfo, PeerAddress};
+pub use recorder::Recorder;
 pub use service::MainServiceBuilder;
 use std::path::Path;
 use tokio_stream::{StreamExt, StreamMap};
@@ -62,32 +77,70 @@ use tokio_stream::{StreamExt, StreamMap};
 /// * `events`: channel to send the events through the WebSocket.
 /// * `dbus`: D-Bus connection.
 /// * `web_ui_dir`: public directory containing the web UI.
+/// * `certificate`: certificate used by the server.
+/// * `journal`: journal to write the events to, if any.
+/// * `recorder`: recorder to write the HTTP API traffic to, if any.
 pub async fn service<P>(
     config: ServiceConfig,
     events: EventsSender,
     dbus: zbus::Connection,
     web_ui_dir: P,
+    certificate: CertificateStore,
+    journal: Option<EventsJournal>,
+    recorder: Option<Recorder>,
 ) -> Result<Router, ServiceError>
 where
     P: AsRef<Path>,
 {
-    let network_adapter = NetworkManagerAdapter::from_system()
-        .await
-        .expect("Could not connect to NetworkManager to read the configuration");
+    let network = async {
+        let adapter = NetworkManagerAdapter::from_system().await?;
+        network_service(adapter, events.clone()).await
+    };
 
-    let router = MainServiceBuilder::new(events.clone(), web_ui_dir)
-        .add_service("/l10n", l10n_service(dbus.clone(), events.clone()).await?)
-        .add_service("/manager", manager_service(dbus.clone()).await?)
-        .add_service("/software", software_service(dbus.clone()).await?)
-        .add_service("/storage", storage_service(dbus.clone()).await?)
-        .add_service("/bootloader", bootloader_service(dbus.clone()).await?)
-        .add_service("/network", network_service(network_adapter, events).await?)
-        .add_service("/questions", questions_service(dbus.clone()).await?)
-        .add_service("/users", users_service(dbus.clone()).await?)
-        .add_service("/scripts", scripts_service().await?)
+    let mut builder = MainServiceBuilder::new(events.clone(), web_ui_dir)
+        .add_service(
+            "/l10n",
+            or_unavailable("l10n", l10n_service(dbus.clone(), events.clone()).await),
+        )
+        .add_service(
+            "/manager",
+            or_unavailable("manager", manager_service(dbus.clone()).await),
+        )
+        .add_service(
+            "/software",
+            or_unavailable("software", software_service(dbus.clone()).await),
+        )
+        .add_service(
+            "/storage",
+            or_unavailable("storage", storage_service(dbus.clone()).await),
+        )
+        .add_service(
+            "/bootloader",
+            or_unavailable("bootloader", bootloader_service(dbus.clone()).await),
+        )
+        .add_service("/network", or_unavailable("network", network.await))
+        .add_service(
+            "/questions",
+            or_unavailable("questions", questions_service(dbus.clone()).await),
+        )
+        .add_service(
+            "/users",
+            or_unavailable("users", users_service(dbus.clone()).await),
+        )
+        .add_service(
+            "/scripts",
+            or_unavailable("scripts", scripts_service().await),
+        )
         .with_config(config)
-        .build();
-    Ok(router)
+        .with_certificate(certificate)
+        .with_dbus(dbus);
+    if let Some(journal) = journal {
+        builder = builder.with_journal(journal);
+    }
+    if let Some(recorder) = recorder {
+        builder = builder.with_recorder(recorder);
+    }
+    Ok(builder.build())
 }
 
 /// Starts monitoring the D-Bus service progress.
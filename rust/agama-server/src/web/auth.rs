@@ -19,13 +19,19 @@
 // find current contact information at www.suse.com.
 
 //! Contains the code to handle access authorization.
+//!
+//! Clients can authenticate using a bearer token (`Authorization` header) or the `agamaToken`
+//! cookie. As the cookie is sent automatically by the browsers, the requests changing the state
+//! (`POST`, `PUT`, `PATCH` and `DELETE`) which are authenticated with the cookie must include
+//! the CSRF token (double-submit): the `X-CSRF-Token` header must contain the value of the
+//! `agamaCsrfToken` cookie, which is derived from the authentication token.
 
 use super::state::ServiceState;
 use agama_lib::auth::{AuthToken, AuthTokenError, TokenClaims};
 use async_trait::async_trait;
 use axum::{
     extract::FromRequestParts,
-    http::{request, StatusCode},
+    http::{request, Method, StatusCode},
     response::{IntoResponse, Response},
     Json, RequestPartsExt,
 };
@@ -33,6 +39,7 @@ use axum_extra::{
     headers::{self, authorization::Bearer},
     TypedHeader,
 };
+use openssl::{error::ErrorStack, hash::MessageDigest, memcmp, pkey::PKey, sign::Signer};
 use pam::PamError;
 use serde_json::json;
 use thiserror::Error;
@@ -49,6 +56,12 @@ pub enum AuthError {
     /// The authentication failed (most probably the password is wrong)
     #[error("Authentication via PAM failed: {0}")]
     Failed(#[from] PamError),
+    /// The CSRF token is missing or it does not match the authentication token.
+    #[error("Missing or invalid CSRF token")]
+    InvalidCsrfToken,
+    /// The CSRF token could not be generated.
+    #[error("Could not generate the CSRF token: {0}")]
+    CsrfToken(#[from] ErrorStack),
 }
 
 impl IntoResponse for AuthError {
@@ -56,7 +69,86 @@ impl IntoResponse for AuthError {
         let body = json!({
             "error": self.to_string()
         });
-        (StatusCode::BAD_REQUEST, Json(body)).into_response()
+        let status = match self {
+            AuthError::InvalidCsrfToken => StatusCode::FORBIDDEN,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Name of the cookie containing the authentication token.
+pub const AUTH_COOKIE: &str = "agamaToken";
+/// Name of the cookie containing the CSRF token.
+pub const CSRF_COOKIE: &str = "agamaCsrfToken";
+/// Name of the header containing the CSRF token.
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// Returns the CSRF token for an authentication token.
+///
+/// It is an HMAC of the authentication token, so it does not need to be stored and it cannot be
+/// forged without knowing the secret.
+///
+/// * `token`: authentication token.
+/// * `secret`: secret used to sign the tokens.
+pub fn csrf_token(token: &AuthToken, secret: &str) -> Result<String, AuthError> {
+    let key = PKey::hmac(secret.as_bytes())?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(token.as_str().as_bytes())?;
+    let hmac = signer.sign_to_vec()?;
+    Ok(hmac.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Checks the CSRF token of a request authenticated using the cookie.
+///
+/// * `parts`: request parts.
+/// * `cookie`: request cookies.
+/// * `token`: authentication token.
+/// * `secret`: secret used to sign the tokens.
+fn check_csrf_token(
+    parts: &request::Parts,
+    cookie: &headers::Cookie,
+    token: &AuthToken,
+    secret: &str,
+) -> Result<(), AuthError> {
+    if [Method::GET, Method::HEAD, Method::OPTIONS].contains(&parts.method) {
+        return Ok(());
+    }
+
+    let header = parts
+        .headers
+        .get(CSRF_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(AuthError::InvalidCsrfToken)?;
+    let cookie = cookie.get(CSRF_COOKIE).ok_or(AuthError::InvalidCsrfToken)?;
+    let expected = csrf_token(token, secret)?;
+
+    let matches = |value: &str| {
+        value.len() == expected.len() && memcmp::eq(value.as_bytes(), expected.as_bytes())
+    };
+    if matches(header) && matches(cookie) {
+        Ok(())
+    } else {
+        Err(AuthError::InvalidCsrfToken)
+    }
+}
+
+/// Credentials of the process on the other side of a Unix socket.
+///
+/// The server adds them to the requests received through a Unix socket. Requests coming from
+/// root are considered as authenticated.
+#[derive(Clone, Copy, Debug)]
+pub struct PeerCredentials {
+    /// User ID of the peer process.
+    pub uid: u32,
+    /// Process ID of the peer process, if known.
+    pub pid: Option<i32>,
+}
+
+impl PeerCredentials {
+    /// Whether the peer process belongs to root.
+    pub fn is_root(&self) -> bool {
+        self.uid == 0
     }
 }
 
@@ -68,24 +160,33 @@ impl FromRequestParts<ServiceState> for TokenClaims {
         parts: &mut request::Parts,
         state: &ServiceState,
     ) -> Result<Self, Self::Rejection> {
-        let token = match parts
+        if parts
+            .extensions
+            .get::<PeerCredentials>()
+            .is_some_and(|c| c.is_root())
+        {
+            return Ok(TokenClaims::default());
+        }
+
+        let secret = &state.config.jwt_secret;
+        match parts
             .extract::<TypedHeader<headers::Authorization<Bearer>>>()
             .await
         {
-            Ok(TypedHeader(headers::Authorization(bearer))) => bearer.token().to_owned(),
+            Ok(TypedHeader(headers::Authorization(bearer))) => {
+                let token = AuthToken::new(bearer.token());
+                Ok(token.claims(secret)?)
+            }
             Err(_) => {
-                let cookie = parts
+                let TypedHeader(cookie) = parts
                     .extract::<TypedHeader<headers::Cookie>>()
                     .await
                     .map_err(|_| AuthError::MissingToken)?;
-                cookie
-                    .get("agamaToken")
-                    .ok_or(AuthError::MissingToken)?
-                    .to_owned()
+                let token = AuthToken::new(cookie.get(AUTH_COOKIE).ok_or(AuthError::MissingToken)?);
+                let claims = token.claims(secret)?;
+                check_csrf_token(parts, &cookie, &token, secret)?;
+                Ok(claims)
             }
-        };
-
-        let token = AuthToken::new(&token);
-        Ok(token.claims(&state.config.jwt_secret)?)
+        }
     }
 }
@@ -344,6 +344,11 @@ impl Issue {
             severity,
         }
     }
+
+    /// Returns the issue severity (0 for warnings, 1 for errors).
+    pub fn severity(&self) -> u32 {
+        self.severity
+    }
 }
 
 /// Builds a stream of the changes in the the `org.opensuse.Agama1.Issues`
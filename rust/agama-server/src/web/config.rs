@@ -30,15 +30,30 @@
 //! All the settings are merged into a single configuration. The values in the latter locations
 //! take precedence.
 
+use cidr::IpCidr;
 use config::{Config, ConfigError, File};
 use rand::distributions::{Alphanumeric, DistString};
 use serde::Deserialize;
+use std::net::IpAddr;
 
 /// Web service configuration.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize)]
 pub struct ServiceConfig {
     /// Key to sign the JSON Web Tokens.
     pub jwt_secret: String,
+    /// Origins allowed to access the API from a browser (CORS), e.g. `https://portal.example.net`.
+    /// `*` allows any origin, although the credentials (cookies) are not sent in that case. By
+    /// default, only same-origin access is allowed.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Addresses or networks (e.g., `10.0.0.0/8`) of the reverse proxies whose
+    /// `X-Forwarded-Proto`, `X-Forwarded-Host` and `X-Forwarded-For` headers are honored.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpCidr>,
+    /// Prefix of all the URLs (e.g., `/installers/node1`), useful when running behind a reverse
+    /// proxy which does not strip it.
+    #[serde(default)]
+    pub url_prefix: Option<String>,
 }
 
 impl ServiceConfig {
@@ -55,12 +70,57 @@ impl ServiceConfig {
             .build()?;
         config.try_deserialize()
     }
-}
 
-impl Default for ServiceConfig {
-    fn default() -> Self {
-        Self {
-            jwt_secret: "".to_string(),
+    /// Returns the normalized URL prefix (e.g., `/installers/node1`) or an empty string if there
+    /// is no prefix.
+    pub fn url_prefix(&self) -> String {
+        let prefix = self
+            .url_prefix
+            .as_deref()
+            .unwrap_or_default()
+            .trim_matches('/');
+        if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("/{prefix}")
         }
     }
+
+    /// Determines whether the given address belongs to a trusted proxy.
+    ///
+    /// * `addr`: address to check.
+    pub fn is_trusted_proxy(&self, addr: &IpAddr) -> bool {
+        let addr = addr.to_canonical();
+        self.trusted_proxies.iter().any(|n| n.contains(&addr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ServiceConfig;
+    use std::net::IpAddr;
+
+    #[test]
+    fn test_url_prefix() {
+        let mut config = ServiceConfig::default();
+        assert_eq!(config.url_prefix(), "");
+
+        config.url_prefix = Some("installers/node1/".to_string());
+        assert_eq!(config.url_prefix(), "/installers/node1");
+    }
+
+    #[test]
+    fn test_trusted_proxies() {
+        let config: ServiceConfig = serde_json::from_str(
+            r#"{ "jwt_secret": "", "trusted_proxies": ["10.0.0.0/8", "192.168.1.1"] }"#,
+        )
+        .unwrap();
+
+        let addr: IpAddr = "10.1.2.3".parse().unwrap();
+        assert!(config.is_trusted_proxy(&addr));
+        let addr: IpAddr = "::ffff:192.168.1.1".parse().unwrap();
+        assert!(config.is_trusted_proxy(&addr));
+        let addr: IpAddr = "192.168.1.2".parse().unwrap();
+        assert!(!config.is_trusted_proxy(&addr));
+    }
 }
@@ -19,11 +19,11 @@
 // find current contact information at www.suse.com.
 
 //! This module implements builders for the generation of OpenAPI documentation for the common APIs
-//! (e.g., issues, service status or progress).
+//! (e.g., issues, service status, progress or jobs).
 
 use super::ApiDocBuilder;
 use crate::web::common::{Issue, ServiceStatus};
-use agama_lib::progress::Progress;
+use agama_lib::{jobs::Job, progress::Progress};
 use utoipa::openapi::{
     path::OperationBuilder, schema::RefBuilder, ArrayBuilder, Components, ComponentsBuilder,
     ContentBuilder, HttpMethod, PathItem, Paths, PathsBuilder, ResponseBuilder, ResponsesBuilder,
@@ -206,3 +206,58 @@ impl ApiDocBuilder for ProgressApiDocBuilder {
         ComponentsBuilder::new().schema_from::<Progress>().build()
     }
 }
+
+/// Implements a builder for the jobs API documentation.
+pub struct JobsApiDocBuilder {
+    path: String,
+}
+
+impl JobsApiDocBuilder {
+    /// Creates a new builder.
+    ///
+    /// * `path`: path of the API (e.g., "/api/storage/jobs").
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+}
+
+impl ApiDocBuilder for JobsApiDocBuilder {
+    fn title(&self) -> String {
+        "Jobs HTTP API".to_string()
+    }
+
+    fn paths(&self) -> Paths {
+        let path_item = PathItem::new(
+            HttpMethod::Get,
+            OperationBuilder::new()
+                .summary(Some("Service jobs".to_string()))
+                .operation_id(Some("jobs"))
+                .responses(
+                    ResponsesBuilder::new().response(
+                        "200",
+                        ResponseBuilder::new().description("List of jobs").content(
+                            "application/json",
+                            ContentBuilder::new()
+                                .schema(Some(
+                                    ArrayBuilder::new().items(
+                                        RefBuilder::new()
+                                            .ref_location("#/components/schemas/Job".to_string()),
+                                    ),
+                                ))
+                                .build(),
+                        ),
+                    ),
+                ),
+        );
+
+        PathsBuilder::new()
+            .path(self.path.to_string(), path_item)
+            .build()
+    }
+
+    fn components(&self) -> Components {
+        ComponentsBuilder::new().schema_from::<Job>().build()
+    }
+}
@@ -40,6 +40,7 @@ impl ApiDocBuilder for ManagerApiDocBuilder {
             .path_from::<crate::manager::web::__path_installer_status>()
             .path_from::<crate::manager::web::__path_list_logs>()
             .path_from::<crate::manager::web::__path_probe_action>()
+            .path_from::<crate::manager::web::__path_probe_sync_action>()
             .build()
     }
 
@@ -52,8 +53,8 @@ impl ApiDocBuilder for ManagerApiDocBuilder {
     }
 
     fn nested(&self) -> Option<OpenApi> {
-        let mut status = ServiceStatusApiDocBuilder::new("/api/storage/status").build();
-        let progress = ProgressApiDocBuilder::new("/api/storage/progress").build();
+        let mut status = ServiceStatusApiDocBuilder::new("/api/manager/status").build();
+        let progress = ProgressApiDocBuilder::new("/api/manager/progress").build();
         status.merge(progress);
         Some(status)
     }
@@ -32,12 +32,25 @@ impl ApiDocBuilder for MiscApiDocBuilder {
     fn paths(&self) -> Paths {
         PathsBuilder::new()
             .path_from::<crate::web::http::__path_ping>()
+            .path_from::<crate::web::http::__path_login>()
+            .path_from::<crate::web::http::__path_login_from_query>()
+            .path_from::<crate::web::http::__path_logout>()
+            .path_from::<crate::web::http::__path_session>()
+            .path_from::<crate::web::health::__path_health>()
+            .path_from::<crate::web::http::__path_certificate>()
+            .path_from::<crate::web::metrics::__path_metrics>()
+            .path_from::<crate::web::journal::__path_history>()
             .build()
     }
 
     fn components(&self) -> Components {
         ComponentsBuilder::new()
             .schema_from::<crate::web::http::PingResponse>()
+            .schema_from::<crate::web::http::AuthResponse>()
+            .schema_from::<crate::web::http::LoginRequest>()
+            .schema_from::<crate::web::health::HealthResponse>()
+            .schema_from::<crate::web::health::BackendHealth>()
+            .schema_from::<crate::web::http::CertificateResponse>()
             .build()
     }
 }
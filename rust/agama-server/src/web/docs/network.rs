@@ -36,13 +36,27 @@ impl ApiDocBuilder for NetworkApiDocBuilder {
             .path_from::<crate::network::web::__path_apply>()
             .path_from::<crate::network::web::__path_connect>()
             .path_from::<crate::network::web::__path_connection>()
+            .path_from::<crate::network::web::__path_confirm>()
+            .path_from::<crate::network::web::__path_bindings>()
             .path_from::<crate::network::web::__path_connections>()
             .path_from::<crate::network::web::__path_delete_connection>()
             .path_from::<crate::network::web::__path_devices>()
+            .path_from::<crate::network::web::__path_diagnostics>()
             .path_from::<crate::network::web::__path_disconnect>()
+            .path_from::<crate::network::web::__path_general_settings>()
             .path_from::<crate::network::web::__path_general_state>()
+            .path_from::<crate::network::web::__path_import>()
+            .path_from::<crate::network::web::__path_import_preview>()
+            .path_from::<crate::network::web::__path_install>()
+            .path_from::<crate::network::web::__path_issues>()
+            .path_from::<crate::network::web::__path_nmstate>()
+            .path_from::<crate::network::web::__path_proxy>()
+            .path_from::<crate::network::web::__path_rollback>()
             .path_from::<crate::network::web::__path_update_connection>()
+            .path_from::<crate::network::web::__path_update_general_settings>()
             .path_from::<crate::network::web::__path_update_general_state>()
+            .path_from::<crate::network::web::__path_update_nmstate>()
+            .path_from::<crate::network::web::__path_update_proxy>()
             .path_from::<crate::network::web::__path_wifi_networks>()
             .build()
     }
@@ -50,17 +64,34 @@ impl ApiDocBuilder for NetworkApiDocBuilder {
     fn components(&self) -> Components {
         ComponentsBuilder::new()
             .schema_from::<agama_lib::network::settings::BondSettings>()
+            .schema_from::<agama_lib::network::settings::BridgePortSettings>()
+            .schema_from::<agama_lib::network::settings::BridgeSettings>()
+            .schema_from::<agama_lib::network::settings::GeneralSettings>()
             .schema_from::<agama_lib::network::settings::IEEE8021XSettings>()
+            .schema_from::<agama_lib::network::settings::InfinibandSettings>()
+            .schema_from::<agama_lib::network::settings::IpRouteSettings>()
             .schema_from::<agama_lib::network::settings::MatchSettings>()
             .schema_from::<agama_lib::network::settings::NetworkConnection>()
             .schema_from::<agama_lib::network::settings::NetworkSettings>()
             .schema_from::<agama_lib::network::settings::NetworkSettings>()
+            .schema_from::<agama_lib::network::settings::ProxySettings>()
+            .schema_from::<agama_lib::network::settings::TunSettings>()
+            .schema_from::<agama_lib::network::settings::VlanSettings>()
+            .schema_from::<agama_lib::network::settings::WireguardPeerSettings>()
+            .schema_from::<agama_lib::network::settings::WireguardSettings>()
             .schema_from::<agama_lib::network::settings::WirelessSettings>()
             .schema_from::<agama_lib::network::types::BondMode>()
             .schema_from::<agama_lib::network::types::DeviceState>()
             .schema_from::<agama_lib::network::types::DeviceType>()
+            .schema_from::<agama_lib::network::types::DnsMode>()
             .schema_from::<agama_lib::network::types::SSID>()
             .schema_from::<agama_lib::network::types::Status>()
+            .schema_from::<crate::network::binding::BindingPreview>()
+            .schema_from::<crate::network::binding::DeviceBinding>()
+            .schema_from::<crate::network::diagnostics::CheckKind>()
+            .schema_from::<crate::network::diagnostics::CheckResult>()
+            .schema_from::<crate::network::diagnostics::CheckStatus>()
+            .schema_from::<crate::network::diagnostics::DiagnosticsReport>()
             .schema_from::<crate::network::model::AccessPoint>()
             .schema_from::<crate::network::model::BondConfig>()
             .schema_from::<crate::network::model::BondOptions>()
@@ -72,6 +103,7 @@ impl ApiDocBuilder for NetworkApiDocBuilder {
             .schema_from::<crate::network::model::EAPMethod>()
             .schema_from::<crate::network::model::GroupAlgorithm>()
             .schema_from::<crate::network::model::GeneralState>()
+            .schema_from::<crate::network::model::GlobalDns>()
             .schema_from::<crate::network::model::IEEE8021XConfig>()
             .schema_from::<crate::network::model::InfinibandConfig>()
             .schema_from::<crate::network::model::InfinibandTransportMode>()
@@ -90,12 +122,18 @@ impl ApiDocBuilder for NetworkApiDocBuilder {
             .schema_from::<crate::network::model::VlanConfig>()
             .schema_from::<crate::network::model::VlanProtocol>()
             .schema_from::<crate::network::model::WEPAuthAlg>()
+            .schema_from::<crate::network::model::WireguardConfig>()
+            .schema_from::<crate::network::model::WireguardPeer>()
             .schema_from::<crate::network::model::WEPKeyType>()
             .schema_from::<crate::network::model::WEPSecurity>()
             .schema_from::<crate::network::model::WPAProtocolVersion>()
             .schema_from::<crate::network::model::WirelessBand>()
             .schema_from::<crate::network::model::WirelessConfig>()
             .schema_from::<crate::network::model::WirelessMode>()
+            .schema_from::<crate::network::install::TargetBackend>()
+            .schema_from::<crate::network::web::ImportPreview>()
+            .schema_from::<crate::network::web::InstallParams>()
+            .schema_from::<crate::web::common::Issue>()
             .schema("IpAddr", schemas::ip_addr())
             .schema("IpInet", schemas::ip_inet())
             .schema("macaddr.MacAddr6", schemas::mac_addr6())
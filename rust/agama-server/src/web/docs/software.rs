@@ -21,7 +21,7 @@
 use utoipa::openapi::{Components, ComponentsBuilder, OpenApi, Paths, PathsBuilder};
 
 use super::{
-    common::{IssuesApiDocBuilder, ServiceStatusApiDocBuilder},
+    common::{IssuesApiDocBuilder, ProgressApiDocBuilder, ServiceStatusApiDocBuilder},
     ApiDocBuilder,
 };
 
@@ -34,11 +34,14 @@ impl ApiDocBuilder for SoftwareApiDocBuilder {
 
     fn paths(&self) -> Paths {
         PathsBuilder::new()
+            .path_from::<crate::software::web::__path_deregister>()
             .path_from::<crate::software::web::__path_get_config>()
+            .path_from::<crate::software::web::__path_get_registration>()
             .path_from::<crate::software::web::__path_patterns>()
             .path_from::<crate::software::web::__path_probe>()
             .path_from::<crate::software::web::__path_products>()
             .path_from::<crate::software::web::__path_proposal>()
+            .path_from::<crate::software::web::__path_register>()
             .path_from::<crate::software::web::__path_set_config>()
             .path_from::<crate::software::web::__path_set_resolvables>()
             .build()
@@ -49,6 +52,7 @@ impl ApiDocBuilder for SoftwareApiDocBuilder {
             .schema_from::<agama_lib::product::Product>()
             .schema_from::<agama_lib::product::RegistrationRequirement>()
             .schema_from::<agama_lib::software::Pattern>()
+            .schema_from::<agama_lib::software::model::RegistrationError>()
             .schema_from::<agama_lib::software::model::RegistrationInfo>()
             .schema_from::<agama_lib::software::model::RegistrationParams>()
             .schema_from::<agama_lib::software::model::ResolvableParams>()
@@ -68,13 +72,15 @@ impl ApiDocBuilder for SoftwareApiDocBuilder {
                 "software_issues",
             )
             .add(
-                "/api/product/issues/product",
+                "/api/software/issues/product",
                 "List of product-related issues",
                 "product_issues",
             )
             .build();
-        let status = ServiceStatusApiDocBuilder::new("/api/storage/status").build();
+        let status = ServiceStatusApiDocBuilder::new("/api/software/status").build();
+        let progress = ProgressApiDocBuilder::new("/api/software/progress").build();
         issues.merge(status);
+        issues.merge(progress);
         Some(issues)
     }
 }
@@ -21,7 +21,9 @@
 use utoipa::openapi::{Components, ComponentsBuilder, OpenApi, Paths, PathsBuilder};
 
 use super::{
-    common::{IssuesApiDocBuilder, ProgressApiDocBuilder, ServiceStatusApiDocBuilder},
+    common::{
+        IssuesApiDocBuilder, JobsApiDocBuilder, ProgressApiDocBuilder, ServiceStatusApiDocBuilder,
+    },
     ApiDocBuilder,
 };
 
@@ -36,9 +38,11 @@ impl ApiDocBuilder for StorageApiDocBuilder {
         PathsBuilder::new()
             .path_from::<crate::storage::web::__path_actions>()
             .path_from::<crate::storage::web::__path_devices_dirty>()
+            .path_from::<crate::storage::web::__path_get_config>()
             .path_from::<crate::storage::web::__path_get_proposal_settings>()
             .path_from::<crate::storage::web::__path_probe>()
             .path_from::<crate::storage::web::__path_product_params>()
+            .path_from::<crate::storage::web::__path_set_config>()
             .path_from::<crate::storage::web::__path_set_proposal_settings>()
             .path_from::<crate::storage::web::__path_staging_devices>()
             .path_from::<crate::storage::web::__path_system_devices>()
@@ -130,7 +134,9 @@ impl ApiDocBuilder for StorageApiDocBuilder {
         let status = ServiceStatusApiDocBuilder::new("/api/storage/status").build();
         let progress = ProgressApiDocBuilder::new("/api/storage/progress").build();
         issues.merge(status);
+        let jobs = JobsApiDocBuilder::new("/api/storage/jobs").build();
         issues.merge(progress);
+        issues.merge(jobs);
         Some(issues)
     }
 }
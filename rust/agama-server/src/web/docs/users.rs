@@ -65,7 +65,7 @@ impl ApiDocBuilder for UsersApiDocBuilder {
                 "user_issues",
             )
             .build();
-        let status = ServiceStatusApiDocBuilder::new("/api/storage/status").build();
+        let status = ServiceStatusApiDocBuilder::new("/api/users/status").build();
         issues.merge(status);
         Some(issues)
     }
@@ -36,8 +36,12 @@ use agama_lib::{
     users::FirstUser,
 };
 use serde::Serialize;
-use std::collections::HashMap;
-use tokio::sync::broadcast::{Receiver, Sender};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast::{self, error::RecvError, Receiver, Sender};
 
 use super::common::Issue;
 
@@ -153,3 +157,274 @@ pub enum Event {
 
 pub type EventsSender = Sender<Event>;
 pub type EventsReceiver = Receiver<Event>;
+
+/// Event with its sequence number.
+///
+/// The sequence number allows the clients to detect lost events and to resume the stream after
+/// reconnecting (see [EventsBuffer]).
+#[derive(Clone, Debug, Serialize)]
+pub struct SequencedEvent {
+    pub seq: u64,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+/// Control messages sent to the clients through the events streams.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub enum ControlMessage {
+    /// Some events were lost. The client receives the events from `seq` on.
+    Lagged { seq: u64 },
+}
+
+/// Events a client is interested in.
+#[derive(Debug, Default)]
+pub struct EventsFilter {
+    events: Vec<String>,
+    services: Vec<String>,
+}
+
+impl EventsFilter {
+    /// Creates a new filter. An empty list means "all".
+    ///
+    /// * `events`: event types (e.g., "Progress").
+    /// * `services`: services (e.g., "storage").
+    pub fn new(events: Vec<String>, services: Vec<String>) -> Self {
+        Self { events, services }
+    }
+
+    /// Whether the event (in JSON format) should be sent to the client.
+    ///
+    /// Events which are not related to a service are sent regardless the services filter.
+    pub fn matches(&self, event: &Value) -> bool {
+        let matches_type = self.events.is_empty()
+            || event["type"]
+                .as_str()
+                .is_some_and(|t| self.events.iter().any(|e| e == t));
+        let matches_service = self.services.is_empty()
+            || event["service"]
+                .as_str()
+                .map_or(true, |s| self.services.iter().any(|e| e == s));
+        matches_type && matches_service
+    }
+}
+
+/// Result of subscribing to the [EventsBuffer].
+pub struct Subscription {
+    /// Buffered events the client has not seen yet.
+    pub missed: Vec<SequencedEvent>,
+    /// Whether some of the events the client has not seen are not available anymore.
+    pub lagged: bool,
+    /// Sequence number of the next event to be sent.
+    pub next_seq: u64,
+    /// Receiver for the new events.
+    pub receiver: Receiver<SequencedEvent>,
+}
+
+struct EventsBufferState {
+    next_seq: u64,
+    lagged: u64,
+    events: VecDeque<SequencedEvent>,
+}
+
+/// Assigns sequence numbers to the events and keeps the last ones in memory.
+///
+/// It listens to the events channel, so the code emitting events does not need to know about
+/// it. The clients subscribe to the buffer instead of the events channel to receive the
+/// [SequencedEvent]s, including the ones they missed if they reconnect.
+#[derive(Clone)]
+pub struct EventsBuffer {
+    state: Arc<Mutex<EventsBufferState>>,
+    sender: Sender<SequencedEvent>,
+}
+
+impl EventsBuffer {
+    /// Number of events kept in memory.
+    pub const CAPACITY: usize = 1024;
+
+    /// Creates a new buffer listening to the given events channel.
+    ///
+    /// It spawns a task to process the events, so it must be called within a Tokio runtime.
+    ///
+    /// * `events`: channel to listen for events.
+    pub fn new(events: &EventsSender) -> Self {
+        let (sender, _) = broadcast::channel(Self::CAPACITY);
+        let state = EventsBufferState {
+            next_seq: 1,
+            lagged: 0,
+            events: VecDeque::with_capacity(Self::CAPACITY),
+        };
+        let buffer = Self {
+            state: Arc::new(Mutex::new(state)),
+            sender,
+        };
+        tokio::spawn(buffer.clone().run(events.subscribe()));
+        buffer
+    }
+
+    /// Subscribes to the events.
+    ///
+    /// * `last_seq`: sequence number of the last event seen by the client. If it is `None`,
+    ///   only new events are received.
+    pub fn subscribe(&self, last_seq: Option<u64>) -> Subscription {
+        let state = self.state.lock().unwrap();
+        // subscribing while holding the lock guarantees that no event is lost or duplicated
+        let receiver = self.sender.subscribe();
+        let next_seq = state.next_seq;
+
+        let Some(last_seq) = last_seq else {
+            return Subscription {
+                missed: vec![],
+                lagged: false,
+                next_seq,
+                receiver,
+            };
+        };
+
+        let oldest_seq = state.events.front().map(|e| e.seq).unwrap_or(next_seq);
+        let missed = state
+            .events
+            .iter()
+            .filter(|e| e.seq > last_seq)
+            .cloned()
+            .collect();
+        Subscription {
+            missed,
+            lagged: last_seq + 1 < oldest_seq,
+            next_seq,
+            receiver,
+        }
+    }
+
+    /// Returns the buffered events with a sequence number greater than the given one.
+    ///
+    /// * `last_seq`: sequence number of the last known event.
+    pub fn events_since(&self, last_seq: u64) -> Vec<SequencedEvent> {
+        let state = self.state.lock().unwrap();
+        state
+            .events
+            .iter()
+            .filter(|e| e.seq > last_seq)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the number of events the buffer skipped because it lagged behind.
+    pub fn lagged(&self) -> u64 {
+        self.state.lock().unwrap().lagged
+    }
+
+    fn push(&self, event: Event) {
+        let mut state = self.state.lock().unwrap();
+        let event = SequencedEvent {
+            seq: state.next_seq,
+            event,
+        };
+        state.next_seq += 1;
+        if state.events.len() == Self::CAPACITY {
+            state.events.pop_front();
+        }
+        state.events.push_back(event.clone());
+        _ = self.sender.send(event);
+    }
+
+    async fn run(self, mut events: EventsReceiver) {
+        loop {
+            match events.recv().await {
+                Ok(event) => self.push(event),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("The events buffer skipped {} events", skipped);
+                    // do not replay events across the gap
+                    let mut state = self.state.lock().unwrap();
+                    state.next_seq += skipped;
+                    state.lagged += skipped;
+                    state.events.clear();
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, EventsBuffer, EventsFilter, SequencedEvent};
+    use serde_json::json;
+    use tokio::sync::broadcast;
+
+    async fn wait_for(buffer: &EventsBuffer, seq: u64) {
+        while buffer.events_since(seq - 1).is_empty() {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_events() {
+        let (tx, _rx) = broadcast::channel(16);
+        let buffer = EventsBuffer::new(&tx);
+
+        tx.send(Event::QuestionsChanged).unwrap();
+        tx.send(Event::RegistrationChanged).unwrap();
+        wait_for(&buffer, 2).await;
+
+        let subscription = buffer.subscribe(Some(1));
+        assert!(!subscription.lagged);
+        assert_eq!(subscription.next_seq, 3);
+        let seqs: Vec<u64> = subscription.missed.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![2]);
+
+        let mut receiver = subscription.receiver;
+        tx.send(Event::QuestionsChanged).unwrap();
+        let SequencedEvent { seq, event } = receiver.recv().await.unwrap();
+        assert_eq!(seq, 3);
+        assert!(matches!(event, Event::QuestionsChanged));
+    }
+
+    #[tokio::test]
+    async fn test_resume_expired_events() {
+        let (tx, _rx) = broadcast::channel(16);
+        let buffer = EventsBuffer::new(&tx);
+
+        for seq in 1..=(EventsBuffer::CAPACITY as u64 + 2) {
+            // the channel is small, so wait for the buffer to process each event
+            tx.send(Event::QuestionsChanged).unwrap();
+            wait_for(&buffer, seq).await;
+        }
+
+        let subscription = buffer.subscribe(Some(0));
+        assert!(subscription.lagged);
+        assert_eq!(subscription.missed.len(), EventsBuffer::CAPACITY);
+    }
+
+    #[test]
+    fn test_serialize_sequenced_event() {
+        let event = SequencedEvent {
+            seq: 7,
+            event: Event::LocaleChanged {
+                locale: "en_US.UTF-8".to_string(),
+            },
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["seq"], 7);
+        assert_eq!(json["type"], "LocaleChanged");
+        assert_eq!(json["locale"], "en_US.UTF-8");
+    }
+
+    #[test]
+    fn test_events_filter() {
+        let progress = json!({ "seq": 1, "type": "Progress", "service": "storage" });
+        let questions = json!({ "seq": 2, "type": "QuestionsChanged" });
+
+        let all = EventsFilter::default();
+        assert!(all.matches(&progress));
+        assert!(all.matches(&questions));
+
+        let by_type = EventsFilter::new(vec!["QuestionsChanged".to_string()], vec![]);
+        assert!(!by_type.matches(&progress));
+        assert!(by_type.matches(&questions));
+
+        let by_service = EventsFilter::new(vec![], vec!["software".to_string()]);
+        assert!(!by_service.matches(&progress));
+        assert!(by_service.matches(&questions));
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Implements the health check of the backend services.
+//!
+//! The web server can start even if some backend service is not available. In that case, the
+//! routes of that service respond with a `503 Service Unavailable` error (see
+//! [unavailable_service]) and the `/api/health` endpoint reports the problem.
+
+use super::state::ServiceState;
+use agama_lib::{error::ServiceError, proxies::ServiceStatusProxy};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json, Router};
+use serde::Serialize;
+use serde_json::json;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+const PING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Backend service used by the web server.
+pub struct Backend {
+    /// Name of the service in the API.
+    pub name: &'static str,
+    /// D-Bus service name.
+    pub destination: &'static str,
+    /// D-Bus object implementing the `org.opensuse.Agama1.ServiceStatus` interface, if any.
+    pub status_path: Option<&'static str>,
+    /// Whether the service lives in the system bus instead of Agama's bus.
+    pub system_bus: bool,
+}
+
+/// Backend services to check.
+pub const BACKENDS: [Backend; 7] = [
+    Backend {
+        name: "manager",
+        destination: "org.opensuse.Agama.Manager1",
+        status_path: Some("/org/opensuse/Agama/Manager1"),
+        system_bus: false,
+    },
+    Backend {
+        name: "software",
+        destination: "org.opensuse.Agama.Software1",
+        status_path: Some("/org/opensuse/Agama/Software1"),
+        system_bus: false,
+    },
+    Backend {
+        name: "storage",
+        destination: "org.opensuse.Agama.Storage1",
+        status_path: Some("/org/opensuse/Agama/Storage1"),
+        system_bus: false,
+    },
+    Backend {
+        name: "users",
+        destination: "org.opensuse.Agama.Manager1",
+        status_path: Some("/org/opensuse/Agama/Users1"),
+        system_bus: false,
+    },
+    Backend {
+        name: "l10n",
+        destination: "org.opensuse.Agama1",
+        status_path: None,
+        system_bus: false,
+    },
+    Backend {
+        name: "questions",
+        destination: "org.opensuse.Agama1",
+        status_path: None,
+        system_bus: false,
+    },
+    Backend {
+        name: "network",
+        destination: "org.freedesktop.NetworkManager",
+        status_path: None,
+        system_bus: true,
+    },
+];
+
+#[derive(Serialize, ToSchema)]
+pub struct BackendHealth {
+    /// Service name
+    name: String,
+    /// D-Bus service name
+    service: String,
+    /// Whether the service is reachable
+    available: bool,
+    /// Current service status (0 = idle, 1 = busy), if the service reports it
+    status: Option<u32>,
+    /// Error message when the service is not available
+    error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct HealthResponse {
+    /// Whether all the backend services are available
+    healthy: bool,
+    /// Health of each backend service
+    services: Vec<BackendHealth>,
+}
+
+/// Pings a D-Bus service using the `org.freedesktop.DBus.Peer` interface.
+///
+/// * `dbus`: D-Bus connection.
+/// * `destination`: D-Bus service name.
+pub async fn ping(dbus: &zbus::Connection, destination: &str) -> Result<(), ServiceError> {
+    let proxy = zbus::fdo::PeerProxy::builder(dbus)
+        .destination(destination)?
+        .path("/")?
+        .build()
+        .await?;
+    tokio::time::timeout(PING_TIMEOUT, proxy.ping())
+        .await
+        .map_err(|_| ServiceError::InternalError(format!("{} did not answer", destination)))??;
+    Ok(())
+}
+
+async fn service_status(
+    dbus: &zbus::Connection,
+    backend: &Backend,
+) -> Result<Option<u32>, ServiceError> {
+    let Some(path) = backend.status_path else {
+        return Ok(None);
+    };
+
+    let proxy = ServiceStatusProxy::builder(dbus)
+        .destination(backend.destination)?
+        .path(path)?
+        .build()
+        .await?;
+    Ok(Some(proxy.current().await?))
+}
+
+async fn check(dbus: Option<&zbus::Connection>, backend: &Backend) -> BackendHealth {
+    let result = match dbus {
+        Some(dbus) => match ping(dbus, backend.destination).await {
+            Ok(()) => service_status(dbus, backend).await,
+            Err(error) => Err(error),
+        },
+        None => Err(ServiceError::InternalError(
+            "No D-Bus connection".to_string(),
+        )),
+    };
+
+    let (available, status, error) = match result {
+        Ok(status) => (true, status, None),
+        Err(error) => (false, None, Some(error.to_string())),
+    };
+    BackendHealth {
+        name: backend.name.to_string(),
+        service: backend.destination.to_string(),
+        available,
+        status,
+        error,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    context_path = "/api",
+    responses(
+        (status = 200, description = "All the backend services are available", body = HealthResponse),
+        (status = 503, description = "Some backend service is not available", body = HealthResponse)
+    )
+)]
+pub async fn health(State(state): State<ServiceState>) -> impl IntoResponse {
+    let system_dbus = zbus::Connection::system().await.ok();
+
+    let checks = BACKENDS.iter().map(|backend| {
+        let dbus = if backend.system_bus {
+            system_dbus.as_ref()
+        } else {
+            state.dbus.as_ref()
+        };
+        check(dbus, backend)
+    });
+    let services = futures_util::future::join_all(checks).await;
+
+    let healthy = services.iter().all(|s| s.available);
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(HealthResponse { healthy, services }))
+}
+
+/// Returns the service router or, if it could not be built, a router that responds to any
+/// request with a `503 Service Unavailable` error.
+///
+/// * `name`: service name.
+/// * `result`: result of building the service router.
+pub fn or_unavailable(name: &str, result: Result<Router, ServiceError>) -> Router {
+    match result {
+        Ok(router) => router,
+        Err(error) => {
+            tracing::error!("The {} service is not available: {}", name, error);
+            unavailable_service(name)
+        }
+    }
+}
+
+/// Returns a router that responds to any request with a `503 Service Unavailable` error.
+///
+/// * `name`: service name.
+pub fn unavailable_service(name: &str) -> Router {
+    let message = format!("The {} service is not available", name);
+    Router::new().fallback(|| async move {
+        let body = json!({ "error": message });
+        (StatusCode::SERVICE_UNAVAILABLE, Json(body))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::or_unavailable;
+    use agama_lib::error::ServiceError;
+    use axum::{body::Body, http::Request, http::StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_unavailable_service() {
+        let router = or_unavailable(
+            "storage",
+            Err(ServiceError::InternalError("not running".to_string())),
+        );
+        let request = Request::builder()
+            .uri("/devices/system")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -20,14 +20,20 @@
 
 //! Implements the basic handlers for the HTTP-based API (login, logout, ping, etc.).
 
-use super::{auth::AuthError, state::ServiceState};
+use super::{
+    auth::{csrf_token, AuthError, AUTH_COOKIE, CSRF_COOKIE},
+    config::ServiceConfig,
+    proxy::ClientInfo,
+    state::ServiceState,
+};
 use agama_lib::auth::{AuthToken, TokenClaims};
 use axum::{
     extract::{Query, State},
     http::{header, HeaderMap, HeaderValue, StatusCode},
     response::IntoResponse,
-    Json,
+    Extension, Json,
 };
+use chrono::Utc;
 use pam::Client;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -52,6 +58,43 @@ pub async fn ping() -> Json<PingResponse> {
     })
 }
 
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateResponse {
+    /// SHA-256 fingerprint of the certificate (colon separated hexadecimal bytes)
+    fingerprint: String,
+    /// Subject alternative names (DNS names and IP addresses)
+    subject_alt_names: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/certificate",
+    context_path = "/api",
+    responses(
+        (status = 200, description = "The certificate used by the server", body = CertificateResponse),
+        (status = 404, description = "The server does not use a certificate")
+    )
+)]
+pub async fn certificate(
+    State(state): State<ServiceState>,
+) -> Result<Json<CertificateResponse>, StatusCode> {
+    let Some(store) = state.certificate else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    let certificate = store.certificate();
+    let fingerprint = certificate.fingerprint().map_err(|error| {
+        tracing::error!("Could not calculate the certificate fingerprint: {}", error);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(CertificateResponse {
+        fingerprint,
+        subject_alt_names: certificate.subject_alt_names(),
+    }))
+}
+
 #[derive(Serialize, utoipa::ToSchema)]
 pub struct AuthResponse {
     /// Bearer token to use on subsequent calls
@@ -73,6 +116,7 @@ pub struct LoginRequest {
 )]
 pub async fn login(
     State(state): State<ServiceState>,
+    Extension(client): Extension<ClientInfo>,
     Json(login): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, AuthError> {
     let mut pam_client = Client::with_password("agama")?;
@@ -82,17 +126,13 @@ pub async fn login(
     pam_client.authenticate()?;
 
     let token = AuthToken::generate(&state.config.jwt_secret)?;
+    let claims = token.claims(&state.config.jwt_secret)?;
     let content = Json(AuthResponse {
         token: token.to_string(),
     });
 
     let mut headers = HeaderMap::new();
-    let cookie = auth_cookie_from_token(&token);
-    headers.insert(
-        header::SET_COOKIE,
-        cookie.parse().expect("could not build a valid cookie"),
-    );
-
+    add_auth_cookies(&mut headers, &token, &claims, &state.config, &client)?;
     Ok((headers, content))
 }
 
@@ -102,38 +142,58 @@ pub struct LoginFromQueryParams {
     token: String,
 }
 
+/// Sets the authentication cookie and redirects to the web UI.
+///
+/// The `Referrer-Policy` header prevents leaking the token to other sites.
 #[utoipa::path(get, path = "/login", responses(
-    (status = 301, description = "Injects the authentication cookie if correct and redirects to the web UI")
+    (status = 307, description = "Injects the authentication cookie if correct and redirects to the web UI")
 ))]
 pub async fn login_from_query(
     State(state): State<ServiceState>,
+    Extension(client): Extension<ClientInfo>,
     Query(params): Query<LoginFromQueryParams>,
 ) -> impl IntoResponse {
     let mut headers = HeaderMap::new();
 
     let token = AuthToken::new(&params.token);
-    if token.claims(&state.config.jwt_secret).is_ok() {
-        let cookie = auth_cookie_from_token(&token);
-        headers.insert(
-            header::SET_COOKIE,
-            cookie.parse().expect("could not build a valid cookie"),
-        );
+    if let Ok(claims) = token.claims(&state.config.jwt_secret) {
+        if let Err(error) = add_auth_cookies(&mut headers, &token, &claims, &state.config, &client)
+        {
+            tracing::error!("Could not set the authentication cookies: {}", error);
+        }
     }
 
-    headers.insert(header::LOCATION, HeaderValue::from_static("/"));
+    let location = format!("{}/", state.config.url_prefix());
+    headers.insert(
+        header::LOCATION,
+        HeaderValue::from_str(&location).expect("could not build a valid location"),
+    );
+    headers.insert(
+        header::REFERRER_POLICY,
+        HeaderValue::from_static("no-referrer"),
+    );
     (StatusCode::TEMPORARY_REDIRECT, headers)
 }
 
 #[utoipa::path(delete, path = "/api/auth", responses(
     (status = 204, description = "The user has been logged out.")
 ))]
-pub async fn logout(_claims: TokenClaims) -> Result<impl IntoResponse, AuthError> {
+pub async fn logout(
+    State(state): State<ServiceState>,
+    _claims: TokenClaims,
+) -> Result<impl IntoResponse, AuthError> {
     let mut headers = HeaderMap::new();
-    let cookie = "agamaToken=deleted; HttpOnly; Expires=Thu, 01 Jan 1970 00:00:00 GMT".to_string();
-    headers.insert(
-        header::SET_COOKIE,
-        cookie.parse().expect("could not build a valid cookie"),
-    );
+    for name in [AUTH_COOKIE, CSRF_COOKIE] {
+        let cookie = format!(
+            "{}=deleted; Path={}; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+            name,
+            cookie_path(&state.config)
+        );
+        headers.append(
+            header::SET_COOKIE,
+            cookie.parse().expect("could not build a valid cookie"),
+        );
+    }
     Ok(headers)
 }
 
@@ -146,15 +206,57 @@ pub async fn session(_claims: TokenClaims) -> Result<(), AuthError> {
     Ok(())
 }
 
-/// Creates the cookie containing the authentication token.
+/// Adds the cookies containing the authentication token and the CSRF token.
 ///
-/// It is a session token (no expiration date) so it should be gone
-/// when the browser is closed.
+/// Both cookies expire with the token and they are restricted to the same site. The
+/// authentication cookie is not accessible from JavaScript, while the CSRF one must be, so the
+/// web UI can send it in the `X-CSRF-Token` header (see [super::auth]).
 ///
 /// See https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Set-Cookie
 /// for further information.
 ///
+/// * `headers`: response headers.
 /// * `token`: authentication token.
-fn auth_cookie_from_token(token: &AuthToken) -> String {
-    format!("agamaToken={}; HttpOnly", &token.to_string())
+/// * `claims`: token claims.
+/// * `config`: service configuration, which defines the URL prefix.
+/// * `client`: client information. The cookies are marked as secure when using HTTPS.
+fn add_auth_cookies(
+    headers: &mut HeaderMap,
+    token: &AuthToken,
+    claims: &TokenClaims,
+    config: &ServiceConfig,
+    client: &ClientInfo,
+) -> Result<(), AuthError> {
+    let max_age = (claims.exp - Utc::now().timestamp()).max(0);
+    let mut attributes = format!(
+        "Path={}; SameSite=Strict; Max-Age={}",
+        cookie_path(config),
+        max_age
+    );
+    if client.is_https() {
+        attributes.push_str("; Secure");
+    }
+
+    let csrf = csrf_token(token, &config.jwt_secret)?;
+    let cookies = [
+        format!("{}={}; HttpOnly; {}", AUTH_COOKIE, token, attributes),
+        format!("{}={}; {}", CSRF_COOKIE, csrf, attributes),
+    ];
+    for cookie in cookies {
+        headers.append(
+            header::SET_COOKIE,
+            cookie.parse().expect("could not build a valid cookie"),
+        );
+    }
+    Ok(())
+}
+
+/// Path of the authentication cookie.
+fn cookie_path(config: &ServiceConfig) -> String {
+    let prefix = config.url_prefix();
+    if prefix.is_empty() {
+        "/".to_string()
+    } else {
+        prefix
+    }
 }
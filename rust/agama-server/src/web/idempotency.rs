@@ -0,0 +1,311 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Implements a middleware to honor the `Idempotency-Key` header.
+//!
+//! Clients (or proxies) might retry a `POST`, `PUT` or `PATCH` request, triggering the same
+//! action twice (e.g., starting the installation). When the request includes an
+//! `Idempotency-Key` header, the response is kept for a while and any further request using the
+//! same key, method and path gets the same response without running the handler again. The
+//! replayed responses include the `Idempotent-Replayed: true` header.
+//!
+//! * A request reusing a key with a different body is rejected (`422 Unprocessable Entity`).
+//! * A request reusing a key which is still being processed is rejected (`409 Conflict`).
+//! * Server errors (`5xx`) are not kept, so the client can retry the request.
+
+use super::state::ServiceState;
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Maximum size of a request or response body.
+const BODY_LIMIT: usize = 10 * 1024 * 1024;
+/// Maximum length of a key.
+const MAX_KEY_LENGTH: usize = 255;
+/// How long the responses are kept.
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+/// Maximum number of kept responses.
+const DEFAULT_CAPACITY: usize = 1024;
+
+#[derive(Clone, Debug)]
+struct CachedResponse {
+    status: StatusCode,
+    content_type: Option<HeaderValue>,
+    body: Bytes,
+}
+
+impl IntoResponse for CachedResponse {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, self.body).into_response();
+        let headers = response.headers_mut();
+        if let Some(content_type) = self.content_type {
+            headers.insert(header::CONTENT_TYPE, content_type);
+        }
+        headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+        response
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    /// Hash of the request body.
+    fingerprint: u64,
+    /// Response, if the request is already processed.
+    response: Option<CachedResponse>,
+    created_at: Instant,
+}
+
+/// Result of looking up a key.
+#[derive(Debug)]
+enum Lookup {
+    /// The key is new, so the request must be processed.
+    New,
+    /// The request was already processed.
+    Done(CachedResponse),
+    /// The request is still being processed.
+    InProgress,
+    /// The key was used for a different request.
+    Mismatch,
+}
+
+/// Keeps the responses of the requests including an idempotency key.
+#[derive(Clone, Debug)]
+pub struct IdempotencyCache {
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL, DEFAULT_CAPACITY)
+    }
+}
+
+impl IdempotencyCache {
+    /// Creates a new cache.
+    ///
+    /// * `ttl`: how long the responses are kept.
+    /// * `capacity`: maximum number of responses. The oldest ones are removed when it is reached.
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            ttl,
+            capacity,
+        }
+    }
+
+    /// Looks up a key, reserving it if it is unknown.
+    fn lookup(&self, key: &str, fingerprint: u64) -> Lookup {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        entries.retain(|_, e| now.duration_since(e.created_at) < self.ttl);
+
+        if let Some(entry) = entries.get(key) {
+            if entry.fingerprint != fingerprint {
+                return Lookup::Mismatch;
+            }
+            return match &entry.response {
+                Some(response) => Lookup::Done(response.clone()),
+                None => Lookup::InProgress,
+            };
+        }
+
+        if entries.len() >= self.capacity {
+            let oldest = entries
+                .iter()
+                .filter(|(_, e)| e.response.is_some())
+                .min_by_key(|(_, e)| e.created_at)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(
+            key.to_string(),
+            Entry {
+                fingerprint,
+                response: None,
+                created_at: now,
+            },
+        );
+        Lookup::New
+    }
+
+    /// Stores the response for a reserved key.
+    fn store(&self, key: &str, response: CachedResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.get_mut(key) {
+            entry.response = Some(response);
+        }
+    }
+
+    /// Releases a reserved key, so the request can be retried.
+    fn release(&self, key: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.remove(key);
+    }
+}
+
+fn fingerprint(body: &Bytes) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Middleware to honor the `Idempotency-Key` header.
+pub async fn idempotency(
+    State(state): State<ServiceState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if ![Method::POST, Method::PUT, Method::PATCH].contains(request.method()) {
+        return next.run(request).await;
+    }
+
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY) else {
+        return next.run(request).await;
+    };
+
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_string(),
+        _ => {
+            return (StatusCode::BAD_REQUEST, "Invalid idempotency key").into_response();
+        }
+    };
+    let cache_key = format!("{} {} {}", request.method(), request.uri().path(), key);
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, BODY_LIMIT).await {
+        Ok(body) => body,
+        Err(error) => return (StatusCode::PAYLOAD_TOO_LARGE, error.to_string()).into_response(),
+    };
+
+    let cache = &state.idempotency;
+    match cache.lookup(&cache_key, fingerprint(&body)) {
+        Lookup::New => {}
+        Lookup::Done(response) => {
+            tracing::info!("Replaying the response for the idempotency key {}", key);
+            return response.into_response();
+        }
+        Lookup::InProgress => {
+            return (
+                StatusCode::CONFLICT,
+                "A request with the same idempotency key is in progress",
+            )
+                .into_response();
+        }
+        Lookup::Mismatch => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "The idempotency key was used for a different request",
+            )
+                .into_response();
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if response.status().is_server_error() {
+        cache.release(&cache_key);
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, BODY_LIMIT).await {
+        Ok(body) => body,
+        Err(error) => {
+            cache.release(&cache_key);
+            return (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response();
+        }
+    };
+
+    cache.store(
+        &cache_key,
+        CachedResponse {
+            status: parts.status,
+            content_type: parts.headers.get(header::CONTENT_TYPE).cloned(),
+            body: body.clone(),
+        },
+    );
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CachedResponse, IdempotencyCache, Lookup};
+    use axum::{body::Bytes, http::StatusCode};
+    use std::time::Duration;
+
+    fn response() -> CachedResponse {
+        CachedResponse {
+            status: StatusCode::OK,
+            content_type: None,
+            body: Bytes::from("{}"),
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let cache = IdempotencyCache::default();
+        assert!(matches!(cache.lookup("POST /probe 1", 10), Lookup::New));
+        assert!(matches!(
+            cache.lookup("POST /probe 1", 10),
+            Lookup::InProgress
+        ));
+        assert!(matches!(
+            cache.lookup("POST /probe 1", 20),
+            Lookup::Mismatch
+        ));
+
+        cache.store("POST /probe 1", response());
+        assert!(matches!(cache.lookup("POST /probe 1", 10), Lookup::Done(_)));
+
+        cache.release("POST /probe 1");
+        assert!(matches!(cache.lookup("POST /probe 1", 10), Lookup::New));
+    }
+
+    #[test]
+    fn test_capacity_and_ttl() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60), 1);
+        cache.lookup("POST /probe 1", 10);
+        cache.store("POST /probe 1", response());
+        cache.lookup("POST /probe 2", 10);
+        assert!(matches!(cache.lookup("POST /probe 1", 10), Lookup::New));
+
+        let cache = IdempotencyCache::new(Duration::ZERO, 10);
+        cache.lookup("POST /probe 1", 10);
+        cache.store("POST /probe 1", response());
+        assert!(matches!(cache.lookup("POST /probe 1", 10), Lookup::New));
+    }
+}
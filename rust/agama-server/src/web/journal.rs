@@ -0,0 +1,299 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Implements a journal of the events.
+//!
+//! The journal appends each event, including its sequence number and a timestamp, to a
+//! JSON-lines file. It allows to find out what happened during an unattended installation, even
+//! if no client was connected. The journal can be queried through the `/api/events/history`
+//! endpoint.
+
+use super::{
+    event::{EventsBuffer, EventsFilter, SequencedEvent},
+    state::ServiceState,
+};
+use crate::error::Error;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+use tokio::{
+    fs::OpenOptions,
+    io::AsyncWriteExt,
+    sync::broadcast::{error::RecvError, Receiver},
+};
+
+/// Journal entry.
+#[derive(Serialize)]
+struct JournalEntry<'a> {
+    timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    event: &'a SequencedEvent,
+}
+
+/// Journal of the events.
+#[derive(Clone, Debug)]
+pub struct EventsJournal {
+    path: PathBuf,
+}
+
+impl EventsJournal {
+    /// Creates a new journal.
+    ///
+    /// * `path`: path of the JSON-lines file.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Starts writing the events from the buffer to the journal.
+    ///
+    /// It spawns a task to write the events, so it must be called within a Tokio runtime.
+    ///
+    /// * `buffer`: events buffer.
+    pub fn start(&self, buffer: &EventsBuffer) {
+        let subscription = buffer.subscribe(None);
+        tokio::spawn(self.clone().run(subscription.receiver));
+    }
+
+    async fn run(self, mut events: Receiver<SequencedEvent>) {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await;
+        let mut file = match file {
+            Ok(file) => file,
+            Err(error) => {
+                tracing::error!(
+                    "Could not open the events journal {}: {}",
+                    self.path.display(),
+                    error
+                );
+                return;
+            }
+        };
+
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let entry = JournalEntry {
+                        timestamp: Utc::now(),
+                        event: &event,
+                    };
+                    let Ok(mut line) = serde_json::to_string(&entry) else {
+                        continue;
+                    };
+                    line.push('\n');
+                    if let Err(error) = file.write_all(line.as_bytes()).await {
+                        tracing::error!("Could not write to the events journal: {}", error);
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("The events journal skipped {} events", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+
+    /// Reads the entries matching the given criteria.
+    ///
+    /// The lines which cannot be parsed (e.g., a partially written line) are ignored.
+    ///
+    /// * `since`: only entries after the given point.
+    /// * `filter`: only entries matching the filter.
+    pub fn read(&self, since: Option<&Since>, filter: &EventsFilter) -> io::Result<Vec<Value>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(error),
+        };
+
+        let mut entries = vec![];
+        for line in BufReader::new(file).lines() {
+            let Ok(entry) = serde_json::from_str::<Value>(&line?) else {
+                continue;
+            };
+            if since.map_or(true, |s| s.precedes(&entry)) && filter.matches(&entry) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Point of the journal to start reading from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Since {
+    /// Sequence number of the last known event.
+    Seq(u64),
+    /// Timestamp.
+    Timestamp(DateTime<Utc>),
+}
+
+impl Since {
+    /// Parses a sequence number or an RFC 3339 timestamp.
+    ///
+    /// * `value`: value to parse.
+    pub fn parse(value: &str) -> Option<Self> {
+        if let Ok(seq) = value.parse::<u64>() {
+            return Some(Self::Seq(seq));
+        }
+
+        DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|t| Self::Timestamp(t.with_timezone(&Utc)))
+    }
+
+    /// Whether the entry comes after this point.
+    fn precedes(&self, entry: &Value) -> bool {
+        match self {
+            Self::Seq(seq) => entry["seq"].as_u64().is_some_and(|s| s > *seq),
+            Self::Timestamp(timestamp) => entry["timestamp"]
+                .as_str()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .is_some_and(|t| t > *timestamp),
+        }
+    }
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct HistoryParams {
+    /// Sequence number of the last known event or an RFC 3339 timestamp.
+    since: Option<String>,
+    /// Comma-separated list of event types.
+    #[serde(rename = "type")]
+    event_type: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/history",
+    context_path = "/api",
+    params(HistoryParams),
+    responses(
+        (status = 200, description = "Journal entries"),
+        (status = 400, description = "Invalid parameters"),
+        (status = 404, description = "The events journal is not enabled")
+    )
+)]
+pub async fn history(
+    State(state): State<ServiceState>,
+    Query(params): Query<HistoryParams>,
+) -> Result<Response, Error> {
+    let Some(journal) = state.journal else {
+        let body = json!({ "error": "The events journal is not enabled" });
+        return Ok((StatusCode::NOT_FOUND, Json(body)).into_response());
+    };
+
+    let since = match params.since.as_deref() {
+        Some(value) => Some(
+            Since::parse(value)
+                .ok_or_else(|| anyhow::anyhow!("Invalid 'since' value: {}", value))?,
+        ),
+        None => None,
+    };
+    let types = params
+        .event_type
+        .map(|t| t.split(',').map(str::to_string).collect())
+        .unwrap_or_default();
+    let filter = EventsFilter::new(types, vec![]);
+
+    let entries = tokio::task::spawn_blocking(move || journal.read(since.as_ref(), &filter))
+        .await
+        .map_err(anyhow::Error::new)?
+        .map_err(anyhow::Error::new)?;
+    Ok(Json(entries).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventsJournal, Since};
+    use crate::web::{
+        event::{EventsBuffer, EventsFilter},
+        Event,
+    };
+    use agama_lib::manager::InstallationPhase;
+    use chrono::{TimeZone, Utc};
+    use std::time::Duration;
+    use tokio::sync::broadcast;
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(Since::parse("42"), Some(Since::Seq(42)));
+        assert_eq!(
+            Since::parse("2025-01-30T10:00:00Z"),
+            Some(Since::Timestamp(
+                Utc.with_ymd_and_hms(2025, 1, 30, 10, 0, 0).unwrap()
+            ))
+        );
+        assert_eq!(Since::parse("yesterday"), None);
+    }
+
+    #[tokio::test]
+    async fn test_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let (tx, _rx) = broadcast::channel(16);
+        let buffer = EventsBuffer::new(&tx);
+        let journal = EventsJournal::new(&path);
+        journal.start(&buffer);
+        // give the tasks the chance to subscribe
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        tx.send(Event::InstallationPhaseChanged {
+            phase: InstallationPhase::Install,
+        })
+        .unwrap();
+        tx.send(Event::LocaleChanged {
+            locale: "es_ES".to_string(),
+        })
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let entries = journal.read(None, &EventsFilter::default()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["type"], "InstallationPhaseChanged");
+        assert!(entries[0]["timestamp"].is_string());
+
+        let since = Since::Seq(entries[0]["seq"].as_u64().unwrap());
+        let entries = journal
+            .read(Some(&since), &EventsFilter::default())
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["type"], "LocaleChanged");
+
+        let filter = EventsFilter::new(vec!["InstallationPhaseChanged".to_string()], vec![]);
+        let entries = journal.read(None, &filter).unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+}
@@ -0,0 +1,576 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Implements a metrics endpoint in the OpenMetrics (Prometheus) text format.
+//!
+//! The metrics are collected from different sources:
+//!
+//! * HTTP requests: a middleware records the number of requests and their latencies. The route
+//!   is normalized, replacing numeric and UUID segments with `:id`.
+//! * Event streams: the WebSocket and SSE handlers track the connected clients and the events
+//!   they lost because they lagged behind.
+//! * Events: the installation phase, the progress and the issues are updated from the events
+//!   channel.
+//! * D-Bus: each backend service is pinged on every scrape to measure the D-Bus latency.
+
+use super::{
+    event::EventsBuffer,
+    health::{self, BACKENDS},
+    state::ServiceState,
+    Event, EventsReceiver, EventsSender,
+};
+use agama_lib::manager::InstallationPhase;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::broadcast::error::RecvError;
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Upper bounds (in seconds) of the histogram buckets.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    requests: BTreeMap<(String, String, u16), u64>,
+    request_durations: BTreeMap<(String, String), Histogram>,
+    stream_clients: BTreeMap<&'static str, i64>,
+    events_lagged: BTreeMap<&'static str, u64>,
+    dbus_durations: BTreeMap<String, Histogram>,
+    dbus_errors: BTreeMap<String, u64>,
+    phase: Option<InstallationPhase>,
+    progress: BTreeMap<String, (u32, u32, bool)>,
+    issues: BTreeMap<(String, String), Vec<u32>>,
+}
+
+/// Transport used by an events stream client.
+#[derive(Clone, Copy, Debug)]
+pub enum Transport {
+    WebSocket,
+    Sse,
+}
+
+impl Transport {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::WebSocket => "websocket",
+            Self::Sse => "sse",
+        }
+    }
+}
+
+/// Decrements the number of clients of an events stream when dropped.
+pub struct StreamClientGuard {
+    metrics: Metrics,
+    transport: Transport,
+}
+
+impl Drop for StreamClientGuard {
+    fn drop(&mut self) {
+        let mut state = self.metrics.state.lock().unwrap();
+        *state
+            .stream_clients
+            .entry(self.transport.label())
+            .or_default() -= 1;
+    }
+}
+
+/// Collects the web server metrics.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    state: Arc<Mutex<MetricsState>>,
+}
+
+impl Metrics {
+    /// Creates a new metrics collector listening to the given events channel.
+    ///
+    /// It spawns a task to process the events, so it must be called within a Tokio runtime.
+    ///
+    /// * `events`: channel to listen for events.
+    pub fn new(events: &EventsSender) -> Self {
+        let metrics = Self::default();
+        tokio::spawn(metrics.clone().run(events.subscribe()));
+        metrics
+    }
+
+    /// Records an HTTP request.
+    ///
+    /// * `method`: HTTP method.
+    /// * `path`: requested path.
+    /// * `status`: response status code.
+    /// * `duration`: time to process the request.
+    pub fn observe_request(&self, method: &str, path: &str, status: u16, duration: Duration) {
+        let route = normalize_route(path);
+        let mut state = self.state.lock().unwrap();
+        *state
+            .requests
+            .entry((method.to_string(), route.clone(), status))
+            .or_default() += 1;
+        state
+            .request_durations
+            .entry((method.to_string(), route))
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Registers a new events stream client.
+    ///
+    /// The client is unregistered when the returned guard is dropped.
+    pub fn stream_client(&self, transport: Transport) -> StreamClientGuard {
+        let mut state = self.state.lock().unwrap();
+        *state.stream_clients.entry(transport.label()).or_default() += 1;
+        StreamClientGuard {
+            metrics: self.clone(),
+            transport,
+        }
+    }
+
+    /// Records the events an events stream client lost.
+    ///
+    /// * `transport`: client transport.
+    /// * `skipped`: number of lost events.
+    pub fn events_lagged(&self, transport: Transport, skipped: u64) {
+        let mut state = self.state.lock().unwrap();
+        *state.events_lagged.entry(transport.label()).or_default() += skipped;
+    }
+
+    fn observe_dbus(&self, service: &str, result: Option<Duration>) {
+        let mut state = self.state.lock().unwrap();
+        match result {
+            Some(duration) => state
+                .dbus_durations
+                .entry(service.to_string())
+                .or_default()
+                .observe(duration.as_secs_f64()),
+            None => *state.dbus_errors.entry(service.to_string()).or_default() += 1,
+        }
+    }
+
+    fn update(&self, event: &Event) {
+        let mut state = self.state.lock().unwrap();
+        match event {
+            Event::InstallationPhaseChanged { phase } => state.phase = Some(*phase),
+            Event::Progress { service, progress } => {
+                state.progress.insert(
+                    service.clone(),
+                    (progress.current_step, progress.max_steps, progress.finished),
+                );
+            }
+            Event::IssuesChanged {
+                service,
+                path,
+                issues,
+            } => {
+                let severities = issues.iter().map(|i| i.severity()).collect();
+                state
+                    .issues
+                    .insert((service.clone(), path.clone()), severities);
+            }
+            _ => {}
+        }
+    }
+
+    async fn run(self, mut events: EventsReceiver) {
+        loop {
+            match events.recv().await {
+                Ok(event) => self.update(&event),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("The metrics collector skipped {} events", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+
+    /// Pings the backend services in Agama's bus and records the latencies.
+    ///
+    /// * `dbus`: D-Bus connection.
+    async fn ping_dbus_services(&self, dbus: &zbus::Connection) {
+        let backends = BACKENDS.iter().filter(|b| !b.system_bus);
+        let pings = backends.map(|backend| async move {
+            let start = Instant::now();
+            let duration = health::ping(dbus, backend.destination)
+                .await
+                .ok()
+                .map(|_| start.elapsed());
+            (backend.name, duration)
+        });
+
+        for (service, duration) in futures_util::future::join_all(pings).await {
+            self.observe_dbus(service, duration);
+        }
+    }
+
+    /// Renders the metrics in the OpenMetrics text format.
+    ///
+    /// * `events`: events channel.
+    /// * `buffer`: events buffer.
+    fn render(&self, events: &EventsSender, buffer: &EventsBuffer) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+
+        header(&mut out, "agama_http_requests", "counter", "HTTP requests");
+        for ((method, route, status), count) in &state.requests {
+            let _ = writeln!(
+                out,
+                "agama_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                method, route, status, count
+            );
+        }
+
+        header(
+            &mut out,
+            "agama_http_request_duration_seconds",
+            "histogram",
+            "HTTP requests latency",
+        );
+        for ((method, route), histogram) in &state.request_durations {
+            let labels = format!("method=\"{}\",route=\"{}\"", method, route);
+            render_histogram(
+                &mut out,
+                "agama_http_request_duration_seconds",
+                &labels,
+                histogram,
+            );
+        }
+
+        header(
+            &mut out,
+            "agama_event_stream_clients",
+            "gauge",
+            "Connected events stream clients",
+        );
+        for transport in [Transport::WebSocket, Transport::Sse] {
+            let clients = state.stream_clients.get(transport.label()).unwrap_or(&0);
+            let _ = writeln!(
+                out,
+                "agama_event_stream_clients{{transport=\"{}\"}} {}",
+                transport.label(),
+                clients
+            );
+        }
+
+        header(
+            &mut out,
+            "agama_events_queued",
+            "gauge",
+            "Events waiting in the broadcast channel",
+        );
+        let _ = writeln!(out, "agama_events_queued {}", events.len());
+
+        header(
+            &mut out,
+            "agama_events_lagged",
+            "counter",
+            "Events lost by slow consumers",
+        );
+        let _ = writeln!(
+            out,
+            "agama_events_lagged_total{{consumer=\"buffer\"}} {}",
+            buffer.lagged()
+        );
+        for transport in [Transport::WebSocket, Transport::Sse] {
+            let lagged = state.events_lagged.get(transport.label()).unwrap_or(&0);
+            let _ = writeln!(
+                out,
+                "agama_events_lagged_total{{consumer=\"{}\"}} {}",
+                transport.label(),
+                lagged
+            );
+        }
+
+        header(
+            &mut out,
+            "agama_dbus_call_duration_seconds",
+            "histogram",
+            "D-Bus ping latency",
+        );
+        for (service, histogram) in &state.dbus_durations {
+            let labels = format!("service=\"{}\"", service);
+            render_histogram(
+                &mut out,
+                "agama_dbus_call_duration_seconds",
+                &labels,
+                histogram,
+            );
+        }
+
+        header(
+            &mut out,
+            "agama_dbus_call_errors",
+            "counter",
+            "Failed D-Bus pings",
+        );
+        for (service, count) in &state.dbus_errors {
+            let _ = writeln!(
+                out,
+                "agama_dbus_call_errors_total{{service=\"{}\"}} {}",
+                service, count
+            );
+        }
+
+        header(
+            &mut out,
+            "agama_installation_phase",
+            "stateset",
+            "Installation phase",
+        );
+        if let Some(current) = state.phase {
+            for (phase, name) in [
+                (InstallationPhase::Startup, "startup"),
+                (InstallationPhase::Config, "config"),
+                (InstallationPhase::Install, "install"),
+            ] {
+                let _ = writeln!(
+                    out,
+                    "agama_installation_phase{{agama_installation_phase=\"{}\"}} {}",
+                    name,
+                    u8::from(phase == current)
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "agama_progress_step",
+            "gauge",
+            "Current progress step",
+        );
+        for (service, (step, _, _)) in &state.progress {
+            let _ = writeln!(
+                out,
+                "agama_progress_step{{service=\"{}\"}} {}",
+                service, step
+            );
+        }
+        header(
+            &mut out,
+            "agama_progress_steps",
+            "gauge",
+            "Number of progress steps",
+        );
+        for (service, (_, steps, _)) in &state.progress {
+            let _ = writeln!(
+                out,
+                "agama_progress_steps{{service=\"{}\"}} {}",
+                service, steps
+            );
+        }
+        header(
+            &mut out,
+            "agama_progress_finished",
+            "gauge",
+            "Whether the progress is finished",
+        );
+        for (service, (_, _, finished)) in &state.progress {
+            let _ = writeln!(
+                out,
+                "agama_progress_finished{{service=\"{}\"}} {}",
+                service,
+                u8::from(*finished)
+            );
+        }
+
+        header(&mut out, "agama_issues", "gauge", "Issues by severity");
+        for ((service, path), severities) in &state.issues {
+            for (severity, name) in [(0, "warn"), (1, "error")] {
+                let count = severities.iter().filter(|s| **s == severity).count();
+                let _ = writeln!(
+                    out,
+                    "agama_issues{{service=\"{}\",path=\"{}\",severity=\"{}\"}} {}",
+                    service, path, name, count
+                );
+            }
+        }
+
+        out.push_str("# EOF\n");
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "# HELP {} {}.", name, help);
+}
+
+fn render_histogram(out: &mut String, name: &str, labels: &str, histogram: &Histogram) {
+    for (count, bound) in histogram.buckets.iter().zip(BUCKETS) {
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{},le=\"{}\"}} {}",
+            name, labels, bound, count
+        );
+    }
+    let _ = writeln!(
+        out,
+        "{}_bucket{{{},le=\"+Inf\"}} {}",
+        name, labels, histogram.count
+    );
+    let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, histogram.sum);
+    let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, histogram.count);
+}
+
+/// Replaces the numeric and UUID segments of a path with `:id` to keep the number of routes
+/// bounded. Paths outside the API are reported as `/` (static files).
+fn normalize_route(path: &str) -> String {
+    if !path.starts_with("/api/") {
+        return "/".to_string();
+    }
+
+    path.split('/')
+        .map(|segment| {
+            let is_id = !segment.is_empty()
+                && (segment.chars().all(|c| c.is_ascii_digit())
+                    || uuid::Uuid::parse_str(segment).is_ok());
+            if is_id {
+                ":id"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Middleware to record the HTTP requests.
+pub async fn track_requests(
+    State(state): State<ServiceState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+    state
+        .metrics
+        .observe_request(&method, &path, response.status().as_u16(), start.elapsed());
+    response
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    context_path = "/api",
+    responses(
+        (status = 200, description = "Metrics in the OpenMetrics text format", body = String)
+    )
+)]
+pub async fn metrics(State(state): State<ServiceState>) -> impl IntoResponse {
+    if let Some(dbus) = &state.dbus {
+        state.metrics.ping_dbus_services(dbus).await;
+    }
+
+    let body = state.metrics.render(&state.events, &state.events_buffer);
+    Response::builder()
+        .header(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE))
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_route, Histogram, Metrics};
+    use crate::web::{event::EventsBuffer, Event};
+    use agama_lib::manager::InstallationPhase;
+    use std::time::Duration;
+    use tokio::sync::broadcast;
+
+    #[test]
+    fn test_normalize_route() {
+        assert_eq!(
+            normalize_route("/api/questions/12/answer"),
+            "/api/questions/:id/answer"
+        );
+        assert_eq!(
+            normalize_route("/api/network/connections/a7fa6c7e-24a3-4b8e-9d1f-8e6e2e5f0e1a"),
+            "/api/network/connections/:id"
+        );
+        assert_eq!(
+            normalize_route("/api/storage/devices/system"),
+            "/api/storage/devices/system"
+        );
+        assert_eq!(normalize_route("/assets/index.js"), "/");
+    }
+
+    #[test]
+    fn test_histogram() {
+        let mut histogram = Histogram::default();
+        histogram.observe(0.02);
+        histogram.observe(3.0);
+        assert_eq!(histogram.count, 2);
+        assert_eq!(histogram.buckets[0], 0);
+        assert_eq!(histogram.buckets[2], 1);
+        assert_eq!(histogram.buckets[10], 2);
+    }
+
+    #[tokio::test]
+    async fn test_render() {
+        let (tx, _rx) = broadcast::channel(16);
+        let buffer = EventsBuffer::new(&tx);
+        let metrics = Metrics::default();
+
+        metrics.observe_request("GET", "/api/ping", 200, Duration::from_millis(3));
+        metrics.update(&Event::InstallationPhaseChanged {
+            phase: InstallationPhase::Install,
+        });
+        let _client = metrics.stream_client(super::Transport::WebSocket);
+
+        let output = metrics.render(&tx, &buffer);
+        assert!(output.contains(
+            "agama_http_requests_total{method=\"GET\",route=\"/api/ping\",status=\"200\"} 1"
+        ));
+        assert!(output.contains("agama_installation_phase{agama_installation_phase=\"install\"} 1"));
+        assert!(output.contains("agama_event_stream_clients{transport=\"websocket\"} 1"));
+        assert!(output.ends_with("# EOF\n"));
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Implements the support for running behind a reverse proxy.
+//!
+//! When a request comes from a trusted proxy (see [ServiceConfig::trusted_proxies]), the
+//! `X-Forwarded-Proto`, `X-Forwarded-Host` and `X-Forwarded-For` headers are used to find out
+//! the scheme, the host and the address used by the client. Otherwise, those headers are ignored.
+//! The result is added to the request as a [ClientInfo] extension.
+
+use super::{config::ServiceConfig, state::ServiceState};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use std::net::{IpAddr, SocketAddr};
+
+const X_FORWARDED_PROTO: &str = "X-Forwarded-Proto";
+const X_FORWARDED_HOST: &str = "X-Forwarded-Host";
+const X_FORWARDED_FOR: &str = "X-Forwarded-For";
+
+/// Address of the peer of a TCP connection.
+///
+/// It is added to the requests by the server.
+#[derive(Clone, Copy, Debug)]
+pub struct PeerAddress {
+    pub addr: SocketAddr,
+    /// Whether the connection uses TLS.
+    pub tls: bool,
+}
+
+/// Information about the client which sent a request.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClientInfo {
+    /// Address of the client. It is unknown for the Unix socket.
+    pub addr: Option<IpAddr>,
+    /// Scheme used by the client (`http` or `https`). It is unknown for the Unix socket.
+    pub scheme: Option<String>,
+    /// Host requested by the client.
+    pub host: Option<String>,
+}
+
+impl ClientInfo {
+    /// Builds the client information for a request.
+    ///
+    /// * `headers`: request headers.
+    /// * `peer`: peer address, if the request came through a TCP connection.
+    /// * `config`: service configuration, which contains the trusted proxies.
+    pub fn new(headers: &HeaderMap, peer: Option<&PeerAddress>, config: &ServiceConfig) -> Self {
+        let mut info = ClientInfo {
+            addr: peer.map(|p| p.addr.ip().to_canonical()),
+            scheme: peer.map(|p| if p.tls { "https" } else { "http" }.to_string()),
+            host: header_value(headers, header::HOST.as_str()),
+        };
+
+        let Some(peer) = peer else {
+            return info;
+        };
+
+        if !config.is_trusted_proxy(&peer.addr.ip()) {
+            return info;
+        }
+
+        if let Some(scheme) = first_value(headers, X_FORWARDED_PROTO) {
+            info.scheme = Some(scheme.to_lowercase());
+        }
+        if let Some(host) = first_value(headers, X_FORWARDED_HOST) {
+            info.host = Some(host);
+        }
+        if let Some(addr) = forwarded_for(headers, config) {
+            info.addr = Some(addr);
+        }
+        info
+    }
+
+    /// Whether the client uses HTTPS.
+    pub fn is_https(&self) -> bool {
+        self.scheme.as_deref() == Some("https")
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Returns the first value of a comma separated header (the one set by the first proxy).
+fn first_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    header_value(headers, name)?
+        .split(',')
+        .map(str::trim)
+        .find(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Returns the client address from the `X-Forwarded-For` header.
+///
+/// The addresses are checked from right to left, skipping the trusted proxies, so the client
+/// cannot fake its address by sending its own header.
+fn forwarded_for(headers: &HeaderMap, config: &ServiceConfig) -> Option<IpAddr> {
+    let mut client = None;
+    for value in headers.get_all(X_FORWARDED_FOR).iter().rev() {
+        let Ok(value) = value.to_str() else {
+            return client;
+        };
+
+        for addr in value.rsplit(',') {
+            let Ok(addr) = addr.trim().parse::<IpAddr>() else {
+                return client;
+            };
+            client = Some(addr.to_canonical());
+            if !config.is_trusted_proxy(&addr) {
+                return client;
+            }
+        }
+    }
+    client
+}
+
+/// Middleware to add the [ClientInfo] to the requests.
+pub async fn client_info(
+    State(state): State<ServiceState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request.extensions().get::<PeerAddress>();
+    let info = ClientInfo::new(request.headers(), peer, &state.config);
+    request.extensions_mut().insert(info);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientInfo, PeerAddress};
+    use crate::web::ServiceConfig;
+    use axum::http::HeaderMap;
+
+    fn config() -> ServiceConfig {
+        ServiceConfig {
+            trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+            ..Default::default()
+        }
+    }
+
+    fn headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("Host", "10.0.0.2".parse().unwrap());
+        headers.insert("X-Forwarded-Proto", "https".parse().unwrap());
+        headers.insert("X-Forwarded-Host", "agama.example.net".parse().unwrap());
+        headers.insert(
+            "X-Forwarded-For",
+            "1.1.1.1, 192.168.1.10, 10.0.0.3".parse().unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_trusted_proxy() {
+        let peer = PeerAddress {
+            addr: "10.0.0.1:80".parse().unwrap(),
+            tls: false,
+        };
+        let info = ClientInfo::new(&headers(), Some(&peer), &config());
+        assert_eq!(info.addr, Some("192.168.1.10".parse().unwrap()));
+        assert_eq!(info.host, Some("agama.example.net".to_string()));
+        assert!(info.is_https());
+    }
+
+    #[test]
+    fn test_untrusted_proxy() {
+        let peer = PeerAddress {
+            addr: "192.168.1.1:80".parse().unwrap(),
+            tls: false,
+        };
+        let info = ClientInfo::new(&headers(), Some(&peer), &config());
+        assert_eq!(info.addr, Some("192.168.1.1".parse().unwrap()));
+        assert_eq!(info.host, Some("10.0.0.2".to_string()));
+        assert!(!info.is_https());
+    }
+
+    #[test]
+    fn test_unix_socket() {
+        let info = ClientInfo::new(&headers(), None, &config());
+        assert_eq!(info.addr, None);
+        assert_eq!(info.scheme, None);
+    }
+}
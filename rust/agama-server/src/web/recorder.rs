@@ -0,0 +1,144 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Implements a middleware to record the HTTP API traffic.
+//!
+//! The requests and responses are appended to a JSON-lines file using the format defined in
+//! [agama_lib::recording], so they can be replayed in the tests of the HTTP clients. Only JSON
+//! bodies are recorded and the authentication endpoint is skipped, so the file does not contain
+//! any password or token.
+
+use super::state::ServiceState;
+use agama_lib::recording::{Exchange, Recording};
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Maximum size of a recorded body.
+const BODY_LIMIT: usize = 10 * 1024 * 1024;
+
+/// Paths which are not recorded.
+const SKIPPED_PATHS: [&str; 1] = ["/api/auth"];
+
+/// Writes the recorded exchanges to a file.
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    path: PathBuf,
+    // serializes the writes to the file
+    lock: Arc<Mutex<()>>,
+}
+
+impl Recorder {
+    /// Creates a new recorder.
+    ///
+    /// * `path`: path of the JSON-lines file.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    async fn write(&self, exchange: Exchange) {
+        let recorder = self.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let _guard = recorder.lock.lock().unwrap();
+            Recording::append(&recorder.path, &exchange)
+        })
+        .await;
+
+        if let Ok(Err(error)) = result {
+            tracing::error!("Could not record the request: {}", error);
+        }
+    }
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("json"))
+}
+
+/// Reads the body if it contains JSON.
+async fn read_json_body(
+    headers: &HeaderMap,
+    body: Body,
+) -> Result<(Body, Option<Bytes>), Response> {
+    if !is_json(headers) {
+        return Ok((body, None));
+    }
+
+    match to_bytes(body, BODY_LIMIT).await {
+        Ok(bytes) => Ok((Body::from(bytes.clone()), Some(bytes))),
+        Err(error) => {
+            Err((axum::http::StatusCode::PAYLOAD_TOO_LARGE, error.to_string()).into_response())
+        }
+    }
+}
+
+/// Middleware to record the HTTP API traffic.
+pub async fn record(State(state): State<ServiceState>, request: Request, next: Next) -> Response {
+    let Some(recorder) = state.recorder else {
+        return next.run(request).await;
+    };
+
+    let path = request.uri().path().to_string();
+    if !path.starts_with("/api/") || SKIPPED_PATHS.contains(&path.as_str()) {
+        return next.run(request).await;
+    }
+
+    let method = request.method().to_string();
+    let query = request.uri().query().map(str::to_string);
+    let (parts, body) = request.into_parts();
+    let (body, request_body) = match read_json_body(&parts.headers, body).await {
+        Ok(result) => result,
+        Err(response) => return response,
+    };
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let status = response.status().as_u16();
+    let (parts, body) = response.into_parts();
+    let (body, response_body) = match read_json_body(&parts.headers, body).await {
+        Ok(result) => result,
+        Err(response) => return response,
+    };
+
+    let exchange = Exchange {
+        method,
+        path,
+        query,
+        request: request_body.and_then(|b| Exchange::body_to_value(&b)),
+        status,
+        response: response_body.and_then(|b| Exchange::body_to_value(&b)),
+    };
+    recorder.write(exchange).await;
+
+    Response::from_parts(parts, body)
+}
@@ -18,10 +18,22 @@
 // To contact SUSE LLC about this file by physical or electronic mail, you may
 // find current contact information at www.suse.com.
 
-use super::http::{login, login_from_query, logout, session};
-use super::{config::ServiceConfig, state::ServiceState, EventsSender};
+use super::http::{certificate, login, login_from_query, logout, session};
+use super::{
+    config::ServiceConfig,
+    event::EventsBuffer,
+    health::health,
+    idempotency::{self, IdempotencyCache},
+    journal::{self, EventsJournal},
+    metrics::{self, Metrics},
+    proxy::{self, ClientInfo},
+    recorder::{self, Recorder},
+    state::ServiceState,
+    EventsSender,
+};
+use crate::cert::CertificateStore;
 use agama_lib::auth::TokenClaims;
-use axum::http::HeaderValue;
+use axum::http::{HeaderName, HeaderValue};
 use axum::{
     body::Body,
     extract::Request,
@@ -37,24 +49,45 @@ use std::{
     path::{Path, PathBuf},
 };
 use tower::Service;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::set_header::SetResponseHeaderLayer;
 use tower_http::{compression::CompressionLayer, services::ServeDir, trace::TraceLayer};
 use tracing::Span;
 
+const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
 /// Builder for Agama main service.
 ///
 /// It is responsible for building an axum service which includes:
 ///
 /// * A static assets directory (`public_dir`).
-/// * A websocket at the `/ws` path.
+/// * A websocket at the `/ws` path (see [super::ws]).
+/// * A Server-Sent Events stream at the `/events` path (see [super::sse]).
+/// * The events journal at the `/events/history` path (see [super::journal]).
 /// * An authentication endpoint at `/auth`.
 /// * A 'ping' endpoint at '/ping'.
-/// * A number of authenticated services that are added using the `add_service` function.
+/// * A 'health' endpoint at '/health' reporting the status of the backend services.
+/// * A 'certificate' endpoint at '/certificate' exposing the certificate fingerprint.
+/// * A 'metrics' endpoint at '/metrics' in the OpenMetrics format (see [super::metrics]).
+/// * A number of authenticated services that are added using the `add_service` function. They
+///   honor the `Idempotency-Key` header (see [super::idempotency]).
+///
+/// When running behind a reverse proxy, the whole service can be mounted under a URL prefix and
+/// cross-origin requests can be allowed (see [ServiceConfig]).
+///
+/// Each request gets an `X-Request-Id` (unless the client already sent one), which is included in
+/// the response and in the tracing span of the request, so it is attached to any log (e.g., D-Bus
+/// calls) written while handling it.
 pub struct MainServiceBuilder {
     config: ServiceConfig,
     events: EventsSender,
     api_router: Router<ServiceState>,
     public_dir: PathBuf,
+    certificate: Option<CertificateStore>,
+    dbus: Option<zbus::Connection>,
+    journal: Option<EventsJournal>,
+    recorder: Option<Recorder>,
 }
 
 impl MainServiceBuilder {
@@ -66,7 +99,11 @@ impl MainServiceBuilder {
     where
         P: AsRef<Path>,
     {
-        let api_router = Router::new().route("/ws", get(super::ws::ws_handler));
+        let api_router = Router::new()
+            .route("/ws", get(super::ws::ws_handler))
+            .route("/events", get(super::sse::sse_handler))
+            .route("/events/history", get(journal::history))
+            .route("/metrics", get(metrics::metrics));
         let config = ServiceConfig::default();
 
         Self {
@@ -74,6 +111,10 @@ impl MainServiceBuilder {
             api_router,
             config,
             public_dir: PathBuf::from(public_dir.as_ref()),
+            certificate: None,
+            dbus: None,
+            journal: None,
+            recorder: None,
         }
     }
 
@@ -81,6 +122,38 @@ impl MainServiceBuilder {
         Self { config, ..self }
     }
 
+    /// Sets the certificate used by the server to expose it through the API.
+    pub fn with_certificate(self, certificate: CertificateStore) -> Self {
+        Self {
+            certificate: Some(certificate),
+            ..self
+        }
+    }
+
+    /// Sets the D-Bus connection used to check the backend services.
+    pub fn with_dbus(self, dbus: zbus::Connection) -> Self {
+        Self {
+            dbus: Some(dbus),
+            ..self
+        }
+    }
+
+    /// Sets the journal to write the events to.
+    pub fn with_journal(self, journal: EventsJournal) -> Self {
+        Self {
+            journal: Some(journal),
+            ..self
+        }
+    }
+
+    /// Sets the recorder to write the HTTP API traffic to.
+    pub fn with_recorder(self, recorder: Recorder) -> Self {
+        Self {
+            recorder: Some(recorder),
+            ..self
+        }
+    }
+
     /// Add an authenticated service.
     ///
     /// * `path`: Path to mount the service under `/api`.
@@ -98,29 +171,68 @@ impl MainServiceBuilder {
     }
 
     pub fn build(self) -> Router {
+        let events_buffer = EventsBuffer::new(&self.events);
+        if let Some(journal) = &self.journal {
+            journal.start(&events_buffer);
+        }
+
         let state = ServiceState {
             config: self.config,
+            events_buffer,
+            metrics: Metrics::new(&self.events),
             events: self.events,
             public_dir: self.public_dir.clone(),
+            certificate: self.certificate,
+            dbus: self.dbus,
+            journal: self.journal,
+            recorder: self.recorder,
+            idempotency: IdempotencyCache::default(),
         };
 
         let api_router = self
             .api_router
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                idempotency::idempotency,
+            ))
             .route_layer(middleware::from_extractor_with_state::<TokenClaims, _>(
                 state.clone(),
             ))
             .route("/ping", get(super::http::ping))
+            .route("/health", get(health))
+            .route("/certificate", get(certificate))
             .route("/auth", post(login).get(session).delete(logout));
 
         tracing::info!("Serving static files from {}", self.public_dir.display());
         let serve = ServeDir::new(self.public_dir).precompressed_gzip();
 
-        Router::new()
+        let prefix = state.config.url_prefix();
+        let cors = cors_layer(&state.config);
+
+        let router = Router::new()
             .nest_service("/", serve)
             .route("/login", get(login_from_query))
             .nest("/api", api_router)
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                recorder::record,
+            ))
             .layer(
                 TraceLayer::new_for_http()
+                    .make_span_with(|request: &Request<Body>| {
+                        let request_id = request
+                            .headers()
+                            .get(X_REQUEST_ID)
+                            .and_then(|v| v.to_str().ok())
+                            .unwrap_or_default();
+                        let client = request
+                            .extensions()
+                            .get::<ClientInfo>()
+                            .and_then(|c| c.addr)
+                            .map(|a| a.to_string())
+                            .unwrap_or_default();
+                        tracing::info_span!("request", id = request_id, client = client)
+                    })
                     .on_request(|request: &Request<Body>, _span: &Span| {
                         tracing::info!("request: {} {}", request.method(), request.uri().path())
                     })
@@ -135,6 +247,60 @@ impl MainServiceBuilder {
                 CACHE_CONTROL,
                 HeaderValue::from_static("no-store"),
             ))
-            .with_state(state)
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                metrics::track_requests,
+            ))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                proxy::client_info,
+            ))
+            .layer(PropagateRequestIdLayer::new(X_REQUEST_ID))
+            .layer(SetRequestIdLayer::new(X_REQUEST_ID, MakeRequestUuid))
+            .with_state(state);
+
+        let router = match cors {
+            Some(cors) => router.layer(cors),
+            None => router,
+        };
+
+        if prefix.is_empty() {
+            router
+        } else {
+            tracing::info!("Serving the web UI and the API under {}", prefix);
+            Router::new().nest(&prefix, router)
+        }
     }
 }
+
+/// Builds the CORS layer according to the allowed origins.
+///
+/// * `config`: service configuration.
+fn cors_layer(config: &ServiceConfig) -> Option<CorsLayer> {
+    if config.allowed_origins.is_empty() {
+        return None;
+    }
+
+    let cors = CorsLayer::new().expose_headers([X_REQUEST_ID]);
+    if config.allowed_origins.iter().any(|o| o == "*") {
+        return Some(cors.allow_origin(Any).allow_methods(Any).allow_headers(Any));
+    }
+
+    let origins: Vec<HeaderValue> = config
+        .allowed_origins
+        .iter()
+        .filter_map(|o| match HeaderValue::from_str(o) {
+            Ok(origin) => Some(origin),
+            Err(_) => {
+                tracing::warn!("Ignoring invalid origin: {}", o);
+                None
+            }
+        })
+        .collect();
+    Some(
+        cors.allow_origin(AllowOrigin::list(origins))
+            .allow_methods(AllowMethods::mirror_request())
+            .allow_headers(AllowHeaders::mirror_request())
+            .allow_credentials(true),
+    )
+}
@@ -0,0 +1,176 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Implements a Server-Sent Events (SSE) endpoint as an alternative to the WebSocket.
+//!
+//! It delivers the same events than the WebSocket (see [super::ws]):
+//!
+//! * The `event` field contains the event type (e.g., `Progress`).
+//! * The `id` field contains the sequence number. Clients can resume the stream by sending the
+//!   `Last-Event-ID` header (browsers do it automatically when reconnecting).
+//! * The `data` field contains the event in JSON format.
+//!
+//! The `events` and `services` query parameters (comma separated lists) restrict the events
+//! the client receives.
+
+use super::{
+    event::{ControlMessage, EventsFilter, SequencedEvent},
+    metrics::{Metrics, StreamClientGuard, Transport},
+    state::ServiceState,
+};
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event as SseEvent, KeepAlive, Sse},
+};
+use futures_util::{stream, Stream};
+use serde::Deserialize;
+use std::{collections::VecDeque, convert::Infallible};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SseParams {
+    /// Comma separated list of event types.
+    events: Option<String>,
+    /// Comma separated list of services.
+    services: Option<String>,
+}
+
+impl SseParams {
+    fn filter(&self) -> EventsFilter {
+        EventsFilter::new(split_list(&self.events), split_list(&self.services))
+    }
+}
+
+fn split_list(list: &Option<String>) -> Vec<String> {
+    list.as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+struct SseStreamState {
+    pending: VecDeque<SseEvent>,
+    receiver: Receiver<SequencedEvent>,
+    filter: EventsFilter,
+    metrics: Metrics,
+    // unregisters the client when the stream is dropped
+    _client: StreamClientGuard,
+}
+
+pub async fn sse_handler(
+    State(state): State<ServiceState>,
+    Query(params): Query<SseParams>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    let last_seq = headers
+        .get(LAST_EVENT_ID)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    let subscription = state.events_buffer.subscribe(last_seq);
+    let filter = params.filter();
+
+    let mut pending = VecDeque::new();
+    if subscription.lagged {
+        pending.push_back(lagged_event(subscription.next_seq));
+    }
+    pending.extend(
+        subscription
+            .missed
+            .iter()
+            .filter_map(|e| to_sse_event(&filter, e)),
+    );
+
+    let state = SseStreamState {
+        pending,
+        receiver: subscription.receiver,
+        filter,
+        metrics: state.metrics.clone(),
+        _client: state.metrics.stream_client(Transport::Sse),
+    };
+    let stream = stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                return Some((Ok(event), state));
+            }
+
+            match state.receiver.recv().await {
+                Ok(event) => {
+                    if let Some(event) = to_sse_event(&state.filter, &event) {
+                        return Some((Ok(event), state));
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("SSE client lagged, {} events skipped", skipped);
+                    state.metrics.events_lagged(Transport::Sse, skipped);
+                    // the next received event is the oldest one in the channel
+                    let Ok(event) = state.receiver.recv().await else {
+                        return None;
+                    };
+                    state.pending.push_back(lagged_event(event.seq));
+                    state.pending.extend(to_sse_event(&state.filter, &event));
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Converts an event into an SSE event, returning `None` if it does not match the filter.
+fn to_sse_event(filter: &EventsFilter, event: &SequencedEvent) -> Option<SseEvent> {
+    let json = serde_json::to_value(event).ok()?;
+    if !filter.matches(&json) {
+        return None;
+    }
+
+    let event_type = json["type"].as_str().unwrap_or_default().to_string();
+    Some(
+        SseEvent::default()
+            .id(event.seq.to_string())
+            .event(event_type)
+            .data(json.to_string()),
+    )
+}
+
+fn lagged_event(seq: u64) -> SseEvent {
+    let message = ControlMessage::Lagged { seq };
+    SseEvent::default()
+        .event("Lagged")
+        .data(serde_json::to_string(&message).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_list;
+
+    #[test]
+    fn test_split_list() {
+        let list = Some("Progress, IssuesChanged,,".to_string());
+        assert_eq!(split_list(&list), vec!["Progress", "IssuesChanged"]);
+        assert!(split_list(&None).is_empty());
+    }
+}
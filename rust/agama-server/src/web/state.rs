@@ -20,7 +20,11 @@
 
 //! Implements the web service state.
 
-use super::{config::ServiceConfig, EventsSender};
+use super::{
+    config::ServiceConfig, event::EventsBuffer, idempotency::IdempotencyCache,
+    journal::EventsJournal, metrics::Metrics, recorder::Recorder, EventsSender,
+};
+use crate::cert::CertificateStore;
 use std::path::PathBuf;
 
 /// Web service state.
@@ -30,5 +34,12 @@ use std::path::PathBuf;
 pub struct ServiceState {
     pub config: ServiceConfig,
     pub events: EventsSender,
+    pub events_buffer: EventsBuffer,
     pub public_dir: PathBuf,
+    pub certificate: Option<CertificateStore>,
+    pub metrics: Metrics,
+    pub dbus: Option<zbus::Connection>,
+    pub journal: Option<EventsJournal>,
+    pub recorder: Option<Recorder>,
+    pub idempotency: IdempotencyCache,
 }
@@ -18,29 +18,146 @@
 // To contact SUSE LLC about this file by physical or electronic mail, you may
 // find current contact information at www.suse.com.
 
-//! Implements the websocket handling.
+//! Implements the WebSocket to deliver the events to the clients.
+//!
+//! Each event includes a sequence number (`seq`). The client can:
+//!
+//! * Resume the stream after reconnecting by passing the last seen sequence number in the
+//!   `since` query parameter (e.g., `/api/ws?since=42`).
+//! * Restrict the events it receives by sending a subscription message with the event types
+//!   and/or the services it is interested in:
+//!   `{ "type": "Subscribe", "events": ["Progress"], "services": ["storage"] }`. Omitting a
+//!   list (or sending an empty one) means "all".
+//!
+//! If the client cannot keep up with the events or the events it asked for are not available
+//! anymore, the server sends a `{ "type": "Lagged", "seq": N }` message. The client should
+//! reload the state it is interested in. The stream continues from the `N` event on.
 
-use super::{state::ServiceState, EventsSender};
+use super::{
+    event::{ControlMessage, EventsFilter, SequencedEvent, Subscription},
+    metrics::{Metrics, Transport},
+    state::ServiceState,
+};
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        State, WebSocketUpgrade,
+        Query, State, WebSocketUpgrade,
     },
     response::IntoResponse,
 };
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct WebSocketParams {
+    /// Sequence number of the last event seen by the client.
+    since: Option<u64>,
+}
+
+/// Messages sent by the client.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum ClientMessage {
+    /// Sets the events the client is interested in.
+    Subscribe {
+        #[serde(default)]
+        events: Vec<String>,
+        #[serde(default)]
+        services: Vec<String>,
+    },
+}
 
 pub async fn ws_handler(
     State(state): State<ServiceState>,
+    Query(params): Query<WebSocketParams>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state.events))
+    let subscription = state.events_buffer.subscribe(params.since);
+    ws.on_upgrade(move |socket| handle_socket(socket, subscription, state.metrics))
 }
 
-async fn handle_socket(mut socket: WebSocket, events: EventsSender) {
-    let mut rx = events.subscribe();
-    while let Ok(msg) = rx.recv().await {
-        if let Ok(json) = serde_json::to_string(&msg) {
-            _ = socket.send(Message::Text(json)).await;
+async fn handle_socket(mut socket: WebSocket, subscription: Subscription, metrics: Metrics) {
+    let _client = metrics.stream_client(Transport::WebSocket);
+    let mut filter = EventsFilter::default();
+
+    if subscription.lagged {
+        let lagged = ControlMessage::Lagged {
+            seq: subscription.next_seq,
+        };
+        if send_json(&mut socket, &lagged).await.is_err() {
+            return;
+        }
+    }
+
+    for event in subscription.missed {
+        if send_event(&mut socket, &filter, &event).await.is_err() {
+            return;
         }
     }
+
+    let mut rx = subscription.receiver;
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                match message {
+                    Some(Ok(Message::Text(text))) => {
+                        match serde_json::from_str(&text) {
+                            Ok(ClientMessage::Subscribe { events, services }) => {
+                                filter = EventsFilter::new(events, services);
+                            }
+                            Err(error) => {
+                                tracing::warn!("Ignoring unknown WebSocket message: {}", error);
+                            }
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                    // the client closed the connection
+                    Some(Err(_)) | None => break,
+                }
+            }
+            event = rx.recv() => {
+                let result = match event {
+                    Ok(event) => send_event(&mut socket, &filter, &event).await,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("WebSocket client lagged, {} events skipped", skipped);
+                        metrics.events_lagged(Transport::WebSocket, skipped);
+                        // the next received event is the oldest one in the channel
+                        let Ok(event) = rx.recv().await else {
+                            break;
+                        };
+                        let lagged = ControlMessage::Lagged { seq: event.seq };
+                        match send_json(&mut socket, &lagged).await {
+                            Ok(()) => send_event(&mut socket, &filter, &event).await,
+                            Err(error) => Err(error),
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn send_event(
+    socket: &mut WebSocket,
+    filter: &EventsFilter,
+    event: &SequencedEvent,
+) -> Result<(), axum::Error> {
+    let Ok(json) = serde_json::to_value(event) else {
+        return Ok(());
+    };
+    if !filter.matches(&json) {
+        return Ok(());
+    }
+    socket.send(Message::Text(json.to_string())).await
+}
+
+async fn send_json<T: Serialize>(socket: &mut WebSocket, message: &T) -> Result<(), axum::Error> {
+    let Ok(json) = serde_json::to_string(message) else {
+        return Ok(());
+    };
+    socket.send(Message::Text(json)).await
 }
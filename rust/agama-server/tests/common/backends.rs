@@ -0,0 +1,692 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! In-process mocks of the Agama D-Bus services.
+//!
+//! [Backends::start] exports the Manager1, Software1, Storage1 and Users1 objects (plus the
+//! ServiceStatus, Progress and Issues interfaces) on the connection of a testing [DBusServer]
+//! and owns the well-known names, so the routers from `agama_server::*::web` can be built on top
+//! of them.
+//!
+//! The state of each mock lives in public fields. Tests change it through
+//! [Backends::interface] and, if the router listens for changes, emit the corresponding
+//! `*_changed` signal using the interface's signal emitter. All the mocks record the methods
+//! they receive, so tests can check them with [Backends::calls].
+//!
+//! [DBusServer]: super::DBusServer
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use zbus::{
+    interface,
+    object_server::{Interface, InterfaceRef, SignalEmitter},
+    zvariant::{OwnedValue, Value},
+};
+
+pub const MANAGER_SERVICE: &str = "org.opensuse.Agama.Manager1";
+pub const MANAGER_PATH: &str = "/org/opensuse/Agama/Manager1";
+pub const USERS_PATH: &str = "/org/opensuse/Agama/Users1";
+pub const SOFTWARE_SERVICE: &str = "org.opensuse.Agama.Software1";
+pub const SOFTWARE_PATH: &str = "/org/opensuse/Agama/Software1";
+pub const PRODUCT_PATH: &str = "/org/opensuse/Agama/Software1/Product";
+pub const PROPOSAL_PATH: &str = "/org/opensuse/Agama/Software1/Proposal";
+pub const STORAGE_SERVICE: &str = "org.opensuse.Agama.Storage1";
+pub const STORAGE_PATH: &str = "/org/opensuse/Agama/Storage1";
+
+/// Methods called on the mocks (e.g., "Manager1.Probe").
+#[derive(Clone, Default)]
+pub struct Calls(Arc<Mutex<Vec<String>>>);
+
+impl Calls {
+    fn push(&self, call: &str) {
+        self.0.lock().unwrap().push(call.to_string());
+    }
+}
+
+/// Set of mocked D-Bus services.
+pub struct Backends {
+    connection: zbus::Connection,
+    calls: Calls,
+}
+
+impl Backends {
+    /// Exports the mocked objects and requests the services names.
+    ///
+    /// * `connection`: connection to the testing D-Bus server.
+    pub async fn start(connection: &zbus::Connection) -> zbus::Result<Self> {
+        let calls = Calls::default();
+        let server = connection.object_server();
+
+        server
+            .at(MANAGER_PATH, MockManager::new(calls.clone()))
+            .await?;
+        server.at(USERS_PATH, MockUsers::new(calls.clone())).await?;
+        server
+            .at(SOFTWARE_PATH, MockSoftware::new(calls.clone()))
+            .await?;
+        server
+            .at(PRODUCT_PATH, MockProduct::new(calls.clone()))
+            .await?;
+        server
+            .at(PRODUCT_PATH, MockRegistration::new(calls.clone()))
+            .await?;
+        server
+            .at(PROPOSAL_PATH, MockProposal::new(calls.clone()))
+            .await?;
+        server
+            .at(STORAGE_PATH, MockStorage::new(calls.clone()))
+            .await?;
+
+        for path in [MANAGER_PATH, USERS_PATH, SOFTWARE_PATH, STORAGE_PATH] {
+            server.at(path, MockServiceStatus::default()).await?;
+            server.at(path, MockIssues::default()).await?;
+        }
+        server.at(PRODUCT_PATH, MockIssues::default()).await?;
+        for path in [MANAGER_PATH, SOFTWARE_PATH, STORAGE_PATH] {
+            server.at(path, MockProgress::default()).await?;
+        }
+
+        for name in [MANAGER_SERVICE, SOFTWARE_SERVICE, STORAGE_SERVICE] {
+            connection.request_name(name).await?;
+        }
+
+        Ok(Self {
+            connection: connection.clone(),
+            calls,
+        })
+    }
+
+    /// Returns a reference to the mocked interface at the given path.
+    ///
+    /// ```ignore
+    /// let manager = backends.interface::<MockManager>(MANAGER_PATH).await;
+    /// manager.get_mut().await.can_install = false;
+    /// ```
+    pub async fn interface<I: Interface>(&self, path: &str) -> InterfaceRef<I> {
+        self.connection
+            .object_server()
+            .interface::<_, I>(path)
+            .await
+            .unwrap_or_else(|_| panic!("no such interface at {path}"))
+    }
+
+    /// Returns the methods called so far.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.0.lock().unwrap().clone()
+    }
+}
+
+/// Mock of the `org.opensuse.Agama1.ServiceStatus` interface.
+#[derive(Default)]
+pub struct MockServiceStatus {
+    /// Current status (0 = idle, 1 = busy).
+    pub current: u32,
+}
+
+#[interface(name = "org.opensuse.Agama1.ServiceStatus")]
+impl MockServiceStatus {
+    #[zbus(property)]
+    fn all(&self) -> Vec<HashMap<String, OwnedValue>> {
+        labelled(&["idle", "busy"])
+    }
+
+    #[zbus(property)]
+    fn current(&self) -> u32 {
+        self.current
+    }
+}
+
+/// Mock of the `org.opensuse.Agama1.Progress` interface.
+pub struct MockProgress {
+    pub current_step: (u32, String),
+    pub total_steps: u32,
+    pub finished: bool,
+    pub steps: Vec<String>,
+}
+
+impl Default for MockProgress {
+    fn default() -> Self {
+        Self {
+            current_step: (0, String::new()),
+            total_steps: 0,
+            finished: true,
+            steps: vec![],
+        }
+    }
+}
+
+#[interface(name = "org.opensuse.Agama1.Progress")]
+impl MockProgress {
+    #[zbus(property)]
+    fn current_step(&self) -> (u32, String) {
+        self.current_step.clone()
+    }
+
+    #[zbus(property)]
+    fn finished(&self) -> bool {
+        self.finished
+    }
+
+    #[zbus(property)]
+    fn total_steps(&self) -> u32 {
+        self.total_steps
+    }
+
+    #[zbus(property)]
+    fn steps(&self) -> Vec<String> {
+        self.steps.clone()
+    }
+}
+
+/// Mock of the `org.opensuse.Agama1.Issues` interface.
+#[derive(Default)]
+pub struct MockIssues {
+    /// Issues as (description, details, source, severity).
+    pub issues: Vec<(String, String, u32, u32)>,
+}
+
+#[interface(name = "org.opensuse.Agama1.Issues")]
+impl MockIssues {
+    #[zbus(property)]
+    fn all(&self) -> Vec<(String, String, u32, u32)> {
+        self.issues.clone()
+    }
+}
+
+/// Mock of the `org.opensuse.Agama.Manager1` interface.
+pub struct MockManager {
+    calls: Calls,
+    pub busy_services: Vec<String>,
+    /// Installation phase (0 = startup, 1 = config, 2 = install).
+    pub phase: u32,
+    pub can_install: bool,
+    pub iguana: bool,
+}
+
+impl MockManager {
+    fn new(calls: Calls) -> Self {
+        Self {
+            calls,
+            busy_services: vec![],
+            phase: 1,
+            can_install: true,
+            iguana: false,
+        }
+    }
+}
+
+#[interface(name = "org.opensuse.Agama.Manager1")]
+impl MockManager {
+    fn can_install(&self) -> bool {
+        self.can_install
+    }
+
+    fn collect_logs(&self) -> String {
+        self.calls.push("Manager1.CollectLogs");
+        "/tmp/agama-logs".to_string()
+    }
+
+    fn commit(&mut self) {
+        self.calls.push("Manager1.Commit");
+        self.phase = 2;
+    }
+
+    fn finish(&self) {
+        self.calls.push("Manager1.Finish");
+    }
+
+    fn probe(&self) {
+        self.calls.push("Manager1.Probe");
+    }
+
+    #[zbus(property)]
+    fn busy_services(&self) -> Vec<String> {
+        self.busy_services.clone()
+    }
+
+    #[zbus(property)]
+    fn current_installation_phase(&self) -> u32 {
+        self.phase
+    }
+
+    #[zbus(property)]
+    fn iguana_backend(&self) -> bool {
+        self.iguana
+    }
+
+    #[zbus(property)]
+    fn installation_phases(&self) -> Vec<HashMap<String, OwnedValue>> {
+        labelled(&["startup", "config", "install"])
+    }
+}
+
+/// Mock of the `org.opensuse.Agama.Users1` interface.
+pub struct MockUsers {
+    calls: Calls,
+    /// First user as (full name, user name, password, hashed password, autologin).
+    pub first_user: (String, String, String, bool, bool),
+    pub root_password: bool,
+    pub root_ssh_key: String,
+    /// Issues to report when setting the first user.
+    pub first_user_issues: Vec<String>,
+}
+
+impl MockUsers {
+    fn new(calls: Calls) -> Self {
+        Self {
+            calls,
+            first_user: Default::default(),
+            root_password: false,
+            root_ssh_key: String::new(),
+            first_user_issues: vec![],
+        }
+    }
+}
+
+#[interface(name = "org.opensuse.Agama.Users1")]
+impl MockUsers {
+    fn remove_first_user(&mut self) -> u32 {
+        self.calls.push("Users1.RemoveFirstUser");
+        self.first_user = Default::default();
+        0
+    }
+
+    fn remove_root_password(&mut self) -> u32 {
+        self.calls.push("Users1.RemoveRootPassword");
+        self.root_password = false;
+        0
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn set_first_user(
+        &mut self,
+        full_name: String,
+        user_name: String,
+        password: String,
+        hashed_password: bool,
+        autologin: bool,
+        _data: HashMap<String, OwnedValue>,
+    ) -> (bool, Vec<String>) {
+        self.calls.push("Users1.SetFirstUser");
+        if self.first_user_issues.is_empty() {
+            self.first_user = (full_name, user_name, password, hashed_password, autologin);
+        }
+        (
+            self.first_user_issues.is_empty(),
+            self.first_user_issues.clone(),
+        )
+    }
+
+    fn set_root_password(&mut self, _value: String, _hashed: bool) -> u32 {
+        self.calls.push("Users1.SetRootPassword");
+        self.root_password = true;
+        0
+    }
+
+    #[zbus(name = "SetRootSSHKey")]
+    fn set_root_ssh_key(&mut self, value: String) -> u32 {
+        self.calls.push("Users1.SetRootSSHKey");
+        self.root_ssh_key = value;
+        0
+    }
+
+    fn write(&self) -> u32 {
+        self.calls.push("Users1.Write");
+        0
+    }
+
+    #[zbus(property)]
+    fn first_user(
+        &self,
+    ) -> (
+        String,
+        String,
+        String,
+        bool,
+        bool,
+        HashMap<String, OwnedValue>,
+    ) {
+        let (full_name, user_name, password, hashed_password, autologin) = self.first_user.clone();
+        (
+            full_name,
+            user_name,
+            password,
+            hashed_password,
+            autologin,
+            HashMap::new(),
+        )
+    }
+
+    #[zbus(property)]
+    fn root_password_set(&self) -> bool {
+        self.root_password
+    }
+
+    #[zbus(property, name = "RootSSHKey")]
+    fn root_ssh_key(&self) -> String {
+        self.root_ssh_key.clone()
+    }
+}
+
+/// Pattern as (category, description, icon, summary, order).
+pub type MockPattern = (String, String, String, String, String);
+
+/// Mock of the `org.opensuse.Agama.Software1` interface.
+pub struct MockSoftware {
+    calls: Calls,
+    pub patterns: HashMap<String, MockPattern>,
+    /// Selected patterns and the reason (0 = user, 1 = auto).
+    pub selected_patterns: HashMap<String, u8>,
+    pub used_disk_space: String,
+}
+
+impl MockSoftware {
+    fn new(calls: Calls) -> Self {
+        let pattern = |category: &str, description: &str, summary: &str, order: &str| {
+            (
+                category.to_string(),
+                description.to_string(),
+                "default".to_string(),
+                summary.to_string(),
+                order.to_string(),
+            )
+        };
+        let patterns = HashMap::from([
+            (
+                "gnome".to_string(),
+                pattern("Graphical Environments", "GNOME desktop", "GNOME", "1010"),
+            ),
+            (
+                "yast2_basis".to_string(),
+                pattern("Base Technologies", "YaST basis", "YaST", "1220"),
+            ),
+        ]);
+        Self {
+            calls,
+            patterns,
+            selected_patterns: HashMap::from([("yast2_basis".to_string(), 1)]),
+            used_disk_space: "4.2 GiB".to_string(),
+        }
+    }
+}
+
+#[interface(name = "org.opensuse.Agama.Software1")]
+impl MockSoftware {
+    fn list_patterns(&self, _filtered: bool) -> HashMap<String, MockPattern> {
+        self.patterns.clone()
+    }
+
+    fn probe(&self) {
+        self.calls.push("Software1.Probe");
+    }
+
+    /// Selects the known patterns and returns the unknown ones.
+    async fn set_user_patterns(
+        &mut self,
+        add: Vec<String>,
+        remove: Vec<String>,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+    ) -> Vec<String> {
+        self.calls.push("Software1.SetUserPatterns");
+        let (known, unknown): (Vec<_>, Vec<_>) =
+            add.into_iter().partition(|p| self.patterns.contains_key(p));
+        for pattern in known {
+            self.selected_patterns.insert(pattern, 0);
+        }
+        for pattern in remove {
+            self.selected_patterns.remove(&pattern);
+        }
+        _ = self.selected_patterns_changed(&emitter).await;
+        unknown
+    }
+
+    fn used_disk_space(&self) -> String {
+        self.used_disk_space.clone()
+    }
+
+    #[zbus(property)]
+    fn selected_patterns(&self) -> HashMap<String, u8> {
+        self.selected_patterns.clone()
+    }
+}
+
+/// Mock of the `org.opensuse.Agama.Software1.Product` interface.
+pub struct MockProduct {
+    calls: Calls,
+    /// Products as (id, name, description).
+    pub products: Vec<(String, String, String)>,
+    pub selected: String,
+}
+
+impl MockProduct {
+    fn new(calls: Calls) -> Self {
+        let product = |id: &str, name: &str, description: &str| {
+            (id.to_string(), name.to_string(), description.to_string())
+        };
+        Self {
+            calls,
+            products: vec![
+                product("Tumbleweed", "openSUSE Tumbleweed", "Rolling release"),
+                product("MicroOS", "openSUSE MicroOS", "Immutable system"),
+            ],
+            selected: String::new(),
+        }
+    }
+}
+
+#[interface(name = "org.opensuse.Agama.Software1.Product")]
+impl MockProduct {
+    fn available_products(&self) -> Vec<(String, String, HashMap<String, OwnedValue>)> {
+        self.products
+            .iter()
+            .map(|(id, name, description)| {
+                let data = HashMap::from([("description".to_string(), owned_str(description))]);
+                (id.clone(), name.clone(), data)
+            })
+            .collect()
+    }
+
+    async fn select_product(
+        &mut self,
+        id: String,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+    ) -> (u32, String) {
+        self.calls.push("Software1.Product.SelectProduct");
+        if !self.products.iter().any(|(p, _, _)| p == &id) {
+            return (3, "Unknown product".to_string());
+        }
+        self.selected = id;
+        _ = self.selected_product_changed(&emitter).await;
+        (0, String::new())
+    }
+
+    #[zbus(property)]
+    fn selected_product(&self) -> String {
+        self.selected.clone()
+    }
+}
+
+/// Mock of the `org.opensuse.Agama1.Registration` interface.
+pub struct MockRegistration {
+    calls: Calls,
+    pub email: String,
+    pub reg_code: String,
+    pub requirement: u32,
+    /// Result of the next registration or deregistration.
+    pub result: (u32, String),
+}
+
+impl MockRegistration {
+    fn new(calls: Calls) -> Self {
+        Self {
+            calls,
+            email: String::new(),
+            reg_code: String::new(),
+            requirement: 0,
+            result: (0, String::new()),
+        }
+    }
+}
+
+#[interface(name = "org.opensuse.Agama1.Registration")]
+impl MockRegistration {
+    fn register(
+        &mut self,
+        reg_code: String,
+        options: HashMap<String, OwnedValue>,
+    ) -> (u32, String) {
+        self.calls.push("Registration.Register");
+        if self.result.0 == 0 {
+            self.reg_code = reg_code;
+            self.email = options
+                .get("Email")
+                .and_then(|v| <&str>::try_from(v).ok())
+                .unwrap_or_default()
+                .to_string();
+        }
+        self.result.clone()
+    }
+
+    fn deregister(&mut self) -> (u32, String) {
+        self.calls.push("Registration.Deregister");
+        if self.result.0 == 0 {
+            self.reg_code.clear();
+            self.email.clear();
+        }
+        self.result.clone()
+    }
+
+    #[zbus(property)]
+    fn email(&self) -> String {
+        self.email.clone()
+    }
+
+    #[zbus(property)]
+    fn reg_code(&self) -> String {
+        self.reg_code.clone()
+    }
+
+    #[zbus(property)]
+    fn requirement(&self) -> u32 {
+        self.requirement
+    }
+}
+
+/// Mock of the `org.opensuse.Agama.Software1.Proposal` interface.
+pub struct MockProposal {
+    calls: Calls,
+    /// Resolvables by list ID.
+    pub resolvables: HashMap<String, Vec<String>>,
+}
+
+impl MockProposal {
+    fn new(calls: Calls) -> Self {
+        Self {
+            calls,
+            resolvables: HashMap::new(),
+        }
+    }
+}
+
+#[interface(name = "org.opensuse.Agama.Software1.Proposal")]
+impl MockProposal {
+    fn set_resolvables(
+        &mut self,
+        id: String,
+        _type: u8,
+        resolvables: Vec<String>,
+        _optional: bool,
+    ) {
+        self.calls.push("Software1.Proposal.SetResolvables");
+        self.resolvables.insert(id, resolvables);
+    }
+}
+
+/// Mock of the `org.opensuse.Agama.Storage1` interface.
+pub struct MockStorage {
+    calls: Calls,
+    /// Storage configuration (JSON).
+    pub config: String,
+    pub deprecated_system: bool,
+}
+
+impl MockStorage {
+    fn new(calls: Calls) -> Self {
+        Self {
+            calls,
+            config: r#"{"storage":{"drives":[]}}"#.to_string(),
+            deprecated_system: false,
+        }
+    }
+}
+
+#[interface(name = "org.opensuse.Agama.Storage1")]
+impl MockStorage {
+    fn finish(&self) {
+        self.calls.push("Storage1.Finish");
+    }
+
+    fn install(&self) {
+        self.calls.push("Storage1.Install");
+    }
+
+    async fn probe(&mut self, #[zbus(signal_emitter)] emitter: SignalEmitter<'_>) {
+        self.calls.push("Storage1.Probe");
+        self.deprecated_system = false;
+        _ = self.deprecated_system_changed(&emitter).await;
+    }
+
+    fn set_config(&mut self, settings: String) -> u32 {
+        self.calls.push("Storage1.SetConfig");
+        self.config = settings;
+        0
+    }
+
+    fn get_config(&self) -> String {
+        self.config.clone()
+    }
+
+    #[zbus(property)]
+    fn deprecated_system(&self) -> bool {
+        self.deprecated_system
+    }
+}
+
+/// Builds a list of `{ id, label }` dictionaries.
+fn labelled(labels: &[&str]) -> Vec<HashMap<String, OwnedValue>> {
+    labels
+        .iter()
+        .enumerate()
+        .map(|(id, label)| {
+            HashMap::from([
+                ("id".to_string(), OwnedValue::from(id as u32)),
+                ("label".to_string(), owned_str(label)),
+            ])
+        })
+        .collect()
+}
+
+fn owned_str(value: &str) -> OwnedValue {
+    Value::from(value)
+        .try_to_owned()
+        .expect("strings can be owned")
+}
@@ -18,6 +18,9 @@
 // To contact SUSE LLC about this file by physical or electronic mail, you may
 // find current contact information at www.suse.com.
 
+pub mod backends;
+pub mod openapi;
+
 use agama_lib::error::ServiceError;
 use axum::body::{to_bytes, Body};
 use std::{
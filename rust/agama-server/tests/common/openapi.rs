@@ -0,0 +1,116 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+//! Helpers to check the routers against the OpenAPI specification.
+
+use agama_server::web::docs::{
+    ApiDocBuilder, BootloaderApiDocBuilder, L10nApiDocBuilder, ManagerApiDocBuilder,
+    MiscApiDocBuilder, NetworkApiDocBuilder, QuestionsApiDocBuilder, ScriptsApiDocBuilder,
+    SoftwareApiDocBuilder, StorageApiDocBuilder, UsersApiDocBuilder,
+};
+use axum::Router;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// Returns the whole OpenAPI specification as JSON.
+pub fn api_doc() -> Value {
+    let builders: Vec<Box<dyn ApiDocBuilder>> = vec![
+        Box::new(BootloaderApiDocBuilder),
+        Box::new(L10nApiDocBuilder),
+        Box::new(ManagerApiDocBuilder),
+        Box::new(MiscApiDocBuilder),
+        Box::new(NetworkApiDocBuilder),
+        Box::new(QuestionsApiDocBuilder),
+        Box::new(ScriptsApiDocBuilder),
+        Box::new(SoftwareApiDocBuilder),
+        Box::new(StorageApiDocBuilder),
+        Box::new(UsersApiDocBuilder),
+    ];
+    let mut api = builders[0].build();
+    for builder in &builders[1..] {
+        api.merge(builder.build());
+    }
+    serde_json::to_value(api).unwrap()
+}
+
+/// Returns the routes of a router as (method, path) pairs, using the OpenAPI syntax for the
+/// path parameters (e.g., `("GET", "/api/questions/{id}")`).
+///
+/// axum does not offer a way to list the routes, so they are extracted from the router debug
+/// output. Services added with `nest_service` are not included.
+pub fn routes(router: &Router) -> BTreeSet<(String, String)> {
+    let debug = format!("{:?}", router);
+    // skip the fallback router
+    let debug = debug.split("fallback_router:").next().unwrap();
+
+    let route_re =
+        Regex::new(r"RouteId\((\d+)\): MethodRouter\(MethodRouter \{(.*?)allow_header").unwrap();
+    let method_re = Regex::new(r"(\w+): (?:Route|BoxedHandler)").unwrap();
+    let path_re = Regex::new(r#"RouteId\((\d+)\): "([^"]*)""#).unwrap();
+    let param_re = Regex::new(r":(\w+)").unwrap();
+
+    let paths: HashMap<_, _> = path_re
+        .captures_iter(debug)
+        .map(|c| {
+            (
+                c[1].to_string(),
+                param_re.replace_all(&c[2], "{$1}").to_string(),
+            )
+        })
+        .collect();
+
+    let mut routes = BTreeSet::new();
+    for route in route_re.captures_iter(debug) {
+        let path = &paths[&route[1]];
+        for method in method_re.captures_iter(&route[2]) {
+            if &method[1] != "fallback" {
+                routes.insert((method[1].to_uppercase(), path.clone()));
+            }
+        }
+    }
+    routes
+}
+
+/// Returns the routes which are not included in the OpenAPI specification.
+///
+/// * `routes`: routes to check (see [routes]).
+pub fn undocumented(routes: &BTreeSet<(String, String)>) -> Vec<String> {
+    let doc = api_doc();
+    routes
+        .iter()
+        .filter(|(method, path)| !is_documented(&doc, method, path))
+        .map(|(method, path)| format!("{method} {path}"))
+        .collect()
+}
+
+/// Determines whether a route is included in the OpenAPI specification.
+///
+/// * `doc`: OpenAPI specification (see [api_doc]).
+/// * `method`: HTTP method.
+/// * `path`: route path.
+pub fn is_documented(doc: &Value, method: &str, path: &str) -> bool {
+    let paths = &doc["paths"];
+    let method = method.to_lowercase();
+    let path = path.trim_end_matches('/');
+    [path.to_string(), format!("{path}/")]
+        .iter()
+        .any(|p| paths[p][&method].is_object())
+}
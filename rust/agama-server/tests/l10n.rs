@@ -121,3 +121,17 @@ async fn test_set_config_locales() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+async fn test_routes_are_documented() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let router = Router::new().nest("/api/l10n", service);
+
+    let undocumented = common::openapi::undocumented(&common::openapi::routes(&router));
+    assert!(
+        undocumented.is_empty(),
+        "undocumented routes: {undocumented:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,118 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+pub mod common;
+
+use agama_server::manager::web::manager_service;
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use common::{
+    backends::{Backends, MockManager, MockServiceStatus, MANAGER_PATH},
+    body_to_string, DBusServer, Started,
+};
+use std::error::Error;
+use tokio::test;
+use tower::ServiceExt;
+
+async fn build_service() -> Result<(DBusServer<Started>, Backends, Router), Box<dyn Error>> {
+    let server = DBusServer::new().start().await?;
+    let backends = Backends::start(&server.connection()).await?;
+    let service = manager_service(server.connection()).await?;
+    Ok((server, backends, service))
+}
+
+fn request(method: Method, uri: &str) -> Request<Body> {
+    Request::builder()
+        .uri(uri)
+        .method(method)
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[test]
+async fn test_installer_status() -> Result<(), Box<dyn Error>> {
+    let (_server, _backends, service) = build_service().await?;
+
+    let response = service.oneshot(request(Method::GET, "/installer")).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(
+        body,
+        r#"{"phase":1,"isBusy":false,"useIguana":false,"canInstall":true}"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_installer_status_when_busy() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+    // the proxies cache the properties, so the state is set before the first request
+    let manager = backends.interface::<MockManager>(MANAGER_PATH).await;
+    manager.get_mut().await.can_install = false;
+    let status = backends.interface::<MockServiceStatus>(MANAGER_PATH).await;
+    status.get_mut().await.current = 1;
+
+    let response = service.oneshot(request(Method::GET, "/installer")).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(
+        body,
+        r#"{"phase":1,"isBusy":true,"useIguana":false,"canInstall":false}"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_probe_and_install() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+
+    let response = service
+        .clone()
+        .oneshot(request(Method::POST, "/probe_sync"))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = service
+        .clone()
+        .oneshot(request(Method::POST, "/install"))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(backends.calls(), vec!["Manager1.Probe", "Manager1.Commit"]);
+
+    // CanInstall is not asked during the installation
+    let response = service.oneshot(request(Method::GET, "/installer")).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.starts_with(r#"{"phase":2,"#));
+    assert!(body.contains(r#""canInstall":false"#));
+    Ok(())
+}
+
+#[test]
+async fn test_manager_status() -> Result<(), Box<dyn Error>> {
+    let (_server, _backends, service) = build_service().await?;
+
+    let response = service.oneshot(request(Method::GET, "/status")).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, r#"{"current":0}"#);
+    Ok(())
+}
@@ -26,9 +26,10 @@ use agama_lib::network::types::{DeviceType, SSID};
 use agama_server::network::web::network_service;
 use agama_server::network::{
     self,
-    model::{self, AccessPoint, GeneralState, StateConfig},
+    model::{self, AccessPoint, GeneralState, NetworkChange, StateConfig},
     Adapter, NetworkAdapterError, NetworkState,
 };
+use agama_server::web::Event;
 
 use async_trait::async_trait;
 use axum::http::header;
@@ -39,7 +40,11 @@ use axum::{
 };
 use common::body_to_string;
 use serde_json::to_string;
-use std::error::Error;
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use tokio::{sync::broadcast, test};
 use tower::ServiceExt;
 
@@ -75,6 +80,59 @@ impl Adapter for NetworkTestAdapter {
     }
 }
 
+/// Adapter which keeps track of the calls to write and confirm the configuration.
+#[derive(Clone, Default)]
+pub struct CheckpointTestAdapter {
+    state: network::NetworkState,
+    calls: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl CheckpointTestAdapter {
+    fn calls(&self) -> Vec<&'static str> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn register(&self, call: &'static str) -> Result<(), NetworkAdapterError> {
+        self.calls.lock().unwrap().push(call);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Adapter for CheckpointTestAdapter {
+    async fn read(&self, _: StateConfig) -> Result<network::NetworkState, NetworkAdapterError> {
+        Ok(self.state.clone())
+    }
+
+    async fn write(&self, _network: &network::NetworkState) -> Result<(), NetworkAdapterError> {
+        self.register("write")
+    }
+
+    async fn write_with_rollback(
+        &self,
+        _network: &network::NetworkState,
+        _timeout: Duration,
+    ) -> Result<(), NetworkAdapterError> {
+        self.register("write_with_rollback")
+    }
+
+    async fn confirm(&self) -> Result<(), NetworkAdapterError> {
+        self.register("confirm")
+    }
+
+    async fn rollback(&self) -> Result<(), NetworkAdapterError> {
+        self.register("rollback")
+    }
+}
+
+fn post_request(uri: &str) -> Request<Body> {
+    Request::builder()
+        .uri(uri)
+        .method(Method::POST)
+        .body(Body::empty())
+        .unwrap()
+}
+
 #[test]
 async fn test_network_state() -> Result<(), Box<dyn Error>> {
     let state = build_state().await;
@@ -240,3 +298,70 @@ async fn test_add_bond_connection() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+async fn test_apply_and_confirm() -> Result<(), Box<dyn Error>> {
+    let adapter = CheckpointTestAdapter {
+        state: build_state().await,
+        ..Default::default()
+    };
+    let (tx, _rx) = broadcast::channel(16);
+    let network_service = network_service(adapter.clone(), tx).await?;
+
+    let request = post_request("/system/apply?confirmTimeout=60");
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let request = post_request("/system/apply");
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = post_request("/system/confirm");
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = post_request("/system/confirm");
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = post_request("/system/apply?confirmTimeout=0");
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    assert_eq!(adapter.calls(), vec!["write_with_rollback", "confirm"]);
+    Ok(())
+}
+
+#[test]
+async fn test_apply_rollback_on_timeout() -> Result<(), Box<dyn Error>> {
+    let adapter = CheckpointTestAdapter {
+        state: build_state().await,
+        ..Default::default()
+    };
+    let (tx, mut rx) = broadcast::channel(16);
+    let network_service = network_service(adapter.clone(), tx).await?;
+
+    let request = post_request("/system/apply?confirmTimeout=1");
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let rolled_back = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Ok(event) = rx.recv().await {
+            if let Event::NetworkChange {
+                change: NetworkChange::ApplyRolledBack,
+            } = event
+            {
+                return true;
+            }
+        }
+        false
+    })
+    .await?;
+    assert!(rolled_back);
+    assert_eq!(adapter.calls(), vec!["write_with_rollback", "rollback"]);
+
+    let request = post_request("/system/confirm");
+    let response = network_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
@@ -0,0 +1,111 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+pub mod common;
+
+use agama_lib::endpoints::ENDPOINTS;
+use agama_server::{
+    bootloader::web::bootloader_service,
+    manager::web::manager_service,
+    network::{
+        model::StateConfig, web::network_service, Adapter, NetworkAdapterError, NetworkState,
+    },
+    questions::web::questions_service,
+    scripts::web::scripts_service,
+    software::web::software_service,
+    storage::web::storage_service,
+    users::web::users_service,
+    web::MainServiceBuilder,
+};
+use async_trait::async_trait;
+use axum::Router;
+use common::{
+    backends::Backends,
+    openapi::{api_doc, is_documented, routes, undocumented},
+    DBusServer,
+};
+use std::{error::Error, path::PathBuf};
+use tokio::{sync::broadcast::channel, test};
+
+/// Routes which are not described in the OpenAPI specification on purpose.
+const UNDOCUMENTED: &[&str] = &[
+    // WebSocket and Server-Sent Events streams
+    "GET /api/ws",
+    "GET /api/events",
+];
+
+struct EmptyAdapter;
+
+#[async_trait]
+impl Adapter for EmptyAdapter {
+    async fn read(&self, _: StateConfig) -> Result<NetworkState, NetworkAdapterError> {
+        Ok(NetworkState::default())
+    }
+
+    async fn write(&self, _network: &NetworkState) -> Result<(), NetworkAdapterError> {
+        Ok(())
+    }
+}
+
+#[test]
+async fn test_routes_are_documented() -> Result<(), Box<dyn Error>> {
+    let server = DBusServer::new().start().await?;
+    let dbus = server.connection();
+    let _backends = Backends::start(&dbus).await?;
+    let (tx, _) = channel(16);
+
+    // The l10n router is checked in the l10n tests because it needs the locales database.
+    let api = Router::new()
+        .nest("/manager", manager_service(dbus.clone()).await?)
+        .nest("/software", software_service(dbus.clone()).await?)
+        .nest("/storage", storage_service(dbus.clone()).await?)
+        .nest("/bootloader", bootloader_service(dbus.clone()).await?)
+        .nest("/network", network_service(EmptyAdapter, tx.clone()).await?)
+        .nest("/questions", questions_service(dbus.clone()).await?)
+        .nest("/users", users_service(dbus.clone()).await?)
+        .nest("/scripts", scripts_service().await?);
+    let main = MainServiceBuilder::new(tx, PathBuf::from("public")).build();
+    let router = Router::new().nest("/api", api).merge(main);
+
+    let routes = routes(&router);
+    assert!(routes.contains(&("GET".to_string(), "/api/software/config".to_string())));
+    let undocumented: Vec<_> = undocumented(&routes)
+        .into_iter()
+        .filter(|r| !UNDOCUMENTED.contains(&r.as_str()))
+        .collect();
+    assert!(
+        undocumented.is_empty(),
+        "Routes missing from the OpenAPI specification: {undocumented:#?}"
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_client_endpoints_are_documented() {
+    let doc = api_doc();
+    let undocumented: Vec<_> = ENDPOINTS
+        .iter()
+        .filter(|(method, path)| !is_documented(&doc, method, &format!("/api{path}")))
+        .collect();
+    assert!(
+        undocumented.is_empty(),
+        "Client endpoints missing from the OpenAPI specification: {undocumented:#?}"
+    );
+}
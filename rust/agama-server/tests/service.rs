@@ -20,16 +20,26 @@
 
 pub mod common;
 
-use agama_lib::auth::AuthToken;
-use agama_server::web::{MainServiceBuilder, ServiceConfig};
+use agama_lib::{auth::AuthToken, recording::Recording};
+use agama_server::web::{
+    csrf_token, MainServiceBuilder, PeerAddress, PeerCredentials, Recorder, ServiceConfig,
+};
 use axum::{
     body::Body,
     http::{Method, Request, StatusCode},
     response::Response,
-    routing::get,
+    routing::{get, post},
 };
 use common::body_to_string;
-use std::{error::Error, path::PathBuf};
+use serde_json::json;
+use std::{
+    error::Error,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
 use tokio::{sync::broadcast::channel, test};
 use tower::ServiceExt;
 
@@ -66,6 +76,7 @@ async fn protected() -> String {
 async fn access_protected_route(token: &str, jwt_secret: &str) -> Response {
     let config = ServiceConfig {
         jwt_secret: jwt_secret.to_string(),
+        ..Default::default()
     };
     let (tx, _) = channel(16);
     let web_service = MainServiceBuilder::new(tx, public_dir())
@@ -103,3 +114,410 @@ async fn test_access_protected_route_failed() -> Result<(), Box<dyn Error>> {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     Ok(())
 }
+
+async fn access_protected_route_as(credentials: PeerCredentials) -> Response {
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .add_service("/protected", get(protected))
+        .build();
+
+    let mut request = Request::builder()
+        .uri("/api/protected")
+        .method(Method::GET)
+        .body(Body::empty())
+        .unwrap();
+    request.extensions_mut().insert(credentials);
+
+    web_service.oneshot(request).await.unwrap()
+}
+
+#[test]
+async fn test_access_protected_route_as_root() -> Result<(), Box<dyn Error>> {
+    let credentials = PeerCredentials {
+        uid: 0,
+        pid: Some(1),
+    };
+    let response = access_protected_route_as(credentials).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let credentials = PeerCredentials {
+        uid: 1000,
+        pid: Some(1),
+    };
+    let response = access_protected_route_as(credentials).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[test]
+async fn test_events_stream() -> Result<(), Box<dyn Error>> {
+    let config = ServiceConfig {
+        jwt_secret: "nots3cr3t".to_string(),
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .with_config(config)
+        .build();
+
+    let token = AuthToken::generate("nots3cr3t")?;
+    let request = Request::builder()
+        .uri("/api/events")
+        .method(Method::GET)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_metrics() -> Result<(), Box<dyn Error>> {
+    let config = ServiceConfig {
+        jwt_secret: "nots3cr3t".to_string(),
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .with_config(config)
+        .build();
+
+    let token = AuthToken::generate("nots3cr3t")?;
+    let request = Request::builder()
+        .uri("/api/metrics")
+        .method(Method::GET)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("agama_event_stream_clients{transport=\"websocket\"} 0"));
+    assert!(body.ends_with("# EOF\n"));
+    Ok(())
+}
+
+#[test]
+async fn test_health_without_dbus() -> Result<(), Box<dyn Error>> {
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir()).build();
+
+    let request = Request::builder()
+        .uri("/api/health")
+        .method(Method::GET)
+        .body(Body::empty())
+        .unwrap();
+
+    let response = web_service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""healthy":false"#));
+    assert!(body.contains(r#""name":"storage""#));
+    Ok(())
+}
+
+#[test]
+async fn test_recording() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("recording.jsonl");
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .add_service("/protected", get(protected))
+        .with_recorder(Recorder::new(&path))
+        .build();
+
+    let request = Request::builder()
+        .uri("/api/ping?verbose=true")
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(&body, "{\"status\":\"success\"}");
+
+    let request = Request::builder()
+        .uri("/api/auth")
+        .method(Method::POST)
+        .header("Content-Type", "application/json")
+        .body(Body::from(r#"{"password":"s3cr3t"}"#))
+        .unwrap();
+    web_service.oneshot(request).await.unwrap();
+
+    let recording = Recording::read(&path)?;
+    assert_eq!(recording.exchanges.len(), 1);
+    let exchange = &recording.exchanges[0];
+    assert_eq!(exchange.method, "GET");
+    assert_eq!(exchange.path, "/api/ping");
+    assert_eq!(exchange.query, Some("verbose=true".to_string()));
+    assert_eq!(exchange.response, Some(json!({ "status": "success" })));
+    Ok(())
+}
+
+#[test]
+async fn test_idempotency_key() -> Result<(), Box<dyn Error>> {
+    let calls = Arc::new(AtomicU32::new(0));
+    let counter = calls.clone();
+    let handler = post(move |body: String| async move {
+        let call = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        format!("{body}:{call}")
+    });
+
+    let credentials = PeerCredentials {
+        uid: 0,
+        pid: Some(1),
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .add_service("/action", handler)
+        .build();
+
+    let request = |key: Option<&str>, body: &'static str| {
+        let mut builder = Request::builder().uri("/api/action").method(Method::POST);
+        if let Some(key) = key {
+            builder = builder.header("Idempotency-Key", key);
+        }
+        let mut request = builder.body(Body::from(body)).unwrap();
+        request.extensions_mut().insert(credentials);
+        request
+    };
+
+    let response = web_service.clone().oneshot(request(Some("1"), "a")).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_to_string(response.into_body()).await, "a:1");
+
+    // the same request is not processed again
+    let response = web_service.clone().oneshot(request(Some("1"), "a")).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Idempotent-Replayed").unwrap(),
+        "true"
+    );
+    assert_eq!(body_to_string(response.into_body()).await, "a:1");
+
+    // the same key cannot be used for a different request
+    let response = web_service.clone().oneshot(request(Some("1"), "b")).await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response = web_service.clone().oneshot(request(None, "a")).await?;
+    assert_eq!(body_to_string(response.into_body()).await, "a:2");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
+#[test]
+async fn test_request_id() -> Result<(), Box<dyn Error>> {
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir()).build();
+
+    let request = Request::builder()
+        .uri("/api/ping")
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.clone().oneshot(request).await?;
+    let request_id = response.headers().get("X-Request-Id").unwrap();
+    assert!(uuid::Uuid::parse_str(request_id.to_str()?).is_ok());
+
+    let request = Request::builder()
+        .uri("/api/ping")
+        .header("X-Request-Id", "agama-1")
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.oneshot(request).await?;
+    assert_eq!(response.headers().get("X-Request-Id").unwrap(), "agama-1");
+    Ok(())
+}
+
+#[test]
+async fn test_cors() -> Result<(), Box<dyn Error>> {
+    let config = ServiceConfig {
+        allowed_origins: vec!["https://portal.example.net".to_string()],
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .add_service("/protected", get(protected))
+        .with_config(config)
+        .build();
+
+    let request = Request::builder()
+        .uri("/api/protected")
+        .method(Method::OPTIONS)
+        .header("Origin", "https://portal.example.net")
+        .header("Access-Control-Request-Method", "GET")
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(
+        headers.get("Access-Control-Allow-Origin").unwrap(),
+        "https://portal.example.net"
+    );
+    assert_eq!(
+        headers.get("Access-Control-Allow-Credentials").unwrap(),
+        "true"
+    );
+
+    let request = Request::builder()
+        .uri("/api/ping")
+        .header("Origin", "https://evil.example.net")
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.oneshot(request).await?;
+    assert!(response
+        .headers()
+        .get("Access-Control-Allow-Origin")
+        .is_none());
+    Ok(())
+}
+
+#[test]
+async fn test_url_prefix() -> Result<(), Box<dyn Error>> {
+    let config = ServiceConfig {
+        jwt_secret: "nots3cr3t".to_string(),
+        url_prefix: Some("/installers/node1".to_string()),
+        trusted_proxies: vec!["10.0.0.0/8".parse()?],
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .with_config(config)
+        .build();
+
+    let request = Request::builder()
+        .uri("/installers/node1/api/ping")
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .uri("/api/ping")
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // the cookie is restricted to the prefix and it is secure behind an HTTPS proxy
+    let token = AuthToken::generate("nots3cr3t")?;
+    let mut request = Request::builder()
+        .uri(format!("/installers/node1/login?token={}", token))
+        .header("X-Forwarded-Proto", "https")
+        .body(Body::empty())
+        .unwrap();
+    request.extensions_mut().insert(PeerAddress {
+        addr: "10.0.0.1:80".parse()?,
+        tls: false,
+    });
+    let response = web_service.oneshot(request).await?;
+    let headers = response.headers();
+    assert_eq!(headers.get("Location").unwrap(), "/installers/node1/");
+    let cookie = headers.get("Set-Cookie").unwrap().to_str()?;
+    assert!(cookie.contains("Path=/installers/node1"));
+    assert!(cookie.contains("Secure"));
+    Ok(())
+}
+
+#[test]
+async fn test_login_from_query_cookies() -> Result<(), Box<dyn Error>> {
+    let config = ServiceConfig {
+        jwt_secret: "nots3cr3t".to_string(),
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .with_config(config)
+        .build();
+
+    let token = AuthToken::generate("nots3cr3t")?;
+    let request = Request::builder()
+        .uri(format!("/login?token={}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(
+        response.headers().get("Referrer-Policy").unwrap(),
+        "no-referrer"
+    );
+
+    let cookies: Vec<_> = response
+        .headers()
+        .get_all("Set-Cookie")
+        .iter()
+        .map(|c| c.to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(cookies.len(), 2);
+    assert!(cookies[0].starts_with(&format!("agamaToken={}; HttpOnly", token)));
+    let csrf = csrf_token(&token, "nots3cr3t")?;
+    assert!(cookies[1].starts_with(&format!("agamaCsrfToken={}; Path=/", csrf)));
+    for cookie in cookies {
+        assert!(cookie.contains("SameSite=Strict"));
+        assert!(cookie.contains("Max-Age=86"));
+        assert!(!cookie.contains("Secure"));
+    }
+    Ok(())
+}
+
+#[test]
+async fn test_csrf_token() -> Result<(), Box<dyn Error>> {
+    let config = ServiceConfig {
+        jwt_secret: "nots3cr3t".to_string(),
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let web_service = MainServiceBuilder::new(tx, public_dir())
+        .add_service("/protected", post(protected))
+        .with_config(config)
+        .build();
+
+    let token = AuthToken::generate("nots3cr3t")?;
+    let csrf = csrf_token(&token, "nots3cr3t")?;
+    let request = |cookie: String, header: Option<&str>| {
+        let mut builder = Request::builder()
+            .uri("/api/protected")
+            .method(Method::POST)
+            .header("Cookie", cookie);
+        if let Some(header) = header {
+            builder = builder.header("X-CSRF-Token", header);
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    // the CSRF token is required when using the cookie
+    let cookie = format!("agamaToken={}; agamaCsrfToken={}", token, csrf);
+    let response = web_service.clone().oneshot(request(cookie, None)).await?;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let cookie = format!("agamaToken={}; agamaCsrfToken={}", token, csrf);
+    let response = web_service
+        .clone()
+        .oneshot(request(cookie, Some("wrong")))
+        .await?;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let cookie = format!("agamaToken={}; agamaCsrfToken={}", token, csrf);
+    let response = web_service
+        .clone()
+        .oneshot(request(cookie, Some(&csrf)))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // it is not needed when using the bearer token
+    let request = Request::builder()
+        .uri("/api/protected")
+        .method(Method::POST)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = web_service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    Ok(())
+}
@@ -0,0 +1,176 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+pub mod common;
+
+use agama_server::software::web::software_service;
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use common::{
+    backends::{Backends, MockIssues, MockRegistration, PRODUCT_PATH},
+    body_to_string, DBusServer, Started,
+};
+use serde_json::{json, Value};
+use std::error::Error;
+use tokio::test;
+use tower::ServiceExt;
+
+async fn build_service() -> Result<(DBusServer<Started>, Backends, Router), Box<dyn Error>> {
+    let server = DBusServer::new().start().await?;
+    let backends = Backends::start(&server.connection()).await?;
+    let service = software_service(server.connection()).await?;
+    Ok((server, backends, service))
+}
+
+fn request(method: Method, uri: &str, body: Value) -> Request<Body> {
+    Request::builder()
+        .uri(uri)
+        .method(method)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+async fn get_json(service: &Router, uri: &str) -> Result<Value, Box<dyn Error>> {
+    let response = service
+        .clone()
+        .oneshot(request(Method::GET, uri, Value::Null))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    Ok(serde_json::from_str(&body)?)
+}
+
+#[test]
+async fn test_products() -> Result<(), Box<dyn Error>> {
+    let (_server, _backends, service) = build_service().await?;
+
+    let products = get_json(&service, "/products").await?;
+    assert_eq!(products[0]["id"], "Tumbleweed");
+    assert_eq!(products[0]["description"], "Rolling release");
+    assert_eq!(products[1]["id"], "MicroOS");
+    Ok(())
+}
+
+#[test]
+async fn test_patterns() -> Result<(), Box<dyn Error>> {
+    let (_server, _backends, service) = build_service().await?;
+
+    let patterns = get_json(&service, "/patterns").await?;
+    let gnome = patterns
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["name"] == "gnome")
+        .unwrap();
+    assert_eq!(gnome["category"], "Graphical Environments");
+    assert_eq!(gnome["order"], "1010");
+    Ok(())
+}
+
+#[test]
+async fn test_software_config() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+
+    let config = json!({ "product": "MicroOS", "patterns": { "gnome": true } });
+    let response = service
+        .clone()
+        .oneshot(request(Method::PUT, "/config", config.clone()))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        backends.calls(),
+        vec![
+            "Software1.Product.SelectProduct",
+            "Software1.SetUserPatterns"
+        ]
+    );
+
+    assert_eq!(get_json(&service, "/config").await?, config);
+
+    let proposal = get_json(&service, "/proposal").await?;
+    assert_eq!(
+        proposal,
+        json!({ "size": "4.2 GiB", "patterns": { "gnome": 0, "yast2_basis": 1 } })
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_wrong_software_config() -> Result<(), Box<dyn Error>> {
+    let (_server, _backends, service) = build_service().await?;
+
+    let config = json!({ "product": "SLES" });
+    let response = service
+        .clone()
+        .oneshot(request(Method::PUT, "/config", config))
+        .await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("Unknown product"));
+
+    let config = json!({ "patterns": { "kde": true } });
+    let response = service
+        .oneshot(request(Method::PUT, "/config", config))
+        .await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("kde"));
+    Ok(())
+}
+
+#[test]
+async fn test_registration() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+
+    let params = json!({ "key": "4BC1", "email": "jane@example.net" });
+    let response = service
+        .clone()
+        .oneshot(request(Method::POST, "/registration", params.clone()))
+        .await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(get_json(&service, "/registration").await?, params);
+
+    let registration = backends.interface::<MockRegistration>(PRODUCT_PATH).await;
+    registration.get_mut().await.result = (2, "Connection failed".to_string());
+    let response = service
+        .oneshot(request(Method::DELETE, "/registration", Value::Null))
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, r#"{"id":2,"message":"Connection failed"}"#);
+    Ok(())
+}
+
+#[test]
+async fn test_product_issues() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+    let issues = backends.interface::<MockIssues>(PRODUCT_PATH).await;
+    issues.get_mut().await.issues =
+        vec![("Product not selected yet".to_string(), String::new(), 0, 1)];
+
+    let issues = get_json(&service, "/issues/product").await?;
+    assert_eq!(issues[0]["description"], "Product not selected yet");
+    assert_eq!(get_json(&service, "/issues/software").await?, json!([]));
+    Ok(())
+}
@@ -0,0 +1,117 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+pub mod common;
+
+use agama_server::storage::web::storage_service;
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use common::{
+    backends::{Backends, MockIssues, MockStorage, STORAGE_PATH},
+    body_to_string, DBusServer, Started,
+};
+use std::error::Error;
+use tokio::test;
+use tower::ServiceExt;
+
+async fn build_service() -> Result<(DBusServer<Started>, Backends, Router), Box<dyn Error>> {
+    let server = DBusServer::new().start().await?;
+    let backends = Backends::start(&server.connection()).await?;
+    let service = storage_service(server.connection()).await?;
+    Ok((server, backends, service))
+}
+
+fn request(method: Method, uri: &str, body: &str) -> Request<Body> {
+    Request::builder()
+        .uri(uri)
+        .method(method)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[test]
+async fn test_storage_config() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+
+    let response = service
+        .clone()
+        .oneshot(request(Method::GET, "/config", ""))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, r#"{"storage":{"drives":[]}}"#);
+
+    let config = r#"{"storage":{"drives":[{"search":"/dev/vda"}]}}"#;
+    let response = service
+        .clone()
+        .oneshot(request(Method::PUT, "/config", config))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(backends.calls(), vec!["Storage1.SetConfig"]);
+
+    let storage = backends.interface::<MockStorage>(STORAGE_PATH).await;
+    assert_eq!(storage.get().await.config, config);
+    Ok(())
+}
+
+#[test]
+async fn test_probe() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+    let storage = backends.interface::<MockStorage>(STORAGE_PATH).await;
+    storage.get_mut().await.deprecated_system = true;
+
+    let response = service
+        .clone()
+        .oneshot(request(Method::GET, "/devices/dirty", ""))
+        .await?;
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, "true");
+
+    let response = service.oneshot(request(Method::POST, "/probe", "")).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(backends.calls(), vec!["Storage1.Probe"]);
+    assert!(!storage.get().await.deprecated_system);
+    Ok(())
+}
+
+#[test]
+async fn test_storage_issues() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+    let issues = backends.interface::<MockIssues>(STORAGE_PATH).await;
+    issues.get_mut().await.issues = vec![(
+        "Cannot accommodate the required file systems".to_string(),
+        "Not enough space".to_string(),
+        1,
+        1,
+    )];
+
+    let response = service.oneshot(request(Method::GET, "/issues", "")).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(
+        body,
+        r#"[{"description":"Cannot accommodate the required file systems","details":"Not enough space","source":1,"severity":1}]"#
+    );
+    Ok(())
+}
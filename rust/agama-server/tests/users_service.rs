@@ -0,0 +1,144 @@
+// Copyright (c) [2025] SUSE LLC
+//
+// All Rights Reserved.
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the Free
+// Software Foundation; either version 2 of the License, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License for
+// more details.
+//
+// You should have received a copy of the GNU General Public License along
+// with this program; if not, contact SUSE LLC.
+//
+// To contact SUSE LLC about this file by physical or electronic mail, you may
+// find current contact information at www.suse.com.
+
+pub mod common;
+
+use agama_server::users::web::users_service;
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use common::{
+    backends::{Backends, MockIssues, MockUsers, USERS_PATH},
+    body_to_string, DBusServer, Started,
+};
+use std::error::Error;
+use tokio::test;
+use tower::ServiceExt;
+
+async fn build_service() -> Result<(DBusServer<Started>, Backends, Router), Box<dyn Error>> {
+    let server = DBusServer::new().start().await?;
+    let backends = Backends::start(&server.connection()).await?;
+    let service = users_service(server.connection()).await?;
+    Ok((server, backends, service))
+}
+
+fn request(method: Method, uri: &str, body: &str) -> Request<Body> {
+    Request::builder()
+        .uri(uri)
+        .method(method)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[test]
+async fn test_first_user() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+
+    let user = r#"{"fullName":"Jane Doe","userName":"jane","password":"n0ts3cr3t","hashedPassword":false,"autologin":true}"#;
+    let response = service
+        .clone()
+        .oneshot(request(Method::PUT, "/first", user))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = service
+        .clone()
+        .oneshot(request(Method::GET, "/first", ""))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, user);
+
+    let response = service
+        .oneshot(request(Method::DELETE, "/first", ""))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        backends.calls(),
+        vec!["Users1.SetFirstUser", "Users1.RemoveFirstUser"]
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_invalid_first_user() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+    let users = backends.interface::<MockUsers>(USERS_PATH).await;
+    users.get_mut().await.first_user_issues = vec!["Password is empty".to_string()];
+
+    let user = r#"{"fullName":"","userName":"jane","password":"","hashedPassword":false,"autologin":false}"#;
+    let response = service
+        .oneshot(request(Method::PUT, "/first", user))
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, r#"["Password is empty"]"#);
+    Ok(())
+}
+
+#[test]
+async fn test_root_config() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+
+    let response = service
+        .clone()
+        .oneshot(request(
+            Method::PATCH,
+            "/root",
+            r#"{"password":"n0ts3cr3t","sshkey":"ssh-ed25519 AAAA"}"#,
+        ))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, "0");
+
+    let response = service.oneshot(request(Method::GET, "/root", "")).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, r#"{"password":true,"sshkey":"ssh-ed25519 AAAA"}"#);
+    assert_eq!(
+        backends.calls(),
+        vec!["Users1.SetRootSSHKey", "Users1.SetRootPassword"]
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_users_issues() -> Result<(), Box<dyn Error>> {
+    let (_server, backends, service) = build_service().await?;
+    let issues = backends.interface::<MockIssues>(USERS_PATH).await;
+    issues.get_mut().await.issues = vec![(
+        "Missing root authentication".to_string(),
+        String::new(),
+        0,
+        1,
+    )];
+
+    let response = service.oneshot(request(Method::GET, "/issues", "")).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(
+        body,
+        r#"[{"description":"Missing root authentication","details":null,"source":0,"severity":1}]"#
+    );
+    Ok(())
+}
@@ -5,9 +5,9 @@ mod tasks {
 
     use agama_cli::Cli;
     use agama_server::web::docs::{
-        ApiDocBuilder, L10nApiDocBuilder, ManagerApiDocBuilder, MiscApiDocBuilder,
-        NetworkApiDocBuilder, QuestionsApiDocBuilder, ScriptsApiDocBuilder, SoftwareApiDocBuilder,
-        StorageApiDocBuilder, UsersApiDocBuilder,
+        ApiDocBuilder, BootloaderApiDocBuilder, L10nApiDocBuilder, ManagerApiDocBuilder,
+        MiscApiDocBuilder, NetworkApiDocBuilder, QuestionsApiDocBuilder, ScriptsApiDocBuilder,
+        SoftwareApiDocBuilder, StorageApiDocBuilder, UsersApiDocBuilder,
     };
     use clap::CommandFactory;
     use clap_complete::aot;
@@ -60,6 +60,7 @@ mod tasks {
     pub fn generate_openapi() -> std::io::Result<()> {
         let out_dir = create_output_dir("openapi")?;
 
+        write_openapi(BootloaderApiDocBuilder {}, out_dir.join("bootloader.json"))?;
         write_openapi(L10nApiDocBuilder {}, out_dir.join("l10n.json"))?;
         write_openapi(ManagerApiDocBuilder {}, out_dir.join("manager.json"))?;
         write_openapi(MiscApiDocBuilder {}, out_dir.join("misc.json"))?;
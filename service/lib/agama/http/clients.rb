@@ -27,4 +27,5 @@ module Agama
   end
 end
 
+require "agama/http/clients/network"
 require "agama/http/clients/scripts"
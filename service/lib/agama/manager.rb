@@ -130,6 +130,8 @@ module Agama
 
       progress.step { software.install }
       progress.step do
+        # the product might define the network backend of the installed system
+        config.pick_product(software.selected_product) if software.selected_product
         on_target do
           users.write
           network.install
@@ -199,7 +201,7 @@ module Agama
     #
     # @return [Network]
     def network
-      @network ||= Network.new(logger)
+      @network ||= Network.new(logger, config)
     end
 
     # Storage manager
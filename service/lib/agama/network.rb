@@ -23,6 +23,7 @@ require "singleton"
 require "yast"
 require "yast2/systemd/service"
 require "y2network/proposal_settings"
+require "agama/http/clients/network"
 require "agama/proxy_setup"
 
 Yast.import "Installation"
@@ -30,17 +31,27 @@ Yast.import "Installation"
 module Agama
   # Backend class to handle network configuration
   class Network
-    def initialize(logger)
+    # @param logger [Logger]
+    # @param config [Config, nil] configuration
+    def initialize(logger, config = nil)
       @logger = logger
+      @config = config
     end
 
     # Writes the network configuration to the installed system
     #
-    # * Copies the connections configuration for NetworkManager, as Agama is not
-    #   performing further configuration of the network.
-    # * Enables the NetworkManager service.
+    # * If the product defines a network backend (see {#backend}), the configuration files are
+    #   written by the network service. Otherwise, the connections configuration for
+    #   NetworkManager, the global DNS configuration and the static host name are copied, as Agama
+    #   is not performing further configuration of the network.
+    # * Enables the network service.
     def install
-      copy_files
+      if backend
+        write_files
+      else
+        copy_files
+        copy_file(HOSTNAME_PATH)
+      end
       enable_service
 
       ProxySetup.instance.install
@@ -51,20 +62,52 @@ module Agama
     # @return [Logger]
     attr_reader :logger
 
+    # @return [Config, nil]
+    attr_reader :config
+
+    SERVICES = {
+      "keyfile"  => "NetworkManager",
+      "networkd" => "systemd-networkd"
+    }.freeze
+    private_constant :SERVICES
+
+    # Network backend of the installed system
+    #
+    # @return [String, nil] "keyfile", "networkd" or nil if it is not defined
+    def backend
+      config&.data&.dig("network", "backend")
+    end
+
     ETC_NM_DIR = "/etc/NetworkManager"
     RUN_NM_DIR = "/run/NetworkManager"
     private_constant :ETC_NM_DIR
 
+    # Global DNS configuration written by the network service
+    DNS_CONF = "conf.d/90-agama-dns.conf"
+    private_constant :DNS_CONF
+
+    # Static host name, which is only written when it is set (the installer uses a transient one)
+    HOSTNAME_PATH = "/etc/hostname"
+
     def enable_service
-      service = Yast2::Systemd::Service.find("NetworkManager")
+      name = SERVICES.fetch(backend, "NetworkManager")
+      service = Yast2::Systemd::Service.find(name)
       if service.nil?
-        logger.error "NetworkManager service was not found"
+        logger.error "#{name} service was not found"
         return
       end
 
       service.enable
     end
 
+    # Writes the configuration files for the network backend into the installed system
+    def write_files
+      response = HTTP::Clients::Network.new.install(Yast::Installation.destdir, backend)
+      return if response.is_a?(Net::HTTPSuccess)
+
+      logger.error "Could not write the network configuration: #{response.body}"
+    end
+
     # Copies NetworkManager configuration files
     def copy_files
       return unless Dir.exist?(ETC_NM_DIR)
@@ -80,6 +123,19 @@ module Agama
         File.join(ETC_NM_DIR, "system-connections"),
         File.join(Yast::Installation.destdir, ETC_NM_DIR, "system-connections")
       )
+
+      copy_file(File.join(ETC_NM_DIR, DNS_CONF))
+    end
+
+    # Copies a file to the same location in the target system
+    #
+    # @param path [String] file to copy
+    def copy_file(path)
+      return unless File.exist?(path)
+
+      target = File.join(Yast::Installation.destdir, path)
+      FileUtils.mkdir_p(File.dirname(target))
+      FileUtils.cp(path, target)
     end
 
     # Copies a directory
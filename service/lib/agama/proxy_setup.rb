@@ -60,10 +60,16 @@ module Agama
     end
 
     def propose
+      Proxy.Read
       add_packages if Proxy.enabled
     end
 
+    # Copies the proxy configuration to the target system
+    #
+    # The configuration is read again, as it might have been changed through the network
+    # service (see the /network/proxy endpoint).
     def install
+      Proxy.Read
       return unless Proxy.enabled
 
       on_local { copy_files }
@@ -21,13 +21,16 @@
 
 require_relative "../test_helper"
 require "tmpdir"
+require "agama/config"
 require "agama/network"
 require "agama/progress"
 
 describe Agama::Network do
-  subject(:network) { described_class.new(logger) }
+  subject(:network) { described_class.new(logger, config) }
 
   let(:logger) { Logger.new($stdout, level: :warn) }
+  let(:config) { Agama::Config.new(config_data) }
+  let(:config_data) { {} }
 
   describe "#install" do
     let(:rootdir) { Dir.mktmpdir }
@@ -41,6 +44,7 @@ describe Agama::Network do
       allow(Yast::Installation).to receive(:destdir).and_return(targetdir)
       allow(Yast2::Systemd::Service).to receive(:find).with("NetworkManager").and_return(service)
       stub_const("Agama::Network::ETC_NM_DIR", etcdir)
+      stub_const("Agama::Network::HOSTNAME_PATH", File.join(rootdir, "etc", "hostname"))
     end
 
     after do
@@ -61,6 +65,20 @@ describe Agama::Network do
       end
     end
 
+    context "when the global DNS configuration and the host name are set" do
+      before do
+        FileUtils.mkdir_p(File.join(etcdir, "conf.d"))
+        FileUtils.touch(File.join(etcdir, "conf.d", "90-agama-dns.conf"))
+        File.write(File.join(rootdir, "etc", "hostname"), "server\n")
+      end
+
+      it "copies them to the target system" do
+        network.install
+        expect(File).to exist(File.join(targetdir, etcdir, "conf.d", "90-agama-dns.conf"))
+        expect(File.read(File.join(targetdir, rootdir, "etc", "hostname"))).to eq("server\n")
+      end
+    end
+
     context "when NetworkManager configuration files are not available" do
       it "does not try to copy any file" do
         expect(FileUtils).to_not receive(:cp_r)
@@ -94,5 +112,47 @@ describe Agama::Network do
         network.install
       end
     end
+
+    context "when the product defines a network backend" do
+      let(:config_data) { { "network" => { "backend" => "networkd" } } }
+      let(:client) { instance_double(Agama::HTTP::Clients::Network, install: response) }
+      let(:response) { Net::HTTPNoContent.new("1.1", "204", "No Content") }
+
+      before do
+        allow(Agama::HTTP::Clients::Network).to receive(:new).and_return(client)
+        allow(Yast2::Systemd::Service).to receive(:find).with("systemd-networkd")
+          .and_return(service)
+        FileUtils.mkdir_p(File.join(etcdir, "system-connections"))
+        FileUtils.touch(File.join(etcdir, "system-connections", "wired.nmconnection"))
+      end
+
+      it "writes the configuration files for the backend" do
+        expect(client).to receive(:install).with(targetdir, "networkd")
+        network.install
+      end
+
+      it "does not copy the NetworkManager configuration files" do
+        network.install
+        expect(Dir).to_not exist(File.join(targetdir, etcdir))
+      end
+
+      it "enables the service for the backend" do
+        expect(service).to receive(:enable)
+        network.install
+      end
+
+      context "and the configuration could not be written" do
+        let(:response) { Net::HTTPBadRequest.new("1.1", "400", "Bad Request") }
+
+        before do
+          allow(response).to receive(:body).and_return("error")
+        end
+
+        it "logs an error" do
+          expect(logger).to receive(:error).with(/Could not write/)
+          network.install
+        end
+      end
+    end
   end
 end
@@ -138,6 +138,12 @@ describe Agama::ProxySetup do
           File.join("/mnt", described_class::CONFIG_PATH))
         proxy.install
       end
+
+      it "reads the configuration again before copying it" do
+        allow(FileUtils).to receive(:cp)
+        expect(Yast::Proxy).to receive(:Read)
+        proxy.install
+      end
     end
   end
 end
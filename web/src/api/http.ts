@@ -22,10 +22,32 @@
 
 import axios from "axios";
 
+const CSRF_COOKIE = "agamaCsrfToken";
+const CSRF_HEADER = "X-CSRF-Token";
+
+/**
+ * The server requires the CSRF token (taken from its cookie) for the requests changing the state
+ * (double-submit). axios sends it automatically.
+ */
 const http = axios.create({
   responseType: "json",
+  xsrfCookieName: CSRF_COOKIE,
+  xsrfHeaderName: CSRF_HEADER,
 });
 
+/**
+ * Returns the header containing the CSRF token, to be used in requests not made through axios
+ *
+ * @return headers object (empty if there is no CSRF token)
+ */
+const csrfHeaders = (): Record<string, string> => {
+  const value = document.cookie.replace(
+    new RegExp(`(?:(?:^|.*;\\s*)${CSRF_COOKIE}\\s*=\\s*([^;]*).*$)|^.*$`),
+    "$1",
+  );
+  return value ? { [CSRF_HEADER]: value } : {};
+};
+
 /**
  * Retrieves the object from given URL
  *
@@ -66,4 +88,4 @@ const post = (url: string, data?: object) => http.post(url, data);
  */
 const del = (url: string) => http.delete(url);
 
-export { get, patch, post, put, del };
+export { get, patch, post, put, del, csrfHeaders };
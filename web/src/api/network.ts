@@ -71,8 +71,26 @@ const deleteConnection = (name: string) => del(`/api/network/connections/${name}
 
 /**
  * Apply network changes
+ *
+ * @param confirmTimeout - seconds to confirm the changes (see confirmChanges). If they are not
+ *   confirmed in time, the previous configuration is restored.
+ */
+const applyChanges = (confirmTimeout?: number) =>
+  post(
+    confirmTimeout
+      ? `/api/network/system/apply?confirmTimeout=${confirmTimeout}`
+      : "/api/network/system/apply",
+  );
+
+/**
+ * Confirm the network changes applied with a confirmation timeout
+ */
+const confirmChanges = () => post("/api/network/system/confirm");
+
+/**
+ * Restore the network configuration previous to the changes waiting for confirmation
  */
-const applyChanges = () => post("/api/network/system/apply");
+const rollbackChanges = () => post("/api/network/system/rollback");
 
 /**
  * Performs the connect action for connection matching given name
@@ -91,6 +109,8 @@ export {
   fetchConnections,
   fetchAccessPoints,
   applyChanges,
+  confirmChanges,
+  rollbackChanges,
   addConnection,
   updateConnection,
   deleteConnection,
@@ -21,6 +21,7 @@
  */
 
 import React, { useCallback, useEffect, useState } from "react";
+import { csrfHeaders } from "~/api/http";
 
 const AuthContext = React.createContext(null);
 
@@ -72,6 +73,7 @@ function AuthProvider({ children }: React.PropsWithChildren) {
   const logout = useCallback(async () => {
     await fetch("/api/auth", {
       method: "DELETE",
+      headers: csrfHeaders(),
     });
     setIsLoggedIn(false);
   }, []);
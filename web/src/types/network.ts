@@ -181,6 +181,8 @@ class Device {
   routes4?: Route[];
   routes6?: Route[];
   macAddress: string;
+  driver?: string;
+  path?: string;
   state: DeviceState;
   connection?: string;
 
@@ -217,6 +219,8 @@ type APIDevice = {
   name: string;
   type: ConnectionType;
   macAddress: string;
+  driver?: string;
+  path?: string;
   state: DeviceState;
   connection?: string;
   ipConfig?: IPConfig;
@@ -350,6 +354,12 @@ type NetworkGeneralState = {
   hostname: string;
   networking_enabled: boolean;
   wireless_enabled: boolean;
+  transient_hostname?: string;
+  dns?: {
+    nameservers: string[];
+    searchlist: string[];
+    mode?: "default" | "dnsmasq" | "systemd-resolved" | "none";
+  };
 };
 
 export {